@@ -0,0 +1,445 @@
+//! Allocator support for the storage of `IndexMap` and `IndexSet`.
+//!
+//! With the `allocator-api2` feature, this re-exports the `Allocator` trait and the
+//! allocator-aware `Vec` from that crate, and uses hashbrown's `RawTable` with the
+//! same allocator. Otherwise, this provides sealed stand-ins that only support the
+//! `Global` allocator, wrapping the standard `Vec` and the default `RawTable`, so
+//! the rest of the crate can be written generically either way.
+
+#[cfg(feature = "allocator-api2")]
+mod inner {
+    pub use allocator_api2::alloc::{Allocator, Global};
+    pub(crate) use allocator_api2::boxed::Box;
+    pub(crate) use allocator_api2::collections::TryReserveError;
+    pub(crate) use allocator_api2::vec::{Drain, IntoIter, Vec};
+    pub(crate) use hashbrown::raw::RawTable;
+
+    // SAFETY: `Global` allocates from the global allocator just like the standard
+    // `Vec`, so the same raw parts are valid for either `Vec` with the same `T`.
+    #[allow(unsafe_code)]
+    mod convert {
+        use super::{Global, Vec};
+        use alloc::vec;
+
+        /// Converts our `Vec` into a standard `Vec`, without copying.
+        pub(crate) fn into_std_vec<T>(vec: Vec<T, Global>) -> vec::Vec<T> {
+            let (ptr, len, capacity) = vec.into_raw_parts();
+            unsafe { vec::Vec::from_raw_parts(ptr, len, capacity) }
+        }
+
+        /// Converts a standard `Vec` into our `Vec`, without copying.
+        pub(crate) fn from_std_vec<T>(vec: vec::Vec<T>) -> Vec<T, Global> {
+            let mut vec = core::mem::ManuallyDrop::new(vec);
+            let (ptr, len, capacity) = (vec.as_mut_ptr(), vec.len(), vec.capacity());
+            unsafe { Vec::from_raw_parts(ptr, len, capacity) }
+        }
+    }
+
+    pub(crate) use self::convert::{from_std_vec, into_std_vec};
+
+    /// The parallel draining iterator of `par_drain`.
+    ///
+    /// This owns the drained items, but keeps the borrow of the `Vec` they came from.
+    #[cfg(feature = "rayon")]
+    pub(crate) struct ParDrain<'a, T: Send> {
+        iter: rayon::vec::IntoIter<T>,
+        marker: core::marker::PhantomData<&'a mut [T]>,
+    }
+
+    /// Drains a range of `vec` in parallel.
+    ///
+    /// `rayon` can only drain a standard `Vec` in place, so this collects the range first.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn par_drain<T: Send, A: Allocator>(
+        vec: &mut Vec<T, A>,
+        range: core::ops::Range<usize>,
+    ) -> ParDrain<'_, T> {
+        use rayon::iter::IntoParallelIterator;
+        ParDrain {
+            iter: vec
+                .drain(range)
+                .collect::<alloc::vec::Vec<T>>()
+                .into_par_iter(),
+            marker: core::marker::PhantomData,
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    impl<T: Send> rayon::iter::ParallelIterator for ParDrain<'_, T> {
+        type Item = T;
+
+        fn drive_unindexed<C>(self, consumer: C) -> C::Result
+        where
+            C: rayon::iter::plumbing::UnindexedConsumer<T>,
+        {
+            self.iter.drive_unindexed(consumer)
+        }
+
+        fn opt_len(&self) -> Option<usize> {
+            self.iter.opt_len()
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    impl<T: Send> rayon::iter::IndexedParallelIterator for ParDrain<'_, T> {
+        fn drive<C>(self, consumer: C) -> C::Result
+        where
+            C: rayon::iter::plumbing::Consumer<T>,
+        {
+            self.iter.drive(consumer)
+        }
+
+        fn len(&self) -> usize {
+            rayon::iter::IndexedParallelIterator::len(&self.iter)
+        }
+
+        fn with_producer<CB>(self, callback: CB) -> CB::Output
+        where
+            CB: rayon::iter::plumbing::ProducerCallback<T>,
+        {
+            self.iter.with_producer(callback)
+        }
+    }
+}
+
+#[cfg(not(feature = "allocator-api2"))]
+mod inner {
+    use alloc::vec;
+    use core::fmt;
+    use core::iter::FusedIterator;
+    use core::marker::PhantomData;
+    use core::ops::{Deref, DerefMut, RangeBounds};
+
+    pub(crate) use alloc::collections::TryReserveError;
+
+    /// A memory allocator for the storage of the collections.
+    ///
+    /// Without the `allocator-api2` feature, this is sealed and only implemented
+    /// by the [`Global`] allocator.
+    pub trait Allocator: Sealed {}
+
+    pub trait Sealed {}
+
+    /// The global memory allocator.
+    #[derive(Copy, Clone, Default, Debug)]
+    pub struct Global;
+
+    impl Sealed for Global {}
+    impl Allocator for Global {}
+
+    /// A standard `Vec` that also carries its allocator type.
+    pub(crate) struct Vec<T, A> {
+        vec: vec::Vec<T>,
+        alloc: A,
+    }
+
+    impl<T> Vec<T, Global> {
+        #[inline]
+        pub(crate) const fn new() -> Self {
+            Vec {
+                vec: vec::Vec::new(),
+                alloc: Global,
+            }
+        }
+    }
+
+    impl<T, A: Allocator> Vec<T, A> {
+        #[inline]
+        pub(crate) fn new_in(alloc: A) -> Self {
+            Vec {
+                vec: vec::Vec::new(),
+                alloc,
+            }
+        }
+
+        #[inline]
+        pub(crate) fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+            Vec {
+                vec: vec::Vec::with_capacity(capacity),
+                alloc,
+            }
+        }
+
+        #[inline]
+        pub(crate) fn allocator(&self) -> &A {
+            &self.alloc
+        }
+
+        #[inline]
+        pub(crate) fn append(&mut self, other: &mut Self) {
+            self.vec.append(&mut other.vec);
+        }
+
+        #[inline]
+        pub(crate) fn drain<R>(&mut self, range: R) -> Drain<'_, T, A>
+        where
+            R: RangeBounds<usize>,
+        {
+            Drain {
+                iter: self.vec.drain(range),
+                alloc: PhantomData,
+            }
+        }
+
+        #[inline]
+        pub(crate) fn split_off(&mut self, at: usize) -> Self
+        where
+            A: Clone,
+        {
+            Vec {
+                vec: self.vec.split_off(at),
+                alloc: self.alloc.clone(),
+            }
+        }
+    }
+
+    impl<T, A> Deref for Vec<T, A> {
+        type Target = vec::Vec<T>;
+
+        #[inline]
+        fn deref(&self) -> &Self::Target {
+            &self.vec
+        }
+    }
+
+    impl<T, A> DerefMut for Vec<T, A> {
+        #[inline]
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.vec
+        }
+    }
+
+    impl<T: Clone, A: Clone> Clone for Vec<T, A> {
+        fn clone(&self) -> Self {
+            Vec {
+                vec: self.vec.clone(),
+                alloc: self.alloc.clone(),
+            }
+        }
+
+        fn clone_from(&mut self, other: &Self) {
+            self.vec.clone_from(&other.vec);
+        }
+    }
+
+    impl<T: fmt::Debug, A> fmt::Debug for Vec<T, A> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Debug::fmt(&self.vec, f)
+        }
+    }
+
+    impl<T, A> IntoIterator for Vec<T, A> {
+        type Item = T;
+        type IntoIter = IntoIter<T, A>;
+
+        #[inline]
+        fn into_iter(self) -> Self::IntoIter {
+            IntoIter {
+                iter: self.vec.into_iter(),
+                alloc: PhantomData,
+            }
+        }
+    }
+
+    impl<T, A> Extend<T> for Vec<T, A> {
+        #[inline]
+        fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+            self.vec.extend(iterable);
+        }
+    }
+
+    /// A standard `vec::IntoIter` that also carries its allocator type.
+    pub(crate) struct IntoIter<T, A> {
+        iter: vec::IntoIter<T>,
+        alloc: PhantomData<A>,
+    }
+
+    /// A standard `vec::Drain` that also carries its allocator type.
+    pub(crate) struct Drain<'a, T, A> {
+        iter: vec::Drain<'a, T>,
+        alloc: PhantomData<A>,
+    }
+
+    impl<T, A> IntoIter<T, A> {
+        #[inline]
+        pub(crate) fn as_slice(&self) -> &[T] {
+            self.iter.as_slice()
+        }
+
+        #[inline]
+        pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
+            self.iter.as_mut_slice()
+        }
+    }
+
+    impl<T, A> Drain<'_, T, A> {
+        #[inline]
+        pub(crate) fn as_slice(&self) -> &[T] {
+            self.iter.as_slice()
+        }
+    }
+
+    impl<T: Clone, A> Clone for IntoIter<T, A> {
+        fn clone(&self) -> Self {
+            IntoIter {
+                iter: self.iter.clone(),
+                alloc: PhantomData,
+            }
+        }
+    }
+
+    macro_rules! forward_iterator {
+        ($($ty:ty),*) => {$(
+            impl<T, A> Iterator for $ty {
+                type Item = T;
+
+                #[inline]
+                fn next(&mut self) -> Option<T> {
+                    self.iter.next()
+                }
+
+                #[inline]
+                fn size_hint(&self) -> (usize, Option<usize>) {
+                    self.iter.size_hint()
+                }
+
+                #[inline]
+                fn count(self) -> usize {
+                    self.iter.len()
+                }
+
+                #[inline]
+                fn nth(&mut self, n: usize) -> Option<T> {
+                    self.iter.nth(n)
+                }
+
+                #[inline]
+                fn last(mut self) -> Option<T> {
+                    self.iter.next_back()
+                }
+            }
+
+            impl<T, A> DoubleEndedIterator for $ty {
+                #[inline]
+                fn next_back(&mut self) -> Option<T> {
+                    self.iter.next_back()
+                }
+
+                #[inline]
+                fn nth_back(&mut self, n: usize) -> Option<T> {
+                    self.iter.nth_back(n)
+                }
+            }
+
+            impl<T, A> ExactSizeIterator for $ty {
+                #[inline]
+                fn len(&self) -> usize {
+                    self.iter.len()
+                }
+            }
+
+            impl<T, A> FusedIterator for $ty {}
+
+            impl<T: fmt::Debug, A> fmt::Debug for $ty {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Debug::fmt(&self.iter, f)
+                }
+            }
+        )*}
+    }
+
+    forward_iterator!(IntoIter<T, A>, Drain<'_, T, A>);
+
+    /// The default `RawTable` that also carries an allocator type.
+    pub(crate) struct RawTable<T, A> {
+        table: hashbrown::raw::RawTable<T>,
+        alloc: PhantomData<A>,
+    }
+
+    impl<T> RawTable<T, Global> {
+        #[inline]
+        pub(crate) const fn new() -> Self {
+            RawTable {
+                table: hashbrown::raw::RawTable::new(),
+                alloc: PhantomData,
+            }
+        }
+    }
+
+    impl<T, A: Allocator> RawTable<T, A> {
+        #[inline]
+        pub(crate) fn new_in(_alloc: A) -> Self {
+            RawTable {
+                table: hashbrown::raw::RawTable::new(),
+                alloc: PhantomData,
+            }
+        }
+
+        #[inline]
+        pub(crate) fn with_capacity_in(capacity: usize, _alloc: A) -> Self {
+            RawTable {
+                table: hashbrown::raw::RawTable::with_capacity(capacity),
+                alloc: PhantomData,
+            }
+        }
+    }
+
+    impl<T, A> Deref for RawTable<T, A> {
+        type Target = hashbrown::raw::RawTable<T>;
+
+        #[inline]
+        fn deref(&self) -> &Self::Target {
+            &self.table
+        }
+    }
+
+    impl<T, A> DerefMut for RawTable<T, A> {
+        #[inline]
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.table
+        }
+    }
+
+    impl<T: Clone, A> Clone for RawTable<T, A> {
+        fn clone(&self) -> Self {
+            RawTable {
+                table: self.table.clone(),
+                alloc: PhantomData,
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_std_vec<T>(vec: Vec<T, Global>) -> vec::Vec<T> {
+        vec.vec
+    }
+
+    #[inline]
+    pub(crate) fn from_std_vec<T>(vec: vec::Vec<T>) -> Vec<T, Global> {
+        Vec { vec, alloc: Global }
+    }
+
+    #[cfg(feature = "rayon")]
+    pub(crate) type ParDrain<'a, T> = rayon::vec::Drain<'a, T>;
+
+    #[cfg(feature = "rayon")]
+    #[inline]
+    pub(crate) fn par_drain<T: Send, A>(
+        vec: &mut Vec<T, A>,
+        range: core::ops::Range<usize>,
+    ) -> ParDrain<'_, T> {
+        use rayon::iter::ParallelDrainRange;
+        vec.vec.par_drain(range)
+    }
+}
+
+pub(crate) use self::inner::{
+    from_std_vec, into_std_vec, Drain, IntoIter, RawTable, TryReserveError, Vec,
+};
+pub use self::inner::{Allocator, Global};
+
+#[cfg(feature = "allocator-api2")]
+pub(crate) use self::inner::Box;
+
+#[cfg(feature = "rayon")]
+pub(crate) use self::inner::{par_drain, ParDrain};
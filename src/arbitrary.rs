@@ -1,15 +1,16 @@
 #[cfg(feature = "arbitrary")]
 #[cfg_attr(docsrs, doc(cfg(feature = "arbitrary")))]
 mod impl_arbitrary {
-    use crate::{IndexMap, IndexSet};
+    use crate::{IndexMap, IndexSet, IndexType};
     use arbitrary::{Arbitrary, Result, Unstructured};
     use core::hash::{BuildHasher, Hash};
 
-    impl<'a, K, V, S> Arbitrary<'a> for IndexMap<K, V, S>
+    impl<'a, K, V, S, Idx> Arbitrary<'a> for IndexMap<K, V, S, Idx>
     where
         K: Arbitrary<'a> + Hash + Eq,
         V: Arbitrary<'a>,
         S: BuildHasher + Default,
+        Idx: IndexType,
     {
         fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
             u.arbitrary_iter()?.collect()
@@ -20,10 +21,11 @@ mod impl_arbitrary {
         }
     }
 
-    impl<'a, T, S> Arbitrary<'a> for IndexSet<T, S>
+    impl<'a, T, S, Idx> Arbitrary<'a> for IndexSet<T, S, Idx>
     where
         T: Arbitrary<'a> + Hash + Eq,
         S: BuildHasher + Default,
+        Idx: IndexType,
     {
         fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
             u.arbitrary_iter()?.collect()
@@ -38,17 +40,18 @@ mod impl_arbitrary {
 #[cfg(feature = "quickcheck")]
 #[cfg_attr(docsrs, doc(cfg(feature = "quickcheck")))]
 mod impl_quickcheck {
-    use crate::{IndexMap, IndexSet};
+    use crate::{IndexMap, IndexSet, IndexType};
     use alloc::boxed::Box;
     use alloc::vec::Vec;
     use core::hash::{BuildHasher, Hash};
     use quickcheck::{Arbitrary, Gen};
 
-    impl<K, V, S> Arbitrary for IndexMap<K, V, S>
+    impl<K, V, S, Idx> Arbitrary for IndexMap<K, V, S, Idx>
     where
         K: Arbitrary + Hash + Eq,
         V: Arbitrary,
         S: BuildHasher + Default + Clone + 'static,
+        Idx: IndexType,
     {
         fn arbitrary(g: &mut Gen) -> Self {
             Self::from_iter(Vec::arbitrary(g))
@@ -60,10 +63,11 @@ mod impl_quickcheck {
         }
     }
 
-    impl<T, S> Arbitrary for IndexSet<T, S>
+    impl<T, S, Idx> Arbitrary for IndexSet<T, S, Idx>
     where
         T: Arbitrary + Hash + Eq,
         S: BuildHasher + Default + Clone + 'static,
+        Idx: IndexType,
     {
         fn arbitrary(g: &mut Gen) -> Self {
             Self::from_iter(Vec::arbitrary(g))
@@ -0,0 +1,73 @@
+use core::fmt;
+use core::hash::Hash;
+
+/// The integer type used by [`IndexMap`][crate::IndexMap] and [`IndexSet`][crate::IndexSet]
+/// to store entry indices in their hash table.
+///
+/// The default is `usize`, which can index any number of entries that fits in memory.
+/// A smaller type reduces the memory used by the hash table, which stores one index
+/// per bucket, at the cost of limiting the number of entries to [`MAX_LEN`][Self::MAX_LEN].
+///
+/// Only the indices in the hash table use this type. Every entry still keeps the
+/// full `usize` hash value of its key, so the table can be rehashed when it grows
+/// without hashing the keys again, and so that hashbrown gets all the hash bits it
+/// uses to probe the table.
+///
+/// Inserting beyond that limit will panic, just like exceeding the capacity of a `Vec`,
+/// while [`try_reserve`][crate::IndexMap::try_reserve] and its variants report it
+/// as a capacity overflow in their [`TryReserveError`][crate::TryReserveError].
+///
+/// ```
+/// use indexmap::IndexMap;
+/// use std::collections::hash_map::RandomState;
+///
+/// let mut map: IndexMap<u8, (), RandomState, u8> = IndexMap::default();
+/// map.extend((0..=254).map(|i| (i, ())));
+/// assert_eq!(map.len(), 255);
+/// assert!(map.try_reserve(1).is_err());
+/// ```
+///
+/// This trait is sealed and cannot be implemented for types outside this crate.
+/// It is implemented for `u8`, `u16`, `u32`, and `usize`.
+pub trait IndexType:
+    Copy + Eq + Ord + Hash + fmt::Debug + Send + Sync + 'static + private::Sealed
+{
+    /// The maximum number of entries that can be indexed by this type.
+    ///
+    /// This is the maximum value of the type, which is itself reserved for internal use,
+    /// so the entries are indexed by the values below it.
+    const MAX_LEN: usize;
+}
+
+macro_rules! impl_index_type {
+    ($($ty:ident),*) => {$(
+        impl IndexType for $ty {
+            const MAX_LEN: usize = $ty::MAX as usize;
+        }
+
+        impl private::Sealed for $ty {
+            #[inline(always)]
+            fn from_usize(i: usize) -> Self {
+                debug_assert!(i <= $ty::MAX as usize);
+                i as $ty
+            }
+
+            #[inline(always)]
+            fn into_usize(self) -> usize {
+                self as usize
+            }
+        }
+    )*}
+}
+
+impl_index_type!(u8, u16, u32, usize);
+
+pub(crate) mod private {
+    pub trait Sealed {
+        /// Convert from an index that must be in range for this type.
+        fn from_usize(i: usize) -> Self;
+
+        /// Convert back to an index.
+        fn into_usize(self) -> usize;
+    }
+}
@@ -34,11 +34,22 @@
 //! * `serde`: Adds implementations for [`Serialize`] and [`Deserialize`]
 //!   to [`IndexMap`] and [`IndexSet`]. Alternative implementations for
 //!   (de)serializing [`IndexMap`] as an ordered sequence are available in the
-//!   [`map::serde_seq`] module.
+//!   [`map::serde_seq`] module, and for rejecting duplicate keys and values in
+//!   the [`map::serde_strict`] and [`set::serde_strict`] modules. The
+//!   [`map::serde_hashed`] module also stores the hash values of the keys,
+//!   so deserializing with the same hasher doesn't need to rehash them.
+//!   Slices are serialized as ordered sequences, or as maps with the
+//!   [`map::serde_slice`] module.
 //! * `arbitrary`: Adds implementations for the [`arbitrary::Arbitrary`] trait
 //!   to [`IndexMap`] and [`IndexSet`].
 //! * `quickcheck`: Adds implementations for the [`quickcheck::Arbitrary`] trait
 //!   to [`IndexMap`] and [`IndexSet`].
+//! * `allocator-api2`: Allows [`IndexMap`] and [`IndexSet`] to use a custom
+//!   allocator for their storage, using the `Allocator` trait from the
+//!   [`allocator-api2`] crate, through methods like `IndexMap::new_in`.
+//!   Conversions that hand the entries to a standard `Box` or `Vec`, like
+//!   `IndexMap::into_boxed_slice` and the owning parallel iterators, are only
+//!   implemented for the `Global` allocator, so they never need to copy.
 //!
 //! _Note: only the `std` feature is enabled by default._
 //!
@@ -48,6 +59,7 @@
 //! [`Deserialize`]: `::serde::Deserialize`
 //! [`arbitrary::Arbitrary`]: `::arbitrary::Arbitrary`
 //! [`quickcheck::Arbitrary`]: `::quickcheck::Arbitrary`
+//! [`allocator-api2`]: https://crates.io/crates/allocator-api2
 //!
 //! ### Alternate Hashers
 //!
@@ -105,17 +117,21 @@ extern crate alloc;
 #[macro_use]
 extern crate std;
 
-use alloc::vec::{self, Vec};
-
+mod allocator;
 mod arbitrary;
+mod index_type;
 #[macro_use]
 mod macros;
 mod mutable_keys;
+mod ordered;
 #[cfg(feature = "serde")]
 mod serde;
 mod util;
 
 pub mod map;
+#[cfg(target_has_atomic = "ptr")]
+#[cfg_attr(docsrs, doc(cfg(target_has_atomic = "ptr")))]
+pub mod persistent;
 pub mod set;
 
 // Placed after `map` and `set` so new `rayon` methods on the types
@@ -126,7 +142,9 @@ mod rayon;
 #[cfg(feature = "rustc-rayon")]
 mod rustc;
 
+pub use crate::index_type::IndexType;
 pub use crate::map::IndexMap;
+pub use crate::ordered::Ordered;
 pub use crate::set::IndexSet;
 pub use equivalent::Equivalent;
 
@@ -200,11 +218,23 @@ impl<K, V> Bucket<K, V> {
     fn muts(&mut self) -> (&mut K, &mut V) {
         (&mut self.key, &mut self.value)
     }
+
+    /// Create an entry for a slice, which never uses the hash value, so it's
+    /// left as zero rather than computed from a key that may not be `Hash`.
+    #[cfg(feature = "serde")]
+    fn unhashed(key: K, value: V) -> Self {
+        Bucket {
+            hash: HashValue(0),
+            key,
+            value,
+        }
+    }
 }
 
 trait Entries {
     type Entry;
-    fn into_entries(self) -> Vec<Self::Entry>;
+    type Alloc: allocator::Allocator;
+    fn into_entries(self) -> allocator::Vec<Self::Entry, Self::Alloc>;
     fn as_entries(&self) -> &[Self::Entry];
     fn as_entries_mut(&mut self) -> &mut [Self::Entry];
     fn with_entries<F>(&mut self, f: F)
@@ -221,19 +251,40 @@ pub struct TryReserveError {
 #[derive(Clone, PartialEq, Eq, Debug)]
 enum TryReserveErrorKind {
     // The standard library's kind is currently opaque to us, otherwise we could unify this.
+    #[cfg(not(feature = "allocator-api2"))]
     Std(alloc::collections::TryReserveError),
     CapacityOverflow,
-    AllocError { layout: alloc::alloc::Layout },
+    AllocError {
+        layout: alloc::alloc::Layout,
+    },
 }
 
 // These are not `From` so we don't expose them in our public API.
 impl TryReserveError {
-    fn from_alloc(error: alloc::collections::TryReserveError) -> Self {
+    #[cfg(not(feature = "allocator-api2"))]
+    fn from_alloc(error: allocator::TryReserveError) -> Self {
         Self {
             kind: TryReserveErrorKind::Std(error),
         }
     }
 
+    #[cfg(feature = "allocator-api2")]
+    fn from_alloc(error: allocator::TryReserveError) -> Self {
+        use allocator_api2::collections::TryReserveErrorKind as Kind;
+        Self {
+            kind: match error.kind() {
+                Kind::CapacityOverflow => TryReserveErrorKind::CapacityOverflow,
+                Kind::AllocError { layout, .. } => TryReserveErrorKind::AllocError { layout },
+            },
+        }
+    }
+
+    fn capacity_overflow() -> Self {
+        Self {
+            kind: TryReserveErrorKind::CapacityOverflow,
+        }
+    }
+
     fn from_hashbrown(error: hashbrown::TryReserveError) -> Self {
         Self {
             kind: match error {
@@ -251,6 +302,7 @@ impl TryReserveError {
 impl core::fmt::Display for TryReserveError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let reason = match &self.kind {
+            #[cfg(not(feature = "allocator-api2"))]
             TryReserveErrorKind::Std(e) => return core::fmt::Display::fmt(e, f),
             TryReserveErrorKind::CapacityOverflow => {
                 " because the computed capacity exceeded the collection's maximum"
@@ -267,3 +319,75 @@ impl core::fmt::Display for TryReserveError {
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 impl std::error::Error for TryReserveError {}
+
+/// The error type returned by [`get_disjoint_indices_mut`][`IndexMap::get_disjoint_indices_mut`].
+///
+/// It indicates one of two possible errors:
+/// - An index is out-of-bounds.
+/// - The same index appeared multiple times in the array.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GetDisjointMutError {
+    /// An index provided was out-of-bounds for the slice.
+    IndexOutOfBounds,
+    /// Two indices provided were overlapping.
+    OverlappingIndices,
+}
+
+impl core::fmt::Display for GetDisjointMutError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let msg = match self {
+            GetDisjointMutError::IndexOutOfBounds => "an index is out of bounds",
+            GetDisjointMutError::OverlappingIndices => "there were overlapping indices",
+        };
+
+        core::fmt::Display::fmt(msg, f)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for GetDisjointMutError {}
+
+/// The error type returned by [`IndexMap::try_extend`] and
+/// [`IndexMap::try_from_iter`] when a key is already present.
+///
+/// It holds the pair that was rejected, and the index of the equivalent key
+/// that was found in the map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateKey<K, V> {
+    index: usize,
+    key: K,
+    value: V,
+}
+
+impl<K, V> DuplicateKey<K, V> {
+    /// Returns the index of the equivalent key that is already in the map.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a reference to the rejected key.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns a reference to the rejected value.
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+
+    /// Converts into the rejected key-value pair.
+    pub fn into_parts(self) -> (K, V) {
+        (self.key, self.value)
+    }
+}
+
+impl<K, V> core::fmt::Display for DuplicateKey<K, V> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "duplicate of the key at index {}", self.index)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<K: core::fmt::Debug, V: core::fmt::Debug> std::error::Error for DuplicateKey<K, V> {}
@@ -1,41 +1,78 @@
 //! [`IndexMap`] is a hash table where the iteration order of the key-value
 //! pairs is independent of the hash values of the keys.
 
+pub mod bimap;
 mod core;
+mod diff;
 mod iter;
+pub mod multimap;
 mod slice;
+pub mod tombstone;
 
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde_borrow;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde_hashed;
 #[cfg(feature = "serde")]
 #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
 pub mod serde_seq;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde_slice;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde_strict;
 
 #[cfg(test)]
 mod tests;
 
+pub use self::bimap::IndexBiMap;
 pub use self::core::raw_entry_v1::{self, RawEntryApiV1};
 pub use self::core::{Entry, IndexedEntry, OccupiedEntry, VacantEntry};
+pub use self::diff::{Diff, DiffOp};
 pub use self::iter::{
-    Drain, IntoIter, IntoKeys, IntoValues, Iter, IterMut, Keys, Splice, Values, ValuesMut,
+    Difference, Drain, Intersection, IntoIter, IntoKeys, IntoValues, Iter, IterMut, Keys, Splice,
+    SymmetricDifference, Values, ValuesMut,
 };
+pub use self::multimap::IndexMultiMap;
 pub use self::slice::Slice;
+pub use self::tombstone::TombstoneMap;
 pub use crate::mutable_keys::MutableKeys;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub use crate::serde::IndexMapSeed;
 
 #[cfg(feature = "rayon")]
 pub use crate::rayon::map as rayon;
 
+use ::core::borrow::Borrow;
 use ::core::cmp::Ordering;
 use ::core::fmt;
 use ::core::hash::{BuildHasher, Hash, Hasher};
+use ::core::mem;
 use ::core::ops::{Index, IndexMut, RangeBounds};
 use alloc::boxed::Box;
-use alloc::vec::Vec;
 
 #[cfg(feature = "std")]
 use std::collections::hash_map::RandomState;
 
 use self::core::IndexMapCore;
-use crate::util::{third, try_simplify_range};
-use crate::{Bucket, Entries, Equivalent, HashValue, TryReserveError};
+use crate::allocator::{self, Allocator, Global};
+use crate::util::{hash_unordered, simplify_range, third, try_simplify_range};
+use crate::{
+    Bucket, DuplicateKey, Entries, Equivalent, GetDisjointMutError, HashValue, IndexType,
+    TryReserveError,
+};
+
+/// Hashes a key with the given builder, borrowing only the builder, so that the
+/// map's core can be borrowed mutably at the same time.
+fn hash_elem_using<S: BuildHasher, Q: ?Sized + Hash>(build: &S, key: &Q) -> HashValue {
+    let mut h = build.build_hasher();
+    key.hash(&mut h);
+    HashValue(h.finish() as usize)
+}
 
 /// A hash table where the iteration order of the key-value pairs is independent
 /// of the hash values of the keys.
@@ -64,6 +101,10 @@ use crate::{Bucket, Entries, Equivalent, HashValue, TryReserveError};
 /// index for a key, and the method `.get_index` looks up the key-value pair by
 /// index.
 ///
+/// The hash table stores these indices as the `Idx` type, which is `usize` by default.
+/// A smaller [`IndexType`] like `u32` reduces the memory used by the table,
+/// but also limits how many entries the map can hold.
+///
 /// # Examples
 ///
 /// ```
@@ -81,21 +122,23 @@ use crate::{Bucket, Entries, Equivalent, HashValue, TryReserveError};
 /// assert_eq!(letters.get(&'y'), None);
 /// ```
 #[cfg(feature = "std")]
-pub struct IndexMap<K, V, S = RandomState> {
-    pub(crate) core: IndexMapCore<K, V>,
+pub struct IndexMap<K, V, S = RandomState, Idx = usize, A: Allocator = Global> {
+    pub(crate) core: IndexMapCore<K, V, Idx, A>,
     hash_builder: S,
 }
 #[cfg(not(feature = "std"))]
-pub struct IndexMap<K, V, S> {
-    pub(crate) core: IndexMapCore<K, V>,
+pub struct IndexMap<K, V, S, Idx = usize, A: Allocator = Global> {
+    pub(crate) core: IndexMapCore<K, V, Idx, A>,
     hash_builder: S,
 }
 
-impl<K, V, S> Clone for IndexMap<K, V, S>
+impl<K, V, S, Idx, A> Clone for IndexMap<K, V, S, Idx, A>
 where
     K: Clone,
     V: Clone,
     S: Clone,
+    Idx: IndexType,
+    A: Allocator + Clone,
 {
     fn clone(&self) -> Self {
         IndexMap {
@@ -110,11 +153,12 @@ where
     }
 }
 
-impl<K, V, S> Entries for IndexMap<K, V, S> {
+impl<K, V, S, Idx: IndexType, A: Allocator> Entries for IndexMap<K, V, S, Idx, A> {
     type Entry = Bucket<K, V>;
+    type Alloc = A;
 
     #[inline]
-    fn into_entries(self) -> Vec<Self::Entry> {
+    fn into_entries(self) -> allocator::Vec<Self::Entry, A> {
         self.core.into_entries()
     }
 
@@ -136,10 +180,12 @@ impl<K, V, S> Entries for IndexMap<K, V, S> {
     }
 }
 
-impl<K, V, S> fmt::Debug for IndexMap<K, V, S>
+impl<K, V, S, Idx, A> fmt::Debug for IndexMap<K, V, S, Idx, A>
 where
     K: fmt::Debug,
     V: fmt::Debug,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if cfg!(not(feature = "test_debug")) {
@@ -179,8 +225,38 @@ impl<K, V, S> IndexMap<K, V, S> {
     /// Computes in **O(n)** time.
     #[inline]
     pub fn with_capacity_and_hasher(n: usize, hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher_idx(n, hash_builder)
+    }
+
+    /// Create a new map with `hash_builder`.
+    ///
+    /// This function is `const`, so it
+    /// can be called in `static` contexts.
+    pub const fn with_hasher(hash_builder: S) -> Self {
+        Self::with_hasher_idx(hash_builder)
+    }
+}
+
+impl<K, V, S, Idx: IndexType> IndexMap<K, V, S, Idx> {
+    /// Create a new map with capacity for `n` key-value pairs, which are
+    /// indexed by `Idx`. (Does not allocate if `n` is zero.)
+    ///
+    /// This is [`with_capacity_and_hasher`][IndexMap::with_capacity_and_hasher]
+    /// for any [`IndexType`], which has to be named or inferred from the context.
+    ///
+    /// Computes in **O(n)** time.
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let map = IndexMap::<&str, i32, _, u16>::with_capacity_and_hasher_idx(10, RandomState::new());
+    /// assert!(map.capacity() >= 10);
+    /// ```
+    #[inline]
+    pub fn with_capacity_and_hasher_idx(n: usize, hash_builder: S) -> Self {
         if n == 0 {
-            Self::with_hasher(hash_builder)
+            Self::with_hasher_idx(hash_builder)
         } else {
             IndexMap {
                 core: IndexMapCore::with_capacity(n),
@@ -189,17 +265,72 @@ impl<K, V, S> IndexMap<K, V, S> {
         }
     }
 
-    /// Create a new map with `hash_builder`.
+    /// Create a new map with `hash_builder`, whose entries are indexed by `Idx`.
+    ///
+    /// This is [`with_hasher`][IndexMap::with_hasher] for any [`IndexType`],
+    /// which has to be named or inferred from the context.
     ///
     /// This function is `const`, so it
     /// can be called in `static` contexts.
-    pub const fn with_hasher(hash_builder: S) -> Self {
+    pub const fn with_hasher_idx(hash_builder: S) -> Self {
         IndexMap {
             core: IndexMapCore::new(),
             hash_builder,
         }
     }
+}
+
+#[cfg(all(feature = "std", feature = "allocator-api2"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "std", feature = "allocator-api2"))))]
+impl<K, V, A: Allocator + Clone> IndexMap<K, V, RandomState, usize, A> {
+    /// Create a new map that allocates its storage in `alloc`. (Does not allocate.)
+    ///
+    /// ```
+    /// use allocator_api2::alloc::Global;
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut map = IndexMap::new_in(Global);
+    /// map.insert("a", 1);
+    /// assert_eq!(map["a"], 1);
+    /// ```
+    #[inline]
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+
+    /// Create a new map with capacity for `n` key-value pairs, allocated in `alloc`.
+    /// (Does not allocate if `n` is zero.)
+    ///
+    /// Computes in **O(n)** time.
+    #[inline]
+    pub fn with_capacity_in(n: usize, alloc: A) -> Self {
+        Self::with_capacity_and_hasher_in(n, <_>::default(), alloc)
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+#[cfg_attr(docsrs, doc(cfg(feature = "allocator-api2")))]
+impl<K, V, S, Idx: IndexType, A: Allocator + Clone> IndexMap<K, V, S, Idx, A> {
+    /// Create a new map with capacity for `n` key-value pairs and `hash_builder`,
+    /// allocated in `alloc`. (Does not allocate if `n` is zero.)
+    ///
+    /// Computes in **O(n)** time.
+    #[inline]
+    pub fn with_capacity_and_hasher_in(n: usize, hash_builder: S, alloc: A) -> Self {
+        IndexMap {
+            core: IndexMapCore::with_capacity_in(n, alloc),
+            hash_builder,
+        }
+    }
+
+    /// Create a new map with `hash_builder`, allocated in `alloc`. (Does not allocate.)
+    #[inline]
+    pub fn with_hasher_in(hash_builder: S, alloc: A) -> Self {
+        Self::with_capacity_and_hasher_in(0, hash_builder, alloc)
+    }
+}
 
+impl<K, V, S, Idx: IndexType, A: Allocator> IndexMap<K, V, S, Idx, A> {
     /// Return the number of elements the map can hold without reallocating.
     ///
     /// This number is a lower bound; the map might be able to hold more,
@@ -215,6 +346,13 @@ impl<K, V, S> IndexMap<K, V, S> {
         &self.hash_builder
     }
 
+    /// Return a reference to the allocator of the map's storage.
+    #[cfg(feature = "allocator-api2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "allocator-api2")))]
+    pub fn allocator(&self) -> &A {
+        self.core.allocator()
+    }
+
     /// Return the number of key-value pairs in the map.
     ///
     /// Computes in **O(1)** time.
@@ -247,7 +385,7 @@ impl<K, V, S> IndexMap<K, V, S> {
     }
 
     /// Return an owning iterator over the keys of the map, in their order
-    pub fn into_keys(self) -> IntoKeys<K, V> {
+    pub fn into_keys(self) -> IntoKeys<K, V, A> {
         IntoKeys::new(self.into_entries())
     }
 
@@ -263,7 +401,7 @@ impl<K, V, S> IndexMap<K, V, S> {
     }
 
     /// Return an owning iterator over the values of the map, in their order
-    pub fn into_values(self) -> IntoValues<K, V> {
+    pub fn into_values(self) -> IntoValues<K, V, A> {
         IntoValues::new(self.into_entries())
     }
 
@@ -294,7 +432,7 @@ impl<K, V, S> IndexMap<K, V, S> {
     ///
     /// ***Panics*** if the starting point is greater than the end point or if
     /// the end point is greater than the length of the map.
-    pub fn drain<R>(&mut self, range: R) -> Drain<'_, K, V>
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, K, V, A>
     where
         R: RangeBounds<usize>,
     {
@@ -311,6 +449,7 @@ impl<K, V, S> IndexMap<K, V, S> {
     pub fn split_off(&mut self, at: usize) -> Self
     where
         S: Clone,
+        A: Clone,
     {
         Self {
             core: self.core.split_off(at),
@@ -371,10 +510,12 @@ impl<K, V, S> IndexMap<K, V, S> {
     }
 }
 
-impl<K, V, S> IndexMap<K, V, S>
+impl<K, V, S, Idx, A> IndexMap<K, V, S, Idx, A>
 where
     K: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Insert a key-value pair in the map.
     ///
@@ -411,15 +552,413 @@ where
         self.core.insert_full(hash, key, value)
     }
 
+    /// Insert a key-value pair in the map at its ordered position among sorted keys.
+    ///
+    /// This is equivalent to finding the position with
+    /// [`binary_search_keys`][Self::binary_search_keys], then either updating
+    /// the value at that position or calling [`insert_before`][Self::insert_before]
+    /// for a new key.
+    ///
+    /// If the sorted key is found in the map, its corresponding value is
+    /// updated with `value`, and the older value is returned inside
+    /// `(index, Some(_))`. Otherwise, the new key-value pair is inserted at
+    /// the sorted position, and `(index, None)` is returned.
+    ///
+    /// If the existing keys are **not** already sorted, then the binary search
+    /// may fail to find a matching key, and the value of the returned `index`
+    /// is unspecified. An equivalent key that is already in the map is moved to
+    /// that position rather than duplicated, so the map stays consistent either way.
+    ///
+    /// Computes in **O(n)** time (average). Instead of repeating calls to
+    /// `insert_sorted`, it may be faster to call batched [`insert`][Self::insert]
+    /// or [`extend`][Self::extend] and only call [`sort_keys`][Self::sort_keys]
+    /// or [`sort_unstable_keys`][Self::sort_unstable_keys] once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    /// let mut map = IndexMap::new();
+    ///
+    /// assert_eq!(map.insert_sorted('m', 1), (0, None));
+    /// assert_eq!(map.insert_sorted('c', 2), (0, None));
+    /// assert_eq!(map.insert_sorted('x', 3), (2, None));
+    /// assert_eq!(map.insert_sorted('c', 4), (0, Some(2)));
+    ///
+    /// assert!(map.keys().eq(&['c', 'm', 'x']));
+    /// assert_eq!(map.binary_search_keys(&'x'), Ok(2));
+    /// ```
+    pub fn insert_sorted(&mut self, key: K, value: V) -> (usize, Option<V>)
+    where
+        K: Ord,
+    {
+        match self.binary_search_keys(&key) {
+            Ok(i) => (i, Some(mem::replace(&mut self[i], value))),
+            Err(i) => self.insert_before(i, key, value),
+        }
+    }
+
+    /// Insert a key-value pair in the map at the given index.
+    ///
+    /// If an equivalent key already exists in the map: the key remains and
+    /// is moved to the given index in the map, its corresponding value is updated
+    /// with `value`, and the older value is returned inside `Some(_)`.
+    /// Note that existing entries **cannot** be moved to `index == map.len()`!
+    /// (See [`insert_before`](Self::insert_before) for different behavior here.)
+    ///
+    /// If no equivalent key existed in the map: the new key-value pair is
+    /// inserted at the given index, and `None` is returned.
+    ///
+    /// ***Panics*** if `index` is out of bounds.
+    /// Valid indices are `0..map.len()` (exclusive) when moving an existing entry, or
+    /// `0..=map.len()` (inclusive) when inserting a new key.
+    ///
+    /// Computes in **O(n)** time (average).
+    ///
+    /// See also [`entry`][Self::entry] if you want to insert *or* modify,
+    /// perhaps only using the index for new entries with [`VacantEntry::shift_insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    /// let mut map: IndexMap<char, ()> = ('a'..='z').map(|c| (c, ())).collect();
+    ///
+    /// // The new key '*' goes exactly at the given index.
+    /// assert_eq!(map.get_index_of(&'*'), None);
+    /// assert_eq!(map.shift_insert(10, '*', ()), None);
+    /// assert_eq!(map.get_index_of(&'*'), Some(10));
+    ///
+    /// // Moving the key 'a' up will shift others down, including the '*' that was at 10.
+    /// assert_eq!(map.shift_insert(10, 'a', ()), Some(()));
+    /// assert_eq!(map.get_index_of(&'a'), Some(10));
+    /// assert_eq!(map.get_index_of(&'*'), Some(9));
+    ///
+    /// // Moving the key 'z' down will shift others up, so '*' moves back to 10.
+    /// assert_eq!(map.shift_insert(9, 'z', ()), Some(()));
+    /// assert_eq!(map.get_index_of(&'z'), Some(9));
+    /// assert_eq!(map.get_index_of(&'*'), Some(10));
+    ///
+    /// // Existing keys can move to len-1 at most, but new keys can insert at the endpoint.
+    /// assert_eq!(map.len(), 27);
+    /// assert_eq!(map.shift_insert(map.len() - 1, '*', ()), Some(()));
+    /// assert_eq!(map.get_index_of(&'*'), Some(26));
+    /// assert_eq!(map.shift_insert(map.len(), '+', ()), None);
+    /// assert_eq!(map.get_index_of(&'+'), Some(27));
+    /// assert_eq!(map.len(), 28);
+    /// ```
+    pub fn shift_insert(&mut self, index: usize, key: K, value: V) -> Option<V> {
+        let len = self.len();
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => {
+                assert!(
+                    index < len,
+                    "index out of bounds: the len is {len} but the index is {index}"
+                );
+
+                let old = mem::replace(entry.get_mut(), value);
+                entry.move_index(index);
+                Some(old)
+            }
+            Entry::Vacant(entry) => {
+                assert!(
+                    index <= len,
+                    "index out of bounds: the len is {len} but the index is {index}. Expected index <= len"
+                );
+
+                entry.shift_insert(index, value);
+                None
+            }
+        }
+    }
+
+    /// Insert a key-value pair in the map before the entry at the given index, or at the end.
+    ///
+    /// If an equivalent key already exists in the map: the key remains and
+    /// is moved to the new position in the map, its corresponding value is updated
+    /// with `value`, and the older value is returned inside `Some(_)`. The returned index
+    /// will either be the given index or one less, depending on how the entry moved.
+    /// (See [`shift_insert`](Self::shift_insert) for different behavior here.)
+    ///
+    /// If no equivalent key existed in the map: the new key-value pair is
+    /// inserted exactly at the given index, and `None` is returned.
+    ///
+    /// ***Panics*** if `index` is out of bounds.
+    /// Valid indices are `0..=map.len()` (inclusive).
+    ///
+    /// Computes in **O(n)** time (average).
+    ///
+    /// See also [`entry`][Self::entry] if you want to insert *or* modify,
+    /// perhaps only using the index for new entries with [`VacantEntry::shift_insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    /// let mut map: IndexMap<char, ()> = ('a'..='z').map(|c| (c, ())).collect();
+    ///
+    /// // The new key '*' goes exactly at the given index.
+    /// assert_eq!(map.get_index_of(&'*'), None);
+    /// assert_eq!(map.insert_before(10, '*', ()), (10, None));
+    /// assert_eq!(map.get_index_of(&'*'), Some(10));
+    ///
+    /// // Moving the key 'a' up will shift others down, so this moves *before* 10 to index 9.
+    /// assert_eq!(map.insert_before(10, 'a', ()), (9, Some(())));
+    /// assert_eq!(map.get_index_of(&'a'), Some(9));
+    /// assert_eq!(map.get_index_of(&'*'), Some(10));
+    ///
+    /// // Moving the key 'z' down will shift others up, so this moves to exactly 10.
+    /// assert_eq!(map.insert_before(10, 'z', ()), (10, Some(())));
+    /// assert_eq!(map.get_index_of(&'z'), Some(10));
+    /// assert_eq!(map.get_index_of(&'*'), Some(11));
+    ///
+    /// // Moving or inserting before the endpoint is also valid.
+    /// assert_eq!(map.len(), 27);
+    /// assert_eq!(map.insert_before(map.len(), '*', ()), (26, Some(())));
+    /// assert_eq!(map.get_index_of(&'*'), Some(26));
+    /// assert_eq!(map.insert_before(map.len(), '+', ()), (27, None));
+    /// assert_eq!(map.get_index_of(&'+'), Some(27));
+    /// assert_eq!(map.len(), 28);
+    /// ```
+    pub fn insert_before(&mut self, mut index: usize, key: K, value: V) -> (usize, Option<V>) {
+        let len = self.len();
+
+        assert!(
+            index <= len,
+            "index out of bounds: the len is {len} but the index is {index}. Expected index <= len"
+        );
+
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => {
+                if index > entry.index() {
+                    // Some entries will shift down when this one moves up,
+                    // so "insert before index" becomes "move to index - 1",
+                    // keeping the entry at the original index unmoved.
+                    index -= 1;
+                }
+                let old = mem::replace(entry.get_mut(), value);
+                entry.move_index(index);
+                (index, Some(old))
+            }
+            Entry::Vacant(entry) => {
+                entry.shift_insert(index, value);
+                (index, None)
+            }
+        }
+    }
+
     /// Get the given key’s corresponding entry in the map for insertion and/or
     /// in-place manipulation.
     ///
     /// Computes in **O(1)** time (amortized average).
-    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, Idx, A> {
         let hash = self.hash(&key);
         self.core.entry(hash, key)
     }
 
+    /// Return an iterator over the key-value pairs whose keys are in `self` but not `other`.
+    ///
+    /// Pairs are produced in the same order that they appear in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let old = IndexMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let new = IndexMap::from([("c", 30), ("a", 10)]);
+    /// assert!(old.difference(&new).eq([(&"b", &2)]));
+    /// ```
+    pub fn difference<'a, V2, S2, Idx2, A2>(
+        &'a self,
+        other: &'a IndexMap<K, V2, S2, Idx2, A2>,
+    ) -> Difference<'a, K, V, V2, S2, Idx2, A2>
+    where
+        S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
+    {
+        Difference::new(self, other)
+    }
+
+    /// Return an iterator over the key-value pairs whose keys are in `self` or `other`,
+    /// but not in both.
+    ///
+    /// Pairs from `self` are produced in their original order, followed by
+    /// pairs from `other` in their original order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let old = IndexMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    /// let new = IndexMap::from([("d", 4), ("c", 30), ("a", 10)]);
+    /// assert!(old.symmetric_difference(&new).eq([(&"b", &2), (&"d", &4)]));
+    /// ```
+    pub fn symmetric_difference<'a, S2, Idx2, A2>(
+        &'a self,
+        other: &'a IndexMap<K, V, S2, Idx2, A2>,
+    ) -> SymmetricDifference<'a, K, V, S, S2, Idx, Idx2, A, A2>
+    where
+        S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
+    {
+        SymmetricDifference::new(self, other)
+    }
+
+    /// Return an iterator over the keys that are in both `self` and `other`,
+    /// with their values from each map.
+    ///
+    /// Entries are produced in the same order that they appear in `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let ports = IndexMap::from([("http", 80), ("ssh", 22), ("https", 443)]);
+    /// let hosts = IndexMap::from([("https", "example.com"), ("http", "localhost")]);
+    /// let both: Vec<_> = ports.intersection_with(&hosts).collect();
+    /// assert_eq!(both, [(&"http", &80, &"localhost"), (&"https", &443, &"example.com")]);
+    /// ```
+    pub fn intersection_with<'a, V2, S2, Idx2, A2>(
+        &'a self,
+        other: &'a IndexMap<K, V2, S2, Idx2, A2>,
+    ) -> Intersection<'a, K, V, V2, S2, Idx2, A2>
+    where
+        S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
+    {
+        Intersection::new(self, other)
+    }
+
+    /// Merge the key-value pairs of `other` into `self`, resolving conflicts with `merge`.
+    ///
+    /// For a key that is already in `self`, `merge` is called with the key, a mutable
+    /// reference to the existing value, and the value from `other`; the key keeps its
+    /// place in the order. New keys are appended in the order they appear in `other`.
+    ///
+    /// Computes in **O(m)** time (amortized average), where *m* is the length of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut totals = IndexMap::from([("apples", 3), ("pears", 1)]);
+    /// let more = IndexMap::from([("kiwis", 4), ("apples", 2)]);
+    /// totals.merge_with(more, |_, total, count| *total += count);
+    /// assert!(totals.into_iter().eq([("apples", 5), ("pears", 1), ("kiwis", 4)]));
+    /// ```
+    pub fn merge_with<I, F>(&mut self, other: I, mut merge: F)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        F: FnMut(&K, &mut V, V),
+    {
+        let iter = other.into_iter();
+        self.reserve_for_extend(iter.size_hint().0);
+        for (key, value) in iter {
+            let hash = self.hash(&key);
+            if let Err((i, _, value)) = self.core.insert_new(hash, key, value) {
+                let (key, existing) = self.as_entries_mut()[i].ref_mut();
+                merge(key, existing, value);
+            }
+        }
+    }
+
+    /// Extend the map with the key-value pairs of the iterable, resolving
+    /// duplicate keys with `resolve`.
+    ///
+    /// Unlike [`extend`][Self::extend], which keeps the last value of a
+    /// duplicate key, `resolve` is called with a mutable reference to the
+    /// existing value and the new value, so it can keep either or combine
+    /// them. This applies to keys that were already in the map and to keys
+    /// that occur more than once in the iterable. Every key keeps the position
+    /// of its first occurrence. See [`merge_with`][Self::merge_with] if the
+    /// callback also needs the key.
+    ///
+    /// Computes in **O(m)** time (amortized average), where *m* is the length
+    /// of the iterable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut map = IndexMap::from([("a", 1)]);
+    /// map.extend_with([("b", 2), ("a", 10), ("b", 20)], |_old, _new| {});
+    /// assert!(map.into_iter().eq([("a", 1), ("b", 2)]));
+    /// ```
+    pub fn extend_with<I, F>(&mut self, iterable: I, mut resolve: F)
+    where
+        I: IntoIterator<Item = (K, V)>,
+        F: FnMut(&mut V, V),
+    {
+        self.merge_with(iterable, move |_, existing, new| resolve(existing, new));
+    }
+
+    /// Extend the map with the key-value pairs of the iterable, failing on
+    /// the first key that is already present.
+    ///
+    /// This fails both for keys that were in the map before and for keys that
+    /// occur more than once in the iterable. The pairs before the duplicate
+    /// remain inserted, in order, and the rest of the iterable is dropped.
+    /// The error holds the rejected pair and the index of the existing key.
+    ///
+    /// The whole iterable is collected before any key is checked, so the new
+    /// entries can be indexed all at once after a single reservation.
+    ///
+    /// Computes in **O(m)** time (amortized average), where *m* is the length
+    /// of the iterable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut map = IndexMap::from([("a", 1)]);
+    /// assert!(map.try_extend([("b", 2), ("c", 3)]).is_ok());
+    ///
+    /// let error = map.try_extend([("d", 4), ("b", 20), ("e", 5)]).unwrap_err();
+    /// assert_eq!(error.index(), 1);
+    /// assert_eq!(error.into_parts(), ("b", 20));
+    /// assert!(map.keys().eq(&["a", "b", "c", "d"]));
+    /// ```
+    pub fn try_extend<I>(&mut self, iterable: I) -> Result<(), DuplicateKey<K, V>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let hash_builder = &self.hash_builder;
+        let iter = iterable.into_iter().map(|(key, value)| Bucket {
+            hash: hash_elem_using(hash_builder, &key),
+            key,
+            value,
+        });
+        self.core.extend_unique(iter).map_err(|(index, rest)| {
+            let Bucket { key, value, .. } = rest.into_iter().next().unwrap();
+            DuplicateKey { index, key, value }
+        })
+    }
+
+    /// Reserve capacity for extending with an iterator whose size hint has
+    /// the lower bound `lower`.
+    fn reserve_for_extend(&mut self, lower: usize) {
+        // (Note: this is a copy of `std`/`hashbrown`'s reservation logic.)
+        // Keys may be already present or show multiple times in the iterator.
+        // Reserve the entire hint lower bound if the map is empty.
+        // Otherwise reserve half the hint (rounded up), so the map
+        // will only resize twice in the worst case.
+        let reserve = if self.is_empty() {
+            lower
+        } else {
+            (lower + 1) / 2
+        };
+        self.reserve(reserve);
+    }
+
     /// Creates a splicing iterator that replaces the specified range in the map
     /// with the given `replace_with` key-value iterator and yields the removed
     /// items. `replace_with` does not need to be the same length as `range`.
@@ -450,23 +989,28 @@ where
     /// assert!(map.into_iter().eq([(0, '_'), (1, 'A'), (5, 'E'), (3, 'C'), (2, 'B'), (4, 'D')]));
     /// assert_eq!(removed, &[(2, 'b'), (3, 'c')]);
     /// ```
-    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, K, V, S>
+    pub fn splice<R, I>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> Splice<'_, I::IntoIter, K, V, S, Idx, A>
     where
         R: RangeBounds<usize>,
         I: IntoIterator<Item = (K, V)>,
+        A: Clone,
     {
         Splice::new(self, range, replace_with.into_iter())
     }
 }
 
-impl<K, V, S> IndexMap<K, V, S>
+impl<K, V, S, Idx, A> IndexMap<K, V, S, Idx, A>
 where
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     pub(crate) fn hash<Q: ?Sized + Hash>(&self, key: &Q) -> HashValue {
-        let mut h = self.hash_builder.build_hasher();
-        key.hash(&mut h);
-        HashValue(h.finish() as usize)
+        hash_elem_using(&self.hash_builder, key)
     }
 
     /// Return `true` if an equivalent to `key` exists in the map.
@@ -563,6 +1107,49 @@ where
         }
     }
 
+    /// Return the values for `N` keys, with mutable access to each of them.
+    ///
+    /// Keys that are not present in the map produce `None` in their position.
+    ///
+    /// ***Panics*** if any of the keys refer to the same entry.
+    ///
+    /// Computes in **O(N)** time (average), with an additional **O(N²)** check for overlaps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut map = IndexMap::from([("alice", 100), ("bob", 50), ("carol", 0)]);
+    /// if let [Some(from), Some(to)] = map.get_disjoint_mut(["alice", "carol"]) {
+    ///     *from -= 30;
+    ///     *to += 30;
+    /// }
+    /// assert_eq!(map["alice"], 70);
+    /// assert_eq!(map["carol"], 30);
+    /// assert_eq!(map.get_disjoint_mut(["bob", "dave"]), [Some(&mut 50), None]);
+    /// ```
+    pub fn get_disjoint_mut<Q: ?Sized, const N: usize>(
+        &mut self,
+        keys: [&Q; N],
+    ) -> [Option<&mut V>; N]
+    where
+        Q: Hash + Equivalent<K>,
+    {
+        let indices = keys.map(|key| self.get_index_of(key));
+        match self.as_mut_slice().get_disjoint_opt_mut(indices) {
+            Err(GetDisjointMutError::IndexOutOfBounds) => {
+                unreachable!(
+                    "Internal error: indices should never be OOB as we got them from get_index_of"
+                );
+            }
+            Err(GetDisjointMutError::OverlappingIndices) => {
+                panic!("duplicate keys found");
+            }
+            Ok(key_values) => key_values.map(|kv_opt| kv_opt.map(|kv| kv.1)),
+        }
+    }
+
     /// Remove the key-value pair equivalent to `key` and return
     /// its value.
     ///
@@ -597,7 +1184,7 @@ where
     /// Remove the key-value pair equivalent to `key` and return
     /// its value.
     ///
-    /// Like [`Vec::swap_remove`], the pair is removed by swapping it with the
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is removed by swapping it with the
     /// last element of the map and popping it off. **This perturbs
     /// the position of what used to be the last element!**
     ///
@@ -613,7 +1200,7 @@ where
 
     /// Remove and return the key-value pair equivalent to `key`.
     ///
-    /// Like [`Vec::swap_remove`], the pair is removed by swapping it with the
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is removed by swapping it with the
     /// last element of the map and popping it off. **This perturbs
     /// the position of what used to be the last element!**
     ///
@@ -633,7 +1220,7 @@ where
     /// Remove the key-value pair equivalent to `key` and return it and
     /// the index it had.
     ///
-    /// Like [`Vec::swap_remove`], the pair is removed by swapping it with the
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is removed by swapping it with the
     /// last element of the map and popping it off. **This perturbs
     /// the position of what used to be the last element!**
     ///
@@ -654,7 +1241,7 @@ where
     /// Remove the key-value pair equivalent to `key` and return
     /// its value.
     ///
-    /// Like [`Vec::remove`], the pair is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -670,7 +1257,7 @@ where
 
     /// Remove and return the key-value pair equivalent to `key`.
     ///
-    /// Like [`Vec::remove`], the pair is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -690,7 +1277,7 @@ where
     /// Remove the key-value pair equivalent to `key` and return it and
     /// the index it had.
     ///
-    /// Like [`Vec::remove`], the pair is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -709,7 +1296,7 @@ where
     }
 }
 
-impl<K, V, S> IndexMap<K, V, S> {
+impl<K, V, S, Idx: IndexType, A: Allocator> IndexMap<K, V, S, Idx, A> {
     /// Remove the last key-value pair
     ///
     /// This preserves the order of the remaining elements.
@@ -773,7 +1360,7 @@ impl<K, V, S> IndexMap<K, V, S> {
     /// the key-value pairs with the result.
     ///
     /// The sort is stable.
-    pub fn sorted_by<F>(self, mut cmp: F) -> IntoIter<K, V>
+    pub fn sorted_by<F>(self, mut cmp: F) -> IntoIter<K, V, A>
     where
         F: FnMut(&K, &V, &K, &V) -> Ordering,
     {
@@ -817,7 +1404,7 @@ impl<K, V, S> IndexMap<K, V, S> {
     ///
     /// The sort is unstable.
     #[inline]
-    pub fn sorted_unstable_by<F>(self, mut cmp: F) -> IntoIter<K, V>
+    pub fn sorted_unstable_by<F>(self, mut cmp: F) -> IntoIter<K, V, A>
     where
         F: FnMut(&K, &V, &K, &V) -> Ordering,
     {
@@ -844,6 +1431,43 @@ impl<K, V, S> IndexMap<K, V, S> {
         });
     }
 
+    /// Sort the key-value pairs in the given index range in place, using the
+    /// comparison function `cmp`, and leave the rest of the map as it is.
+    ///
+    /// The range may be any type that implements [`RangeBounds<usize>`],
+    /// including all of the `std::ops::Range*` types, or even a tuple pair of
+    /// `Bound` start and end values.
+    ///
+    /// Only the indices of the sorted range are updated, so this is cheaper
+    /// than [`sort_by`][Self::sort_by] when the range is a small part of the map.
+    ///
+    /// ***Panics*** if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the map.
+    ///
+    /// Computes in **O(m log m)** time (average) and **O(m)** space, where *m*
+    /// is the length of the range. The sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut map = IndexMap::from([("e", 5), ("d", 4), ("c", 3), ("b", 2), ("a", 1)]);
+    /// map.sort_range_by(1..4, |_, v1, _, v2| v1.cmp(v2));
+    /// assert!(map.keys().eq(&["e", "b", "c", "d", "a"]));
+    /// assert_eq!(map.get_index_of("d"), Some(3));
+    /// ```
+    pub fn sort_range_by<R, F>(&mut self, range: R, mut cmp: F)
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        let range = simplify_range(range, self.len());
+        self.core.with_entries_range(range, move |entries| {
+            entries.sort_by(move |a, b| cmp(&a.key, &a.value, &b.key, &b.value));
+        });
+    }
+
     /// Search over a sorted map for a key.
     ///
     /// Returns the position where that key is present, or the position where it can be inserted to
@@ -901,6 +1525,63 @@ impl<K, V, S> IndexMap<K, V, S> {
         self.as_slice().partition_point(pred)
     }
 
+    /// Returns a slice of the key-value pairs of a sorted map whose keys are within `range`.
+    ///
+    /// The bounds are found with [`partition_point`][Self::partition_point], so this is
+    /// like [`BTreeMap::range`][alloc::collections::BTreeMap::range] for a map that is
+    /// sorted by its keys, e.g. after [`sort_keys`][Self::sort_keys]. If the keys are
+    /// **not** sorted, the returned slice is unspecified. A range whose start is after
+    /// its end returns an empty slice.
+    ///
+    /// Computes in **O(log(n))** time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    /// let mut map: IndexMap<i32, char> = [(40, 'd'), (10, 'a'), (30, 'c'), (20, 'b')].into();
+    /// map.sort_keys();
+    ///
+    /// assert!(map.range_keys(15..35).values().eq(&['b', 'c']));
+    /// assert!(map.range_keys(..=20).keys().eq(&[10, 20]));
+    /// assert!(map.range_keys(50..).is_empty());
+    /// ```
+    pub fn range_keys<Q, R>(&self, range: R) -> &Slice<K, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        self.as_slice().range_keys(range)
+    }
+
+    /// Returns a mutable slice of the key-value pairs of a sorted map whose keys are
+    /// within `range`.
+    ///
+    /// See [`range_keys`][Self::range_keys] for how the bounds are found.
+    ///
+    /// Computes in **O(log(n))** time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    /// let mut map: IndexMap<i32, i32> = (1..=10).map(|k| (k, 0)).collect();
+    ///
+    /// for (_, value) in map.range_keys_mut(4..7) {
+    ///     *value += 1;
+    /// }
+    /// assert!(map.values().eq(&[0, 0, 0, 1, 1, 1, 0, 0, 0, 0]));
+    /// ```
+    pub fn range_keys_mut<Q, R>(&mut self, range: R) -> &mut Slice<K, V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        self.as_mut_slice().range_keys_mut(range)
+    }
+
     /// Reverses the order of the map’s key-value pairs in place.
     ///
     /// Computes in **O(n)** time and **O(1)** space.
@@ -921,12 +1602,27 @@ impl<K, V, S> IndexMap<K, V, S> {
     pub fn as_mut_slice(&mut self) -> &mut Slice<K, V> {
         Slice::from_mut_slice(self.as_entries_mut())
     }
+}
 
+impl<K, V, S, Idx: IndexType> IndexMap<K, V, S, Idx> {
     /// Converts into a boxed slice of all the key-value pairs in the map.
     ///
     /// Note that this will drop the inner hash table and any excess capacity.
     pub fn into_boxed_slice(self) -> Box<Slice<K, V>> {
-        Slice::from_boxed(self.into_entries().into_boxed_slice())
+        let entries = allocator::into_std_vec(self.into_entries());
+        Slice::from_boxed(entries.into_boxed_slice())
+    }
+}
+
+impl<K, V, S, Idx: IndexType, A: Allocator> IndexMap<K, V, S, Idx, A> {
+    /// Converts into a boxed slice of all the key-value pairs in the map,
+    /// keeping them in the map's allocator.
+    ///
+    /// Note that this will drop the inner hash table and any excess capacity.
+    #[cfg(feature = "allocator-api2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "allocator-api2")))]
+    pub fn into_boxed_slice_in(self) -> allocator_api2::boxed::Box<Slice<K, V>, A> {
+        Slice::from_boxed_in(self.into_entries().into_boxed_slice())
     }
 
     /// Get a key-value pair by index
@@ -947,12 +1643,39 @@ impl<K, V, S> IndexMap<K, V, S> {
         self.as_entries_mut().get_mut(index).map(Bucket::ref_mut)
     }
 
+    /// Get an array of `N` key-value pairs by `N` indices, with mutable access to the values.
+    ///
+    /// Valid indices are *0 <= index < self.len()* and each index needs to be unique.
+    ///
+    /// Returns an error if any index is out of bounds, or if the same index appears
+    /// more than once.
+    ///
+    /// Computes in **O(N²)** time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut map = IndexMap::from([(1, 'a'), (3, 'b'), (2, 'c')]);
+    /// assert_eq!(
+    ///     map.get_disjoint_indices_mut([2, 0]),
+    ///     Ok([(&2, &mut 'c'), (&1, &mut 'a')]),
+    /// );
+    /// ```
+    pub fn get_disjoint_indices_mut<const N: usize>(
+        &mut self,
+        indices: [usize; N],
+    ) -> Result<[(&K, &mut V); N], GetDisjointMutError> {
+        self.as_mut_slice().get_disjoint_indices_mut(indices)
+    }
+
     /// Get an entry in the map by index for in-place manipulation.
     ///
     /// Valid indices are *0 <= index < self.len()*
     ///
     /// Computes in **O(1)** time.
-    pub fn get_index_entry(&mut self, index: usize) -> Option<IndexedEntry<'_, K, V>> {
+    pub fn get_index_entry(&mut self, index: usize) -> Option<IndexedEntry<'_, K, V, Idx, A>> {
         if index >= self.len() {
             return None;
         }
@@ -1013,7 +1736,7 @@ impl<K, V, S> IndexMap<K, V, S> {
     ///
     /// Valid indices are *0 <= index < self.len()*
     ///
-    /// Like [`Vec::swap_remove`], the pair is removed by swapping it with the
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is removed by swapping it with the
     /// last element of the map and popping it off. **This perturbs
     /// the position of what used to be the last element!**
     ///
@@ -1026,7 +1749,7 @@ impl<K, V, S> IndexMap<K, V, S> {
     ///
     /// Valid indices are *0 <= index < self.len()*
     ///
-    /// Like [`Vec::remove`], the pair is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -1054,6 +1777,114 @@ impl<K, V, S> IndexMap<K, V, S> {
     pub fn swap_indices(&mut self, a: usize, b: usize) {
         self.core.swap_indices(a, b)
     }
+
+    /// Rotates the key-value pairs in the given index range, so that the pair
+    /// at `range.start + k` becomes the first of the range, and the first `k`
+    /// pairs move to the end of the range.
+    ///
+    /// The range may be any type that implements [`RangeBounds<usize>`],
+    /// including all of the `std::ops::Range*` types, or even a tuple pair of
+    /// `Bound` start and end values. Pairs outside the range are not moved.
+    ///
+    /// ***Panics*** if the range is out of bounds, or if `k` is greater than
+    /// the length of the range.
+    ///
+    /// Computes in **O(m)** time (average), where *m* is the length of the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut map: IndexMap<_, _> = (0..6).map(|i| (i, ())).collect();
+    /// map.rotate_left(1..5, 1);
+    /// assert!(map.keys().eq(&[0, 2, 3, 4, 1, 5]));
+    /// ```
+    pub fn rotate_left<R>(&mut self, range: R, k: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = simplify_range(range, self.len());
+        self.core.rotate_range_left(range, k);
+    }
+
+    /// Rotates the key-value pairs in the given index range, so that the last
+    /// `k` pairs of the range move to its start.
+    ///
+    /// The range may be any type that implements [`RangeBounds<usize>`],
+    /// including all of the `std::ops::Range*` types, or even a tuple pair of
+    /// `Bound` start and end values. Pairs outside the range are not moved.
+    ///
+    /// ***Panics*** if the range is out of bounds, or if `k` is greater than
+    /// the length of the range.
+    ///
+    /// Computes in **O(m)** time (average), where *m* is the length of the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut map: IndexMap<_, _> = (0..6).map(|i| (i, ())).collect();
+    /// map.rotate_right(1..5, 1);
+    /// assert!(map.keys().eq(&[0, 4, 1, 2, 3, 5]));
+    /// ```
+    pub fn rotate_right<R>(&mut self, range: R, k: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = simplify_range(range, self.len());
+        self.core.rotate_range_right(range, k);
+    }
+
+    /// Reverses the order of the key-value pairs in the given index range.
+    ///
+    /// The range may be any type that implements [`RangeBounds<usize>`],
+    /// including all of the `std::ops::Range*` types, or even a tuple pair of
+    /// `Bound` start and end values. Pairs outside the range are not moved.
+    ///
+    /// ***Panics*** if the range is out of bounds.
+    ///
+    /// Computes in **O(m)** time (average), where *m* is the length of the range.
+    pub fn reverse_range<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = simplify_range(range, self.len());
+        self.core.reverse_range(range);
+    }
+
+    /// Moves the key-value pairs in the given index range as a block, so that
+    /// the first of them ends up at index `to`, by shifting the other pairs
+    /// in-between.
+    ///
+    /// This is like [`move_index`][Self::move_index] for several adjacent
+    /// pairs at once, which keep their relative order.
+    ///
+    /// ***Panics*** if the range is out of bounds, or if `to` is greater than
+    /// the length of the map minus the length of the range.
+    ///
+    /// Computes in **O(m)** time (average), where *m* is the distance between
+    /// `to` and the start of the range plus the length of the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut map: IndexMap<_, _> = (0..6).map(|i| (i, ())).collect();
+    /// map.move_range(1..3, 3);
+    /// assert!(map.keys().eq(&[0, 3, 4, 1, 2, 5]));
+    /// map.move_range(3..5, 0);
+    /// assert!(map.keys().eq(&[1, 2, 0, 3, 4, 5]));
+    /// ```
+    pub fn move_range<R>(&mut self, range: R, to: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = simplify_range(range, self.len());
+        self.core.move_range(range, to);
+    }
 }
 
 /// Access [`IndexMap`] values corresponding to a key.
@@ -1078,10 +1909,12 @@ impl<K, V, S> IndexMap<K, V, S> {
 /// map.insert("foo", 1);
 /// println!("{:?}", map["bar"]); // panics!
 /// ```
-impl<K, V, Q: ?Sized, S> Index<&Q> for IndexMap<K, V, S>
+impl<K, V, Q: ?Sized, S, Idx, A> Index<&Q> for IndexMap<K, V, S, Idx, A>
 where
     Q: Hash + Equivalent<K>,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Output = V;
 
@@ -1122,10 +1955,12 @@ where
 /// map.insert("foo", 1);
 /// map["bar"] = 1; // panics!
 /// ```
-impl<K, V, Q: ?Sized, S> IndexMut<&Q> for IndexMap<K, V, S>
+impl<K, V, Q: ?Sized, S, Idx, A> IndexMut<&Q> for IndexMap<K, V, S, Idx, A>
 where
     Q: Hash + Equivalent<K>,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Returns a mutable reference to the value corresponding to the supplied `key`.
     ///
@@ -1167,7 +2002,7 @@ where
 /// map.insert("foo", 1);
 /// println!("{:?}", map[10]); // panics!
 /// ```
-impl<K, V, S> Index<usize> for IndexMap<K, V, S> {
+impl<K, V, S, Idx: IndexType, A: Allocator> Index<usize> for IndexMap<K, V, S, Idx, A> {
     type Output = V;
 
     /// Returns a reference to the value at the supplied `index`.
@@ -1209,7 +2044,7 @@ impl<K, V, S> Index<usize> for IndexMap<K, V, S> {
 /// map.insert("foo", 1);
 /// map[10] = 1; // panics!
 /// ```
-impl<K, V, S> IndexMut<usize> for IndexMap<K, V, S> {
+impl<K, V, S, Idx: IndexType, A: Allocator> IndexMut<usize> for IndexMap<K, V, S, Idx, A> {
     /// Returns a mutable reference to the value at the supplied `index`.
     ///
     /// ***Panics*** if `index` is out of bounds.
@@ -1220,10 +2055,47 @@ impl<K, V, S> IndexMut<usize> for IndexMap<K, V, S> {
     }
 }
 
-impl<K, V, S> FromIterator<(K, V)> for IndexMap<K, V, S>
+impl<K, V, S, Idx> IndexMap<K, V, S, Idx>
 where
     K: Hash + Eq,
     S: BuildHasher + Default,
+    Idx: IndexType,
+{
+    /// Create an `IndexMap` from the sequence of key-value pairs in the
+    /// iterable, failing on the first duplicate key.
+    ///
+    /// Unlike [`from_iter`][FromIterator::from_iter], where the last value of
+    /// a duplicate key prevails, this returns the rejected pair and the index
+    /// of its first occurrence. See [`try_extend`][IndexMap::try_extend] for
+    /// more details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let map: IndexMap<_, _> = IndexMap::try_from_iter([(1, 'a'), (2, 'b')]).unwrap();
+    /// assert_eq!(map.len(), 2);
+    ///
+    /// let result: Result<IndexMap<_, _>, _> = IndexMap::try_from_iter([(1, 'a'), (1, 'b')]);
+    /// let error = result.unwrap_err();
+    /// assert_eq!((error.index(), error.key(), error.value()), (0, &1, &'b'));
+    /// ```
+    pub fn try_from_iter<I>(iterable: I) -> Result<Self, DuplicateKey<K, V>>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let mut map = Self::with_hasher_idx(<_>::default());
+        map.try_extend(iterable)?;
+        Ok(map)
+    }
+}
+
+impl<K, V, S, Idx> FromIterator<(K, V)> for IndexMap<K, V, S, Idx>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+    Idx: IndexType,
 {
     /// Create an `IndexMap` from the sequence of key-value pairs in the
     /// iterable.
@@ -1233,7 +2105,7 @@ where
     fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
         let iter = iterable.into_iter();
         let (low, _) = iter.size_hint();
-        let mut map = Self::with_capacity_and_hasher(low, <_>::default());
+        let mut map = Self::with_capacity_and_hasher_idx(low, <_>::default());
         map.extend(iter);
         map
     }
@@ -1259,10 +2131,12 @@ where
     }
 }
 
-impl<K, V, S> Extend<(K, V)> for IndexMap<K, V, S>
+impl<K, V, S, Idx, A> Extend<(K, V)> for IndexMap<K, V, S, Idx, A>
 where
     K: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Extend the map with all key-value pairs in the iterable.
     ///
@@ -1274,29 +2148,21 @@ where
     /// equivalents of a key occur more than once, the last corresponding value
     /// prevails.
     fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
-        // (Note: this is a copy of `std`/`hashbrown`'s reservation logic.)
-        // Keys may be already present or show multiple times in the iterator.
-        // Reserve the entire hint lower bound if the map is empty.
-        // Otherwise reserve half the hint (rounded up), so the map
-        // will only resize twice in the worst case.
         let iter = iterable.into_iter();
-        let reserve = if self.is_empty() {
-            iter.size_hint().0
-        } else {
-            (iter.size_hint().0 + 1) / 2
-        };
-        self.reserve(reserve);
+        self.reserve_for_extend(iter.size_hint().0);
         iter.for_each(move |(k, v)| {
             self.insert(k, v);
         });
     }
 }
 
-impl<'a, K, V, S> Extend<(&'a K, &'a V)> for IndexMap<K, V, S>
+impl<'a, K, V, S, Idx, A> Extend<(&'a K, &'a V)> for IndexMap<K, V, S, Idx, A>
 where
     K: Hash + Eq + Copy,
     V: Copy,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Extend the map with all key-value pairs in the iterable.
     ///
@@ -1306,24 +2172,30 @@ where
     }
 }
 
-impl<K, V, S> Default for IndexMap<K, V, S>
+impl<K, V, S, Idx> Default for IndexMap<K, V, S, Idx>
 where
     S: Default,
+    Idx: IndexType,
 {
     /// Return an empty [`IndexMap`]
     fn default() -> Self {
-        Self::with_capacity_and_hasher(0, S::default())
+        Self::with_capacity_and_hasher_idx(0, S::default())
     }
 }
 
-impl<K, V1, S1, V2, S2> PartialEq<IndexMap<K, V2, S2>> for IndexMap<K, V1, S1>
+impl<K, V1, S1, Idx1, A1, V2, S2, Idx2, A2> PartialEq<IndexMap<K, V2, S2, Idx2, A2>>
+    for IndexMap<K, V1, S1, Idx1, A1>
 where
     K: Hash + Eq,
     V1: PartialEq<V2>,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
-    fn eq(&self, other: &IndexMap<K, V2, S2>) -> bool {
+    fn eq(&self, other: &IndexMap<K, V2, S2, Idx2, A2>) -> bool {
         if self.len() != other.len() {
             return false;
         }
@@ -1333,10 +2205,29 @@ where
     }
 }
 
-impl<K, V, S> Eq for IndexMap<K, V, S>
+impl<K, V, S, Idx, A> Eq for IndexMap<K, V, S, Idx, A>
 where
     K: Eq + Hash,
     V: Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
 }
+
+impl<K, V, S, Idx, A> Hash for IndexMap<K, V, S, Idx, A>
+where
+    K: Hash,
+    V: Hash,
+    Idx: IndexType,
+    A: Allocator,
+{
+    /// Hashes the key-value pairs without regard for their order, consistent
+    /// with the map's [`PartialEq`].
+    ///
+    /// Use [`Ordered`][crate::Ordered] to hash the map as a sequence instead.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        hash_unordered(self.iter(), state);
+    }
+}
@@ -0,0 +1,796 @@
+//! [`IndexBiMap`] is a one-to-one hash table between left and right values,
+//! where the iteration order of the pairs is independent of their hash values.
+
+use super::core::{erase_index, insert_hashes_no_grow, update_index};
+
+use crate::allocator::{Global, RawTable};
+use crate::{Equivalent, HashValue};
+use alloc::vec::{self, Vec};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter::FusedIterator;
+use core::slice;
+
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+
+/// A hash table of one-to-one pairs, which can be looked up by either side.
+///
+/// Every left value appears in at most one pair, and so does every right
+/// value. All of the pairs are stored once, in their insertion order, and
+/// two hash tables of indices find them by their left or their right value.
+/// This keeps both directions in sync through removal and sorting, unlike a
+/// pair of [`IndexMap`][crate::IndexMap]s maintained by hand.
+///
+/// The pairs are indexed in a compact range without holes in the range
+/// `0..self.len()`, just like an [`IndexMap`][crate::IndexMap].
+///
+/// # Examples
+///
+/// ```
+/// use indexmap::map::IndexBiMap;
+///
+/// let mut ids = IndexBiMap::new();
+/// ids.try_insert(17, "alpha").unwrap();
+/// ids.try_insert(4, "beta").unwrap();
+/// ids.try_insert(42, "gamma").unwrap();
+///
+/// assert_eq!(ids.get_by_left(&4), Some(&"beta"));
+/// assert_eq!(ids.get_by_right("gamma"), Some(&42));
+/// assert_eq!(ids.get_index(0), Some((&17, &"alpha")));
+///
+/// // Neither side may repeat.
+/// assert_eq!(ids.try_insert(4, "delta"), Err((4, "delta")));
+/// assert_eq!(ids.try_insert(5, "alpha"), Err((5, "alpha")));
+///
+/// assert_eq!(ids.shift_remove_by_right("alpha"), Some((17, "alpha")));
+/// assert!(ids.lefts().eq(&[4, 42]));
+/// assert_eq!(ids.get_index_of_right("gamma"), Some(1));
+/// ```
+#[cfg(feature = "std")]
+pub struct IndexBiMap<L, R, S = RandomState> {
+    left_indices: RawTable<usize, Global>,
+    right_indices: RawTable<usize, Global>,
+    entries: Vec<BiBucket<L, R>>,
+    hash_builder: S,
+}
+#[cfg(not(feature = "std"))]
+pub struct IndexBiMap<L, R, S> {
+    left_indices: RawTable<usize, Global>,
+    right_indices: RawTable<usize, Global>,
+    entries: Vec<BiBucket<L, R>>,
+    hash_builder: S,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct BiBucket<L, R> {
+    left_hash: HashValue,
+    right_hash: HashValue,
+    left: L,
+    right: R,
+}
+
+impl<L, R> BiBucket<L, R> {
+    fn refs(&self) -> (&L, &R) {
+        (&self.left, &self.right)
+    }
+
+    fn left_ref(&self) -> &L {
+        &self.left
+    }
+
+    fn right_ref(&self) -> &R {
+        &self.right
+    }
+
+    fn pair(self) -> (L, R) {
+        (self.left, self.right)
+    }
+}
+
+#[inline(always)]
+fn get_left_hash<L, R>(entries: &[BiBucket<L, R>]) -> impl Fn(&usize) -> u64 + '_ {
+    move |&i| entries[i].left_hash.get()
+}
+
+#[inline(always)]
+fn get_right_hash<L, R>(entries: &[BiBucket<L, R>]) -> impl Fn(&usize) -> u64 + '_ {
+    move |&i| entries[i].right_hash.get()
+}
+
+#[inline]
+fn left_equivalent<'a, L, R, Q: ?Sized + Equivalent<L>>(
+    left: &'a Q,
+    entries: &'a [BiBucket<L, R>],
+) -> impl Fn(&usize) -> bool + 'a {
+    move |&i| Q::equivalent(left, &entries[i].left)
+}
+
+#[inline]
+fn right_equivalent<'a, L, R, Q: ?Sized + Equivalent<R>>(
+    right: &'a Q,
+    entries: &'a [BiBucket<L, R>],
+) -> impl Fn(&usize) -> bool + 'a {
+    move |&i| Q::equivalent(right, &entries[i].right)
+}
+
+impl<L, R, S> Clone for IndexBiMap<L, R, S>
+where
+    L: Clone,
+    R: Clone,
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        IndexBiMap {
+            left_indices: self.left_indices.clone(),
+            right_indices: self.right_indices.clone(),
+            entries: self.entries.clone(),
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+}
+
+impl<L, R, S> fmt::Debug for IndexBiMap<L, R, S>
+where
+    L: fmt::Debug,
+    R: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<L, R> IndexBiMap<L, R> {
+    /// Create a new bimap. (Does not allocate.)
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create a new bimap with capacity for `n` pairs.
+    /// (Does not allocate if `n` is zero.)
+    ///
+    /// Computes in **O(n)** time.
+    #[inline]
+    pub fn with_capacity(n: usize) -> Self {
+        Self::with_capacity_and_hasher(n, <_>::default())
+    }
+}
+
+impl<L, R, S> IndexBiMap<L, R, S> {
+    /// Create a new bimap with capacity for `n` pairs.
+    /// (Does not allocate if `n` is zero.)
+    ///
+    /// Computes in **O(n)** time.
+    #[inline]
+    pub fn with_capacity_and_hasher(n: usize, hash_builder: S) -> Self {
+        if n == 0 {
+            Self::with_hasher(hash_builder)
+        } else {
+            IndexBiMap {
+                left_indices: RawTable::with_capacity_in(n, Global),
+                right_indices: RawTable::with_capacity_in(n, Global),
+                entries: Vec::with_capacity(n),
+                hash_builder,
+            }
+        }
+    }
+
+    /// Create a new bimap with `hash_builder`.
+    ///
+    /// This function is `const`, so it
+    /// can be called in `static` contexts.
+    pub const fn with_hasher(hash_builder: S) -> Self {
+        IndexBiMap {
+            left_indices: RawTable::new(),
+            right_indices: RawTable::new(),
+            entries: Vec::new(),
+            hash_builder,
+        }
+    }
+
+    /// Return the number of pairs the bimap can hold without reallocating.
+    ///
+    /// This number is a lower bound; the bimap might be able to hold more,
+    /// but is guaranteed to be able to hold at least this many.
+    ///
+    /// Computes in **O(1)** time.
+    pub fn capacity(&self) -> usize {
+        Ord::min(
+            self.entries.capacity(),
+            Ord::min(self.left_indices.capacity(), self.right_indices.capacity()),
+        )
+    }
+
+    /// Return a reference to the bimap's `BuildHasher`.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Return the number of pairs in the bimap.
+    ///
+    /// Computes in **O(1)** time.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the bimap contains no pairs.
+    ///
+    /// Computes in **O(1)** time.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Return an iterator over the pairs of the bimap, in their order
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// Return an iterator over the left values of the bimap, in their order
+    pub fn lefts(&self) -> Lefts<'_, L, R> {
+        Lefts {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// Return an iterator over the right values of the bimap, in their order
+    pub fn rights(&self) -> Rights<'_, L, R> {
+        Rights {
+            iter: self.entries.iter(),
+        }
+    }
+
+    /// Remove all pairs in the bimap, while preserving its capacity.
+    ///
+    /// Computes in **O(n)** time.
+    pub fn clear(&mut self) {
+        self.left_indices.clear();
+        self.right_indices.clear();
+        self.entries.clear();
+    }
+
+    /// Get a pair by index
+    ///
+    /// Valid indices are *0 <= index < self.len()*
+    ///
+    /// Computes in **O(1)** time.
+    pub fn get_index(&self, index: usize) -> Option<(&L, &R)> {
+        self.entries.get(index).map(BiBucket::refs)
+    }
+
+    /// Get the first pair
+    ///
+    /// Computes in **O(1)** time.
+    pub fn first(&self) -> Option<(&L, &R)> {
+        self.entries.first().map(BiBucket::refs)
+    }
+
+    /// Get the last pair
+    ///
+    /// Computes in **O(1)** time.
+    pub fn last(&self) -> Option<(&L, &R)> {
+        self.entries.last().map(BiBucket::refs)
+    }
+
+    /// Remove the pair by index
+    ///
+    /// Valid indices are *0 <= index < self.len()*
+    ///
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is
+    /// removed by swapping it with the last element of the bimap and popping
+    /// it off. **This perturbs the position of what used to be the last
+    /// element!**
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(L, R)> {
+        let entry = self.entries.get(index)?;
+        erase_index(&mut self.left_indices, entry.left_hash, index);
+        erase_index(&mut self.right_indices, entry.right_hash, index);
+        let last = self.entries.len() - 1;
+        let entry = self.entries.swap_remove(index);
+        if let Some(moved) = self.entries.get(index) {
+            // Point the indices of the swapped pair at its new position.
+            update_index(&mut self.left_indices, moved.left_hash, last, index);
+            update_index(&mut self.right_indices, moved.right_hash, last, index);
+        }
+        Some(entry.pair())
+    }
+
+    /// Remove the pair by index
+    ///
+    /// Valid indices are *0 <= index < self.len()*
+    ///
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by
+    /// shifting all of the elements that follow it, preserving their relative
+    /// order. **This perturbs the index of all of those elements!**
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(L, R)> {
+        let entry = self.entries.get(index)?;
+        erase_index(&mut self.left_indices, entry.left_hash, index);
+        erase_index(&mut self.right_indices, entry.right_hash, index);
+        for (i, moved) in self.entries.iter().enumerate().skip(index + 1) {
+            update_index(&mut self.left_indices, moved.left_hash, i, i - 1);
+            update_index(&mut self.right_indices, moved.right_hash, i, i - 1);
+        }
+        Some(self.entries.remove(index).pair())
+    }
+
+    /// Remove the last pair
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn pop(&mut self) -> Option<(L, R)> {
+        let entry = self.entries.pop()?;
+        let last = self.entries.len();
+        erase_index(&mut self.left_indices, entry.left_hash, last);
+        erase_index(&mut self.right_indices, entry.right_hash, last);
+        Some(entry.pair())
+    }
+
+    /// Scan through each pair in the bimap and keep those where the
+    /// closure `keep` returns `true`.
+    ///
+    /// The elements are visited in order, and remaining elements keep their
+    /// order.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&L, &R) -> bool,
+    {
+        let len = self.entries.len();
+        self.entries.retain(|entry| keep(&entry.left, &entry.right));
+        if self.entries.len() < len {
+            self.rebuild_hash_tables();
+        }
+    }
+
+    /// Sort the bimap's pairs in place using the comparison function `cmp`.
+    ///
+    /// The comparison function receives two left and right value pairs to
+    /// compare (you can sort by either side or both).
+    ///
+    /// Computes in **O(n log n + c)** time and **O(n)** space where *n* is
+    /// the length of the bimap and *c* the capacity. The sort is stable.
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&L, &R, &L, &R) -> Ordering,
+    {
+        self.entries
+            .sort_by(move |a, b| cmp(&a.left, &a.right, &b.left, &b.right));
+        self.rebuild_hash_tables();
+    }
+
+    /// Sort the bimap's pairs in place using the comparison function `cmp`,
+    /// but may not preserve the order of equal elements.
+    ///
+    /// Computes in **O(n log n + c)** time where *n* is
+    /// the length of the bimap and *c* is the capacity. The sort is unstable.
+    pub fn sort_unstable_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&L, &R, &L, &R) -> Ordering,
+    {
+        self.entries
+            .sort_unstable_by(move |a, b| cmp(&a.left, &a.right, &b.left, &b.right));
+        self.rebuild_hash_tables();
+    }
+
+    /// Refill both hash tables after the pairs have been moved around.
+    fn rebuild_hash_tables(&mut self) {
+        self.left_indices.clear();
+        self.right_indices.clear();
+        let entries = &self.entries;
+        insert_hashes_no_grow(&mut self.left_indices, entries.iter().map(|e| e.left_hash));
+        insert_hashes_no_grow(
+            &mut self.right_indices,
+            entries.iter().map(|e| e.right_hash),
+        );
+    }
+}
+
+impl<L, R, S> IndexBiMap<L, R, S>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    S: BuildHasher,
+{
+    fn hash<Q: ?Sized + Hash>(&self, value: &Q) -> HashValue {
+        let mut h = self.hash_builder.build_hasher();
+        value.hash(&mut h);
+        HashValue(h.finish() as usize)
+    }
+
+    /// Reserve capacity for `additional` more pairs.
+    ///
+    /// Computes in **O(n)** time.
+    pub fn reserve(&mut self, additional: usize) {
+        self.left_indices
+            .reserve(additional, get_left_hash(&self.entries));
+        self.right_indices
+            .reserve(additional, get_right_hash(&self.entries));
+        self.entries.reserve(additional);
+    }
+
+    /// Insert the pair `(left, right)` at the end of the bimap, and return
+    /// its index.
+    ///
+    /// If either `left` or `right` is already present in some pair, the bimap
+    /// is left unchanged and the new pair is returned in `Err`, so that no
+    /// existing association is silently broken.
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn try_insert(&mut self, left: L, right: R) -> Result<usize, (L, R)> {
+        let left_hash = self.hash(&left);
+        let right_hash = self.hash(&right);
+        let entries = &*self.entries;
+        if self
+            .left_indices
+            .get(left_hash.get(), left_equivalent(&left, entries))
+            .is_some()
+            || self
+                .right_indices
+                .get(right_hash.get(), right_equivalent(&right, entries))
+                .is_some()
+        {
+            return Err((left, right));
+        }
+
+        let index = self.entries.len();
+        self.left_indices
+            .insert(left_hash.get(), index, get_left_hash(&self.entries));
+        self.right_indices
+            .insert(right_hash.get(), index, get_right_hash(&self.entries));
+        self.entries.push(BiBucket {
+            left_hash,
+            right_hash,
+            left,
+            right,
+        });
+        Ok(index)
+    }
+
+    /// Return `true` if a pair with this left value is present.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Equivalent<L>,
+    {
+        self.get_index_of_left(left).is_some()
+    }
+
+    /// Return `true` if a pair with this right value is present.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Equivalent<R>,
+    {
+        self.get_index_of_right(right).is_some()
+    }
+
+    /// Return the index of the pair with this left value, if it is present.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_index_of_left<Q>(&self, left: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Hash + Equivalent<L>,
+    {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let hash = self.hash(left);
+        let eq = left_equivalent(left, &self.entries);
+        self.left_indices.get(hash.get(), eq).copied()
+    }
+
+    /// Return the index of the pair with this right value, if it is present.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_index_of_right<Q>(&self, right: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Hash + Equivalent<R>,
+    {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let hash = self.hash(right);
+        let eq = right_equivalent(right, &self.entries);
+        self.right_indices.get(hash.get(), eq).copied()
+    }
+
+    /// Return a reference to the right value paired with this left value,
+    /// if it is present.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        Q: ?Sized + Hash + Equivalent<L>,
+    {
+        let i = self.get_index_of_left(left)?;
+        Some(&self.entries[i].right)
+    }
+
+    /// Return a reference to the left value paired with this right value,
+    /// if it is present.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        Q: ?Sized + Hash + Equivalent<R>,
+    {
+        let i = self.get_index_of_right(right)?;
+        Some(&self.entries[i].left)
+    }
+
+    /// Remove the pair with this left value and return it, if it is present.
+    ///
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], this perturbs
+    /// the position of what used to be the last pair.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn swap_remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        Q: ?Sized + Hash + Equivalent<L>,
+    {
+        let i = self.get_index_of_left(left)?;
+        self.swap_remove_index(i)
+    }
+
+    /// Remove the pair with this right value and return it, if it is present.
+    ///
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], this perturbs
+    /// the position of what used to be the last pair.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn swap_remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        Q: ?Sized + Hash + Equivalent<R>,
+    {
+        let i = self.get_index_of_right(right)?;
+        self.swap_remove_index(i)
+    }
+
+    /// Remove the pair with this left value and return it, if it is present.
+    ///
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], this preserves the
+    /// order of the remaining pairs.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn shift_remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        Q: ?Sized + Hash + Equivalent<L>,
+    {
+        let i = self.get_index_of_left(left)?;
+        self.shift_remove_index(i)
+    }
+
+    /// Remove the pair with this right value and return it, if it is present.
+    ///
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], this preserves the
+    /// order of the remaining pairs.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn shift_remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        Q: ?Sized + Hash + Equivalent<R>,
+    {
+        let i = self.get_index_of_right(right)?;
+        self.shift_remove_index(i)
+    }
+}
+
+impl<'a, L, R, S> IntoIterator for &'a IndexBiMap<L, R, S> {
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<L, R, S> IntoIterator for IndexBiMap<L, R, S> {
+    type Item = (L, R);
+    type IntoIter = IntoIter<L, R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            iter: self.entries.into_iter(),
+        }
+    }
+}
+
+impl<L, R, S> Default for IndexBiMap<L, R, S>
+where
+    S: Default,
+{
+    /// Return an empty [`IndexBiMap`]
+    fn default() -> Self {
+        Self::with_capacity_and_hasher(0, S::default())
+    }
+}
+
+impl<L, R, S1, S2> PartialEq<IndexBiMap<L, R, S2>> for IndexBiMap<L, R, S1>
+where
+    L: Hash + Eq,
+    R: Hash + Eq,
+    S1: BuildHasher,
+    S2: BuildHasher,
+{
+    fn eq(&self, other: &IndexBiMap<L, R, S2>) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        self.iter()
+            .all(|(left, right)| other.get_by_left(left).map_or(false, |r| *right == *r))
+    }
+}
+
+impl<L, R, S> Eq for IndexBiMap<L, R, S>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    S: BuildHasher,
+{
+}
+
+/// An iterator over the pairs of an [`IndexBiMap`].
+///
+/// This `struct` is created by the [`IndexBiMap::iter`] method.
+/// See its documentation for more.
+pub struct Iter<'a, L, R> {
+    iter: slice::Iter<'a, BiBucket<L, R>>,
+}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    iterator_methods!(BiBucket::refs);
+}
+
+impl<L, R> DoubleEndedIterator for Iter<'_, L, R> {
+    double_ended_iterator_methods!(BiBucket::refs);
+}
+
+impl<L, R> ExactSizeIterator for Iter<'_, L, R> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<L, R> FusedIterator for Iter<'_, L, R> {}
+
+impl<L, R> Clone for Iter<'_, L, R> {
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<L: fmt::Debug, R: fmt::Debug> fmt::Debug for Iter<'_, L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the left values of an [`IndexBiMap`].
+///
+/// This `struct` is created by the [`IndexBiMap::lefts`] method.
+/// See its documentation for more.
+pub struct Lefts<'a, L, R> {
+    iter: slice::Iter<'a, BiBucket<L, R>>,
+}
+
+impl<'a, L, R> Iterator for Lefts<'a, L, R> {
+    type Item = &'a L;
+
+    iterator_methods!(BiBucket::left_ref);
+}
+
+impl<L, R> DoubleEndedIterator for Lefts<'_, L, R> {
+    double_ended_iterator_methods!(BiBucket::left_ref);
+}
+
+impl<L, R> ExactSizeIterator for Lefts<'_, L, R> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<L, R> FusedIterator for Lefts<'_, L, R> {}
+
+impl<L, R> Clone for Lefts<'_, L, R> {
+    fn clone(&self) -> Self {
+        Lefts {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<L: fmt::Debug, R> fmt::Debug for Lefts<'_, L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the right values of an [`IndexBiMap`].
+///
+/// This `struct` is created by the [`IndexBiMap::rights`] method.
+/// See its documentation for more.
+pub struct Rights<'a, L, R> {
+    iter: slice::Iter<'a, BiBucket<L, R>>,
+}
+
+impl<'a, L, R> Iterator for Rights<'a, L, R> {
+    type Item = &'a R;
+
+    iterator_methods!(BiBucket::right_ref);
+}
+
+impl<L, R> DoubleEndedIterator for Rights<'_, L, R> {
+    double_ended_iterator_methods!(BiBucket::right_ref);
+}
+
+impl<L, R> ExactSizeIterator for Rights<'_, L, R> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<L, R> FusedIterator for Rights<'_, L, R> {}
+
+impl<L, R> Clone for Rights<'_, L, R> {
+    fn clone(&self) -> Self {
+        Rights {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<L, R: fmt::Debug> fmt::Debug for Rights<'_, L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An owning iterator over the pairs of an [`IndexBiMap`].
+///
+/// This `struct` is created by the [`IndexBiMap::into_iter`] method
+/// (provided by the [`IntoIterator`] trait). See its documentation for more.
+pub struct IntoIter<L, R> {
+    iter: vec::IntoIter<BiBucket<L, R>>,
+}
+
+impl<L, R> Iterator for IntoIter<L, R> {
+    type Item = (L, R);
+
+    iterator_methods!(BiBucket::pair);
+}
+
+impl<L, R> DoubleEndedIterator for IntoIter<L, R> {
+    double_ended_iterator_methods!(BiBucket::pair);
+}
+
+impl<L, R> ExactSizeIterator for IntoIter<L, R> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<L, R> FusedIterator for IntoIter<L, R> {}
+
+impl<L: fmt::Debug, R: fmt::Debug> fmt::Debug for IntoIter<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let iter = self.iter.as_slice().iter().map(BiBucket::refs);
+        f.debug_list().entries(iter).finish()
+    }
+}
@@ -12,61 +12,71 @@ mod raw;
 
 pub mod raw_entry_v1;
 
-use hashbrown::raw::RawTable;
-
-use crate::vec::{self, Vec};
+use crate::allocator::{self, Allocator, Global, RawTable, Vec};
 use crate::TryReserveError;
 use core::fmt;
 use core::mem;
-use core::ops::RangeBounds;
+use core::ops::{Range, RangeBounds};
 
 use crate::util::simplify_range;
-use crate::{Bucket, Entries, Equivalent, HashValue};
+use crate::{Bucket, Entries, Equivalent, HashValue, IndexType};
 
 pub use entry::{Entry, IndexedEntry, OccupiedEntry, VacantEntry};
+pub(super) use raw::insert_hashes_no_grow;
 
 /// Core of the map that does not depend on S
-pub(crate) struct IndexMapCore<K, V> {
+pub(crate) struct IndexMapCore<K, V, Idx = usize, A: Allocator = Global> {
     /// indices mapping from the entry hash to its index.
-    indices: RawTable<usize>,
+    indices: RawTable<Idx, A>,
     /// entries is a dense vec of entries in their order.
-    entries: Vec<Bucket<K, V>>,
+    entries: Vec<Bucket<K, V>, A>,
 }
 
 #[inline(always)]
-fn get_hash<K, V>(entries: &[Bucket<K, V>]) -> impl Fn(&usize) -> u64 + '_ {
-    move |&i| entries[i].hash.get()
+fn get_hash<K, V, Idx: IndexType>(entries: &[Bucket<K, V>]) -> impl Fn(&Idx) -> u64 + '_ {
+    move |&i| entries[i.into_usize()].hash.get()
 }
 
 #[inline]
-fn equivalent<'a, K, V, Q: ?Sized + Equivalent<K>>(
+fn equivalent<'a, K, V, Idx: IndexType, Q: ?Sized + Equivalent<K>>(
     key: &'a Q,
     entries: &'a [Bucket<K, V>],
-) -> impl Fn(&usize) -> bool + 'a {
-    move |&i| Q::equivalent(key, &entries[i].key)
+) -> impl Fn(&Idx) -> bool + 'a {
+    move |&i| Q::equivalent(key, &entries[i.into_usize()].key)
 }
 
 #[inline]
-fn erase_index(table: &mut RawTable<usize>, hash: HashValue, index: usize) {
-    let erased = table.erase_entry(hash.get(), move |&i| i == index);
+pub(super) fn erase_index<Idx: IndexType, A: Allocator>(
+    table: &mut RawTable<Idx, A>,
+    hash: HashValue,
+    index: usize,
+) {
+    let erased = table.erase_entry(hash.get(), move |&i| i.into_usize() == index);
     debug_assert!(erased);
 }
 
 #[inline]
-fn update_index(table: &mut RawTable<usize>, hash: HashValue, old: usize, new: usize) {
+pub(super) fn update_index<Idx: IndexType, A: Allocator>(
+    table: &mut RawTable<Idx, A>,
+    hash: HashValue,
+    old: usize,
+    new: usize,
+) {
     let index = table
-        .get_mut(hash.get(), move |&i| i == old)
+        .get_mut(hash.get(), move |&i| i.into_usize() == old)
         .expect("index not found");
-    *index = new;
+    *index = Idx::from_usize(new);
 }
 
-impl<K, V> Clone for IndexMapCore<K, V>
+impl<K, V, Idx, A> Clone for IndexMapCore<K, V, Idx, A>
 where
     K: Clone,
     V: Clone,
+    Idx: IndexType,
+    A: Allocator + Clone,
 {
     fn clone(&self) -> Self {
-        let mut new = Self::new();
+        let mut new = Self::new_in(self.entries.allocator().clone());
         new.clone_from(self);
         new
     }
@@ -83,10 +93,12 @@ where
     }
 }
 
-impl<K, V> fmt::Debug for IndexMapCore<K, V>
+impl<K, V, Idx, A> fmt::Debug for IndexMapCore<K, V, Idx, A>
 where
     K: fmt::Debug,
     V: fmt::Debug,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("IndexMapCore")
@@ -96,11 +108,12 @@ where
     }
 }
 
-impl<K, V> Entries for IndexMapCore<K, V> {
+impl<K, V, Idx: IndexType, A: Allocator> Entries for IndexMapCore<K, V, Idx, A> {
     type Entry = Bucket<K, V>;
+    type Alloc = A;
 
     #[inline]
-    fn into_entries(self) -> Vec<Self::Entry> {
+    fn into_entries(self) -> Vec<Self::Entry, A> {
         self.entries
     }
 
@@ -123,10 +136,7 @@ impl<K, V> Entries for IndexMapCore<K, V> {
     }
 }
 
-impl<K, V> IndexMapCore<K, V> {
-    /// The maximum capacity before the `entries` allocation would exceed `isize::MAX`.
-    const MAX_ENTRIES_CAPACITY: usize = (isize::MAX as usize) / mem::size_of::<Bucket<K, V>>();
-
+impl<K, V, Idx: IndexType> IndexMapCore<K, V, Idx> {
     #[inline]
     pub(crate) const fn new() -> Self {
         IndexMapCore {
@@ -137,12 +147,64 @@ impl<K, V> IndexMapCore<K, V> {
 
     #[inline]
     pub(crate) fn with_capacity(n: usize) -> Self {
+        Self::with_capacity_in(n, Global)
+    }
+
+    /// Create a map from entries with precomputed hashes, without checking
+    /// that the hashes are correct or that the keys are unique.
+    ///
+    /// ***Panics*** if the entries can't be indexed by `Idx`.
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_entries_unchecked(entries: Vec<Bucket<K, V>, Global>) -> Self {
+        if entries.len() > Idx::MAX_LEN {
+            index_capacity_overflow();
+        }
+        let mut indices = RawTable::with_capacity_in(entries.len(), Global);
+        raw::insert_bulk_no_grow(&mut indices, &entries);
+        IndexMapCore { indices, entries }
+    }
+}
+
+impl<K, V, Idx: IndexType, A: Allocator> IndexMapCore<K, V, Idx, A> {
+    /// The maximum capacity before the `entries` allocation would exceed `isize::MAX`,
+    /// or the entries could not be indexed by `Idx`.
+    const MAX_ENTRIES_CAPACITY: usize = {
+        let max = (isize::MAX as usize) / mem::size_of::<Bucket<K, V>>();
+        if max < Idx::MAX_LEN {
+            max
+        } else {
+            Idx::MAX_LEN
+        }
+    };
+
+    #[inline]
+    pub(crate) fn new_in(alloc: A) -> Self
+    where
+        A: Clone,
+    {
         IndexMapCore {
-            indices: RawTable::with_capacity(n),
-            entries: Vec::with_capacity(n),
+            indices: RawTable::new_in(alloc.clone()),
+            entries: Vec::new_in(alloc),
         }
     }
 
+    #[inline]
+    pub(crate) fn with_capacity_in(n: usize, alloc: A) -> Self
+    where
+        A: Clone,
+    {
+        IndexMapCore {
+            indices: RawTable::with_capacity_in(n, alloc.clone()),
+            entries: Vec::with_capacity_in(n, alloc),
+        }
+    }
+
+    #[cfg(feature = "allocator-api2")]
+    #[inline]
+    pub(crate) fn allocator(&self) -> &A {
+        self.entries.allocator()
+    }
+
     #[inline]
     pub(crate) fn len(&self) -> usize {
         self.indices.len()
@@ -150,7 +212,22 @@ impl<K, V> IndexMapCore<K, V> {
 
     #[inline]
     pub(crate) fn capacity(&self) -> usize {
-        Ord::min(self.indices.capacity(), self.entries.capacity())
+        let capacity = Ord::min(self.indices.capacity(), self.entries.capacity());
+        Ord::min(capacity, Idx::MAX_LEN)
+    }
+
+    /// Return whether `additional` more entries can be indexed by `Idx`.
+    #[inline]
+    fn has_index_capacity(&self, additional: usize) -> bool {
+        additional <= Idx::MAX_LEN - self.indices.len()
+    }
+
+    /// ***Panics*** if `additional` more entries can't be indexed by `Idx`.
+    #[inline]
+    fn assert_index_capacity(&self, additional: usize) {
+        if !self.has_index_capacity(additional) {
+            index_capacity_overflow();
+        }
     }
 
     pub(crate) fn clear(&mut self) {
@@ -165,7 +242,7 @@ impl<K, V> IndexMapCore<K, V> {
         }
     }
 
-    pub(crate) fn drain<R>(&mut self, range: R) -> vec::Drain<'_, Bucket<K, V>>
+    pub(crate) fn drain<R>(&mut self, range: R) -> allocator::Drain<'_, Bucket<K, V>, A>
     where
         R: RangeBounds<usize>,
     {
@@ -175,38 +252,46 @@ impl<K, V> IndexMapCore<K, V> {
     }
 
     #[cfg(feature = "rayon")]
-    pub(crate) fn par_drain<R>(&mut self, range: R) -> rayon::vec::Drain<'_, Bucket<K, V>>
+    pub(crate) fn par_drain<R>(&mut self, range: R) -> allocator::ParDrain<'_, Bucket<K, V>>
     where
         K: Send,
         V: Send,
         R: RangeBounds<usize>,
     {
-        use rayon::iter::ParallelDrainRange;
         let range = simplify_range(range, self.entries.len());
         self.erase_indices(range.start, range.end);
-        self.entries.par_drain(range)
+        allocator::par_drain(&mut self.entries, range)
     }
 
-    pub(crate) fn split_off(&mut self, at: usize) -> Self {
+    pub(crate) fn split_off(&mut self, at: usize) -> Self
+    where
+        A: Clone,
+    {
         assert!(at <= self.entries.len());
         self.erase_indices(at, self.entries.len());
         let entries = self.entries.split_off(at);
 
-        let mut indices = RawTable::with_capacity(entries.len());
+        let alloc = entries.allocator().clone();
+        let mut indices = RawTable::with_capacity_in(entries.len(), alloc);
         raw::insert_bulk_no_grow(&mut indices, &entries);
         Self { indices, entries }
     }
 
-    pub(crate) fn split_splice<R>(&mut self, range: R) -> (Self, vec::IntoIter<Bucket<K, V>>)
+    pub(crate) fn split_splice<R>(
+        &mut self,
+        range: R,
+    ) -> (Self, allocator::IntoIter<Bucket<K, V>, A>)
     where
         R: RangeBounds<usize>,
+        A: Clone,
     {
         let range = simplify_range(range, self.len());
         self.erase_indices(range.start, self.entries.len());
         let entries = self.entries.split_off(range.end);
         let drained = self.entries.split_off(range.start);
 
-        let mut indices = RawTable::with_capacity(entries.len());
+        let alloc = entries.allocator().clone();
+        let mut indices = RawTable::with_capacity_in(entries.len(), alloc);
         raw::insert_bulk_no_grow(&mut indices, &entries);
         (Self { indices, entries }, drained.into_iter())
     }
@@ -221,6 +306,7 @@ impl<K, V> IndexMapCore<K, V> {
 
     /// Reserve capacity for `additional` more key-value pairs.
     pub(crate) fn reserve(&mut self, additional: usize) {
+        self.assert_index_capacity(additional);
         self.indices.reserve(additional, get_hash(&self.entries));
         // Only grow entries if necessary, since we also round up capacity.
         if additional > self.entries.capacity() - self.entries.len() {
@@ -242,12 +328,16 @@ impl<K, V> IndexMapCore<K, V> {
 
     /// Reserve capacity for `additional` more key-value pairs, without over-allocating.
     pub(crate) fn reserve_exact(&mut self, additional: usize) {
+        self.assert_index_capacity(additional);
         self.indices.reserve(additional, get_hash(&self.entries));
         self.entries.reserve_exact(additional);
     }
 
     /// Try to reserve capacity for `additional` more key-value pairs.
     pub(crate) fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if !self.has_index_capacity(additional) {
+            return Err(TryReserveError::capacity_overflow());
+        }
         self.indices
             .try_reserve(additional, get_hash(&self.entries))
             .map_err(TryReserveError::from_hashbrown)?;
@@ -275,6 +365,9 @@ impl<K, V> IndexMapCore<K, V> {
 
     /// Try to reserve capacity for `additional` more key-value pairs, without over-allocating.
     pub(crate) fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if !self.has_index_capacity(additional) {
+            return Err(TryReserveError::capacity_overflow());
+        }
         self.indices
             .try_reserve(additional, get_hash(&self.entries))
             .map_err(TryReserveError::from_hashbrown)?;
@@ -311,13 +404,39 @@ impl<K, V> IndexMapCore<K, V> {
         self.entries.push(Bucket { hash, key, value });
     }
 
+    /// Insert a key-value pair in `entries` at a particular index,
+    /// *without* checking whether it already exists.
+    fn shift_insert_unique(&mut self, index: usize, hash: HashValue, key: K, value: V) {
+        let end = self.indices.len();
+        assert!(index <= end);
+        self.assert_index_capacity(1);
+        // Increment others first so we don't have duplicate indices.
+        self.increment_indices(index, end);
+        let entries = &*self.entries;
+        self.indices
+            .insert(hash.get(), Idx::from_usize(index), move |&i| {
+                // Adjust for the incremented indices to find hashes.
+                let i = i.into_usize();
+                debug_assert_ne!(i, index);
+                let i = if i < index { i } else { i - 1 };
+                entries[i].hash.get()
+            });
+        if self.entries.len() == self.entries.capacity() {
+            // Reserve our own capacity synced to the indices,
+            // rather than letting `Vec::insert` just double it.
+            self.reserve_entries(1);
+        }
+        self.entries.insert(index, Bucket { hash, key, value });
+    }
+
     /// Return the index in `entries` where an equivalent key can be found
     pub(crate) fn get_index_of<Q>(&self, hash: HashValue, key: &Q) -> Option<usize>
     where
         Q: ?Sized + Equivalent<K>,
     {
         let eq = equivalent(key, &self.entries);
-        self.indices.get(hash.get(), eq).copied()
+        let i = self.indices.get(hash.get(), eq)?;
+        Some(i.into_usize())
     }
 
     pub(crate) fn insert_full(&mut self, hash: HashValue, key: K, value: V) -> (usize, Option<V>)
@@ -334,6 +453,77 @@ impl<K, V> IndexMapCore<K, V> {
         }
     }
 
+    /// Append key-value pairs whose keys are expected to be new, failing at
+    /// the first key that is already present.
+    ///
+    /// The pairs are collected into `entries` first, and then indexed with a
+    /// single reservation and without growing the table further. If a key is
+    /// already present, that pair and the rest are removed again, and returned
+    /// with the index of the existing key.
+    pub(crate) fn extend_unique<I>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), (usize, alloc::vec::Vec<Bucket<K, V>>)>
+    where
+        I: IntoIterator<Item = Bucket<K, V>>,
+        K: Eq,
+    {
+        /// Drops the entries that aren't indexed, in case of a panic.
+        struct Guard<'a, K, V, Idx: IndexType, A: Allocator>(&'a mut IndexMapCore<K, V, Idx, A>);
+
+        impl<K, V, Idx: IndexType, A: Allocator> Drop for Guard<'_, K, V, Idx, A> {
+            fn drop(&mut self) {
+                let len = self.0.indices.len();
+                self.0.entries.truncate(len);
+            }
+        }
+
+        let guard = Guard(self);
+        let map = &mut *guard.0;
+        map.entries.extend(iter);
+        let additional = map.entries.len() - map.indices.len();
+        map.assert_index_capacity(additional);
+        map.indices.reserve(additional, get_hash(&map.entries));
+        match raw::insert_bulk_unique_no_grow(&mut map.indices, &map.entries) {
+            Ok(()) => Ok(()),
+            Err(i) => {
+                let start = map.indices.len();
+                Err((i, map.entries.drain(start..).collect()))
+            }
+        }
+    }
+
+    /// Append a key-value pair if no equivalent key exists yet, and return its
+    /// index, or else return the index of the existing key with the new pair.
+    pub(crate) fn insert_new(
+        &mut self,
+        hash: HashValue,
+        key: K,
+        value: V,
+    ) -> Result<usize, (usize, K, V)>
+    where
+        K: Eq,
+    {
+        match self.find_or_insert(hash, &key) {
+            Ok(i) => Err((i, key, value)),
+            Err(i) => {
+                debug_assert_eq!(i, self.entries.len());
+                self.push_entry(hash, key, value);
+                Ok(i)
+            }
+        }
+    }
+
+    /// Append a key-value pair, *without* checking whether an equivalent key
+    /// already exists, and return its index.
+    ///
+    /// Lookups by key will find an arbitrary one of any duplicates.
+    pub(crate) fn push(&mut self, hash: HashValue, key: K, value: V) -> usize {
+        let i = self.entries.len();
+        self.insert_unique(hash, key, value);
+        i
+    }
+
     /// Same as `insert_full`, except it also replaces the key
     pub(crate) fn replace_full(
         &mut self,
@@ -369,6 +559,7 @@ impl<K, V> IndexMapCore<K, V> {
         let eq = equivalent(key, &self.entries);
         match self.indices.remove_entry(hash.get(), eq) {
             Some(index) => {
+                let index = index.into_usize();
                 let (key, value) = self.shift_remove_finish(index);
                 Some((index, key, value))
             }
@@ -409,8 +600,9 @@ impl<K, V> IndexMapCore<K, V> {
         if shifted_entries.len() > self.indices.buckets() / 2 {
             // Shift all indices in range.
             for i in self.indices_mut() {
-                if start <= *i && *i < end {
-                    *i -= 1;
+                let index = i.into_usize();
+                if start <= index && index < end {
+                    *i = Idx::from_usize(index - 1);
                 }
             }
         } else {
@@ -431,8 +623,9 @@ impl<K, V> IndexMapCore<K, V> {
         if shifted_entries.len() > self.indices.buckets() / 2 {
             // Shift all indices in range.
             for i in self.indices_mut() {
-                if start <= *i && *i < end {
-                    *i += 1;
+                let index = i.into_usize();
+                if start <= index && index < end {
+                    *i = Idx::from_usize(index + 1);
                 }
             }
         } else {
@@ -448,7 +641,7 @@ impl<K, V> IndexMapCore<K, V> {
         let from_hash = self.entries[from].hash;
         if from != to {
             // Use a sentinel index so other indices don't collide.
-            update_index(&mut self.indices, from_hash, from, usize::MAX);
+            update_index(&mut self.indices, from_hash, from, Idx::MAX_LEN);
 
             // Update all other indices and rotate the entry positions.
             if from < to {
@@ -460,7 +653,7 @@ impl<K, V> IndexMapCore<K, V> {
             }
 
             // Change the sentinel index to its final position.
-            update_index(&mut self.indices, from_hash, usize::MAX, to);
+            update_index(&mut self.indices, from_hash, Idx::MAX_LEN, to);
         }
     }
 
@@ -476,7 +669,14 @@ impl<K, V> IndexMapCore<K, V> {
             .indices
             .get_many_mut(
                 [self.entries[a].hash.get(), self.entries[b].hash.get()],
-                move |i, &x| if i == 0 { x == a } else { x == b },
+                move |i, &x| {
+                    let x = x.into_usize();
+                    if i == 0 {
+                        x == a
+                    } else {
+                        x == b
+                    }
+                },
             )
             .expect("indices not found");
 
@@ -492,6 +692,7 @@ impl<K, V> IndexMapCore<K, V> {
         let eq = equivalent(key, &self.entries);
         match self.indices.remove_entry(hash.get(), eq) {
             Some(index) => {
+                let index = index.into_usize();
                 let (key, value) = self.swap_remove_finish(index);
                 Some((index, key, value))
             }
@@ -582,6 +783,29 @@ impl<K, V> IndexMapCore<K, V> {
         }
     }
 
+    /// Retain the entries in order like [`retain_in_order`][Self::retain_in_order],
+    /// but erase and shift the indices of the affected entries one by one,
+    /// rather than rebuilding the hash table.
+    pub(crate) fn retain_in_order_incremental<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&mut K, &mut V) -> bool,
+    {
+        let mut kept = 0;
+        for i in 0..self.entries.len() {
+            let entry = &mut self.entries[i];
+            if keep(&mut entry.key, &mut entry.value) {
+                if kept < i {
+                    update_index(&mut self.indices, entry.hash, i, kept);
+                    self.entries.swap(kept, i);
+                }
+                kept += 1;
+            } else {
+                erase_index(&mut self.indices, entry.hash, i);
+            }
+        }
+        self.entries.truncate(kept);
+    }
+
     fn rebuild_hash_table(&mut self) {
         self.indices.clear();
         raw::insert_bulk_no_grow(&mut self.indices, &self.entries);
@@ -594,9 +818,117 @@ impl<K, V> IndexMapCore<K, V> {
         // be, given that this is an in-place reversal.
         let len = self.entries.len();
         for i in self.indices_mut() {
-            *i = len - *i - 1;
+            *i = Idx::from_usize(len - i.into_usize() - 1);
+        }
+    }
+
+    /// Apply `f` to the entries in `range`, which may reorder them, and then
+    /// update the indices of only that range.
+    pub(super) fn with_entries_range<F>(&mut self, range: Range<usize>, f: F)
+    where
+        F: FnOnce(&mut [Bucket<K, V>]),
+    {
+        if range.len() > self.indices.buckets() / 2 {
+            // Most of the table is affected, so just rebuild it.
+            f(&mut self.entries[range]);
+            self.rebuild_hash_table();
+        } else {
+            self.reinsert_range(range, f);
+        }
+    }
+
+    /// Apply `f` to the entries in `range`, which must move each entry from
+    /// index `i` to `new_index(i)`, and update the indices to match.
+    fn permute_range<F, G>(&mut self, range: Range<usize>, f: F, new_index: G)
+    where
+        F: FnOnce(&mut [Bucket<K, V>]),
+        G: Fn(usize) -> usize,
+    {
+        if range.len() > self.indices.buckets() / 2 {
+            // Sweep the whole table, since we know where each index goes.
+            for i in self.indices_mut() {
+                let index = i.into_usize();
+                if range.contains(&index) {
+                    *i = Idx::from_usize(new_index(index));
+                }
+            }
+            f(&mut self.entries[range]);
+        } else {
+            self.reinsert_range(range, f);
+        }
+    }
+
+    /// Erase the indices of `range`, apply `f` to its entries, and then
+    /// insert the indices again at the positions where the entries ended up.
+    fn reinsert_range<F>(&mut self, range: Range<usize>, f: F)
+    where
+        F: FnOnce(&mut [Bucket<K, V>]),
+    {
+        // Erasing them first means they never collide with their new positions.
+        for (i, entry) in range.clone().zip(&self.entries[range.clone()]) {
+            erase_index(&mut self.indices, entry.hash, i);
+        }
+        f(&mut self.entries[range.clone()]);
+        for i in range {
+            let hash = self.entries[i].hash.get();
+            self.indices
+                .insert(hash, Idx::from_usize(i), get_hash(&self.entries));
+        }
+    }
+
+    pub(super) fn rotate_range_left(&mut self, range: Range<usize>, k: usize) {
+        let Range { start, end } = range;
+        let len = end - start;
+        assert!(k <= len, "rotation {} should be <= range length {}", k, len);
+        if k == 0 || k == len {
+            return;
+        }
+        self.permute_range(
+            range,
+            move |entries| entries.rotate_left(k),
+            move |i| start + (i - start + len - k) % len,
+        );
+    }
+
+    pub(super) fn rotate_range_right(&mut self, range: Range<usize>, k: usize) {
+        let Range { start, end } = range;
+        let len = end - start;
+        assert!(k <= len, "rotation {} should be <= range length {}", k, len);
+        if k == 0 || k == len {
+            return;
         }
+        self.permute_range(
+            range,
+            move |entries| entries.rotate_right(k),
+            move |i| start + (i - start + k) % len,
+        );
     }
+
+    pub(super) fn reverse_range(&mut self, range: Range<usize>) {
+        let Range { start, end } = range;
+        if end - start < 2 {
+            return;
+        }
+        self.permute_range(range, <[_]>::reverse, move |i| start + end - 1 - i);
+    }
+
+    pub(super) fn move_range(&mut self, range: Range<usize>, to: usize) {
+        let Range { start, end } = range;
+        let len = end - start;
+        let max = self.entries.len() - len;
+        assert!(to <= max, "destination {} should be <= {}", to, max);
+        if to < start {
+            self.rotate_range_right(to..end, len);
+        } else if start < to {
+            self.rotate_range_left(start..to + len, len);
+        }
+    }
+}
+
+#[cold]
+#[inline(never)]
+fn index_capacity_overflow() -> ! {
+    panic!("capacity overflow for the map's index type");
 }
 
 #[test]
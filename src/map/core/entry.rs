@@ -1,10 +1,11 @@
 use super::raw::RawTableEntry;
-use super::{get_hash, IndexMapCore};
-use crate::HashValue;
+use super::IndexMapCore;
+use crate::allocator::{Allocator, Global};
+use crate::{HashValue, IndexType};
 use core::{fmt, mem};
 
-impl<K, V> IndexMapCore<K, V> {
-    pub(crate) fn entry(&mut self, hash: HashValue, key: K) -> Entry<'_, K, V>
+impl<K, V, Idx: IndexType, A: Allocator> IndexMapCore<K, V, Idx, A> {
+    pub(crate) fn entry(&mut self, hash: HashValue, key: K) -> Entry<'_, K, V, Idx, A>
     where
         K: Eq,
     {
@@ -17,14 +18,14 @@ impl<K, V> IndexMapCore<K, V> {
 
 /// Entry for an existing key-value pair in an [`IndexMap`][crate::IndexMap]
 /// or a vacant location to insert one.
-pub enum Entry<'a, K, V> {
+pub enum Entry<'a, K, V, Idx = usize, A: Allocator = Global> {
     /// Existing slot with equivalent key.
-    Occupied(OccupiedEntry<'a, K, V>),
+    Occupied(OccupiedEntry<'a, K, V, Idx, A>),
     /// Vacant slot (no equivalent key in the map).
-    Vacant(VacantEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V, Idx, A>),
 }
 
-impl<'a, K, V> Entry<'a, K, V> {
+impl<'a, K, V, Idx: IndexType, A: Allocator> Entry<'a, K, V, Idx, A> {
     /// Return the index where the key-value pair exists or will be inserted.
     pub fn index(&self) -> usize {
         match *self {
@@ -111,7 +112,9 @@ impl<'a, K, V> Entry<'a, K, V> {
     }
 }
 
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Entry<'_, K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, Idx: IndexType, A: Allocator> fmt::Debug
+    for Entry<'_, K, V, Idx, A>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut tuple = f.debug_tuple("Entry");
         match self {
@@ -124,11 +127,11 @@ impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Entry<'_, K, V> {
 
 /// A view into an occupied entry in an [`IndexMap`][crate::IndexMap].
 /// It is part of the [`Entry`] enum.
-pub struct OccupiedEntry<'a, K, V> {
-    raw: RawTableEntry<'a, K, V>,
+pub struct OccupiedEntry<'a, K, V, Idx = usize, A: Allocator = Global> {
+    raw: RawTableEntry<'a, K, V, Idx, A>,
 }
 
-impl<'a, K, V> OccupiedEntry<'a, K, V> {
+impl<'a, K, V, Idx: IndexType, A: Allocator> OccupiedEntry<'a, K, V, Idx, A> {
     /// Return the index of the key-value pair
     #[inline]
     pub fn index(&self) -> usize {
@@ -182,7 +185,7 @@ impl<'a, K, V> OccupiedEntry<'a, K, V> {
 
     /// Remove the key, value pair stored in the map for this entry, and return the value.
     ///
-    /// Like [`Vec::swap_remove`][crate::Vec::swap_remove], the pair is removed by swapping it with
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is removed by swapping it with
     /// the last element of the map and popping it off.
     /// **This perturbs the position of what used to be the last element!**
     ///
@@ -193,7 +196,7 @@ impl<'a, K, V> OccupiedEntry<'a, K, V> {
 
     /// Remove the key, value pair stored in the map for this entry, and return the value.
     ///
-    /// Like [`Vec::remove`][crate::Vec::remove], the pair is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -216,7 +219,7 @@ impl<'a, K, V> OccupiedEntry<'a, K, V> {
 
     /// Remove and return the key, value pair stored in the map for this entry
     ///
-    /// Like [`Vec::swap_remove`][crate::Vec::swap_remove], the pair is removed by swapping it with
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is removed by swapping it with
     /// the last element of the map and popping it off.
     /// **This perturbs the position of what used to be the last element!**
     ///
@@ -228,7 +231,7 @@ impl<'a, K, V> OccupiedEntry<'a, K, V> {
 
     /// Remove and return the key, value pair stored in the map for this entry
     ///
-    /// Like [`Vec::remove`][crate::Vec::remove], the pair is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -237,9 +240,28 @@ impl<'a, K, V> OccupiedEntry<'a, K, V> {
         let (map, index) = self.raw.remove_index();
         map.shift_remove_finish(index)
     }
+
+    /// Moves the position of the entry to a new index
+    /// by shifting all other entries in-between.
+    ///
+    /// This is equivalent to [`IndexMap::move_index`][`crate::IndexMap::move_index`]
+    /// coming `from` the current [`.index()`][Self::index].
+    ///
+    /// * If `self.index() < to`, the other pairs will shift down while the targeted pair moves up.
+    /// * If `self.index() > to`, the other pairs will shift up while the targeted pair moves down.
+    ///
+    /// ***Panics*** if `to` is out of bounds.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn move_index(self, to: usize) {
+        let (map, index) = self.raw.into_inner();
+        map.move_index(index, to);
+    }
 }
 
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for OccupiedEntry<'_, K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, Idx: IndexType, A: Allocator> fmt::Debug
+    for OccupiedEntry<'_, K, V, Idx, A>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("OccupiedEntry")
             .field("key", self.key())
@@ -250,13 +272,13 @@ impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for OccupiedEntry<'_, K, V> {
 
 /// A view into a vacant entry in an [`IndexMap`][crate::IndexMap].
 /// It is part of the [`Entry`] enum.
-pub struct VacantEntry<'a, K, V> {
-    map: &'a mut IndexMapCore<K, V>,
+pub struct VacantEntry<'a, K, V, Idx = usize, A: Allocator = Global> {
+    map: &'a mut IndexMapCore<K, V, Idx, A>,
     hash: HashValue,
     key: K,
 }
 
-impl<'a, K, V> VacantEntry<'a, K, V> {
+impl<'a, K, V, Idx: IndexType, A: Allocator> VacantEntry<'a, K, V, Idx, A> {
     /// Return the index where a key-value pair may be inserted.
     pub fn index(&self) -> usize {
         self.map.indices.len()
@@ -275,16 +297,31 @@ impl<'a, K, V> VacantEntry<'a, K, V> {
     /// Inserts the entry's key and the given value into the map, and returns a mutable reference
     /// to the value.
     pub fn insert(self, value: V) -> &'a mut V {
-        let i = self.index();
+        self.insert_entry(value).into_mut()
+    }
+
+    /// Inserts the entry's key and the given value into the map, and returns an
+    /// [`OccupiedEntry`] for the new key-value pair.
+    pub fn insert_entry(self, value: V) -> OccupiedEntry<'a, K, V, Idx, A> {
         let Self { map, hash, key } = self;
-        map.indices.insert(hash.get(), i, get_hash(&map.entries));
-        debug_assert_eq!(i, map.entries.len());
-        map.push_entry(hash, key, value);
-        &mut map.entries[i].value
+        let raw = map.insert_unique(hash, key, value);
+        OccupiedEntry { raw }
+    }
+
+    /// Inserts the entry's key and the given value into the map at the given index,
+    /// shifting others to the right, and returns a mutable reference to the value.
+    ///
+    /// ***Panics*** if `index` is out of bounds.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn shift_insert(self, index: usize, value: V) -> &'a mut V {
+        self.map
+            .shift_insert_unique(index, self.hash, self.key, value);
+        &mut self.map.entries[index].value
     }
 }
 
-impl<K: fmt::Debug, V> fmt::Debug for VacantEntry<'_, K, V> {
+impl<K: fmt::Debug, V, Idx: IndexType, A: Allocator> fmt::Debug for VacantEntry<'_, K, V, Idx, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_tuple("VacantEntry").field(self.key()).finish()
     }
@@ -293,15 +330,15 @@ impl<K: fmt::Debug, V> fmt::Debug for VacantEntry<'_, K, V> {
 /// A view into an occupied entry in an [`IndexMap`][crate::IndexMap] obtained by index.
 ///
 /// This `struct` is created from the [`get_index_entry`][crate::IndexMap::get_index_entry] method.
-pub struct IndexedEntry<'a, K, V> {
-    map: &'a mut IndexMapCore<K, V>,
+pub struct IndexedEntry<'a, K, V, Idx = usize, A: Allocator = Global> {
+    map: &'a mut IndexMapCore<K, V, Idx, A>,
     // We have a mutable reference to the map, which keeps the index
     // valid and pointing to the correct entry.
     index: usize,
 }
 
-impl<'a, K, V> IndexedEntry<'a, K, V> {
-    pub(crate) fn new(map: &'a mut IndexMapCore<K, V>, index: usize) -> Self {
+impl<'a, K, V, Idx: IndexType, A: Allocator> IndexedEntry<'a, K, V, Idx, A> {
+    pub(crate) fn new(map: &'a mut IndexMapCore<K, V, Idx, A>, index: usize) -> Self {
         Self { map, index }
     }
 
@@ -342,7 +379,7 @@ impl<'a, K, V> IndexedEntry<'a, K, V> {
 
     /// Remove and return the key, value pair stored in the map for this entry
     ///
-    /// Like [`Vec::swap_remove`][crate::Vec::swap_remove], the pair is removed by swapping it with
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is removed by swapping it with
     /// the last element of the map and popping it off.
     /// **This perturbs the position of what used to be the last element!**
     ///
@@ -353,7 +390,7 @@ impl<'a, K, V> IndexedEntry<'a, K, V> {
 
     /// Remove and return the key, value pair stored in the map for this entry
     ///
-    /// Like [`Vec::remove`][crate::Vec::remove], the pair is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -364,7 +401,7 @@ impl<'a, K, V> IndexedEntry<'a, K, V> {
 
     /// Remove the key, value pair stored in the map for this entry, and return the value.
     ///
-    /// Like [`Vec::swap_remove`][crate::Vec::swap_remove], the pair is removed by swapping it with
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is removed by swapping it with
     /// the last element of the map and popping it off.
     /// **This perturbs the position of what used to be the last element!**
     ///
@@ -375,7 +412,7 @@ impl<'a, K, V> IndexedEntry<'a, K, V> {
 
     /// Remove the key, value pair stored in the map for this entry, and return the value.
     ///
-    /// Like [`Vec::remove`][crate::Vec::remove], the pair is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -383,9 +420,27 @@ impl<'a, K, V> IndexedEntry<'a, K, V> {
     pub fn shift_remove(self) -> V {
         self.shift_remove_entry().1
     }
+
+    /// Moves the position of the entry to a new index
+    /// by shifting all other entries in-between.
+    ///
+    /// This is equivalent to [`IndexMap::move_index`][`crate::IndexMap::move_index`]
+    /// coming `from` the current [`.index()`][Self::index].
+    ///
+    /// * If `self.index() < to`, the other pairs will shift down while the targeted pair moves up.
+    /// * If `self.index() > to`, the other pairs will shift up while the targeted pair moves down.
+    ///
+    /// ***Panics*** if `to` is out of bounds.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn move_index(self, to: usize) {
+        self.map.move_index(self.index, to);
+    }
 }
 
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for IndexedEntry<'_, K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, Idx: IndexType, A: Allocator> fmt::Debug
+    for IndexedEntry<'_, K, V, Idx, A>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("IndexedEntry")
             .field("index", &self.index)
@@ -2,27 +2,68 @@
 //! This module encapsulates the `unsafe` access to `hashbrown::raw::RawTable`,
 //! mostly in dealing with its bucket "pointers".
 
-use super::{equivalent, get_hash, Bucket, HashValue, IndexMapCore};
+use super::{equivalent, get_hash, Bucket, HashValue, IndexMapCore, IndexType};
+use crate::allocator::{Allocator, RawTable};
 use core::fmt;
-use hashbrown::raw::RawTable;
 
-type RawBucket = hashbrown::raw::Bucket<usize>;
+type RawBucket<Idx> = hashbrown::raw::Bucket<Idx>;
 
 /// Inserts many entries into a raw table without reallocating.
 ///
 /// ***Panics*** if there is not sufficient capacity already.
-pub(super) fn insert_bulk_no_grow<K, V>(indices: &mut RawTable<usize>, entries: &[Bucket<K, V>]) {
-    assert!(indices.capacity() - indices.len() >= entries.len());
-    for entry in entries {
+pub(super) fn insert_bulk_no_grow<K, V, Idx: IndexType, A: Allocator>(
+    indices: &mut RawTable<Idx, A>,
+    entries: &[Bucket<K, V>],
+) {
+    insert_hashes_no_grow(indices, entries.iter().map(|entry| entry.hash));
+}
+
+/// Inserts the next indices for many hash values into a raw table without
+/// reallocating, for tables that don't index a slice of [`Bucket`]s.
+///
+/// ***Panics*** if there is not sufficient capacity already.
+pub(crate) fn insert_hashes_no_grow<Idx, A, I>(indices: &mut RawTable<Idx, A>, hashes: I)
+where
+    Idx: IndexType,
+    A: Allocator,
+    I: ExactSizeIterator<Item = HashValue>,
+{
+    assert!(indices.capacity() - indices.len() >= hashes.len());
+    for hash in hashes {
+        let index = Idx::from_usize(indices.len());
+        // SAFETY: we asserted that sufficient capacity exists for all entries.
+        unsafe {
+            indices.insert_no_grow(hash.get(), index);
+        }
+    }
+}
+
+/// Inserts the indices of `entries` that aren't in the table yet, like
+/// [`insert_bulk_no_grow`], but stops at the first entry whose key is already
+/// present, and returns the index of that key.
+///
+/// ***Panics*** if there is not sufficient capacity already.
+pub(super) fn insert_bulk_unique_no_grow<K: Eq, V, Idx: IndexType, A: Allocator>(
+    indices: &mut RawTable<Idx, A>,
+    entries: &[Bucket<K, V>],
+) -> Result<(), usize> {
+    assert!(indices.capacity() >= entries.len());
+    for entry in &entries[indices.len()..] {
+        let hash = entry.hash.get();
+        if let Some(&i) = indices.get(hash, equivalent(&entry.key, entries)) {
+            return Err(i.into_usize());
+        }
+        let index = Idx::from_usize(indices.len());
         // SAFETY: we asserted that sufficient capacity exists for all entries.
         unsafe {
-            indices.insert_no_grow(entry.hash.get(), indices.len());
+            indices.insert_no_grow(hash, index);
         }
     }
+    Ok(())
 }
 
-pub(super) struct DebugIndices<'a>(pub &'a RawTable<usize>);
-impl fmt::Debug for DebugIndices<'_> {
+pub(super) struct DebugIndices<'a, Idx, A: Allocator>(pub &'a RawTable<Idx, A>);
+impl<Idx: IndexType, A: Allocator> fmt::Debug for DebugIndices<'_, Idx, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // SAFETY: we're not letting any of the buckets escape this function
         let indices = unsafe { self.0.iter().map(|raw_bucket| *raw_bucket.as_ref()) };
@@ -30,7 +71,7 @@ impl fmt::Debug for DebugIndices<'_> {
     }
 }
 
-impl<K, V> IndexMapCore<K, V> {
+impl<K, V, Idx: IndexType, A: Allocator> IndexMapCore<K, V, Idx, A> {
     /// Sweep the whole table to erase indices start..end
     pub(super) fn erase_indices_sweep(&mut self, start: usize, end: usize) {
         // SAFETY: we're not letting any of the buckets escape this function
@@ -38,9 +79,10 @@ impl<K, V> IndexMapCore<K, V> {
             let offset = end - start;
             for bucket in self.indices.iter() {
                 let i = bucket.as_mut();
-                if *i >= end {
-                    *i -= offset;
-                } else if *i >= start {
+                let index = i.into_usize();
+                if index >= end {
+                    *i = Idx::from_usize(index - offset);
+                } else if index >= start {
                     self.indices.erase(bucket);
                 }
             }
@@ -63,23 +105,48 @@ impl<K, V> IndexMapCore<K, V> {
         // SAFETY: We're not mutating between find and read/insert.
         unsafe {
             match self.indices.find_or_find_insert_slot(hash, eq, hasher) {
-                Ok(raw_bucket) => Ok(*raw_bucket.as_ref()),
+                Ok(raw_bucket) => Ok(raw_bucket.as_ref().into_usize()),
                 Err(slot) => {
                     let index = self.indices.len();
-                    self.indices.insert_in_slot(hash, slot, index);
+                    self.assert_index_capacity(1);
+                    self.indices
+                        .insert_in_slot(hash, slot, Idx::from_usize(index));
                     Err(index)
                 }
             }
         }
     }
 
+    /// Append a key-value pair to `entries`, *without* checking whether it already exists,
+    /// and return a raw entry for the new index.
+    pub(crate) fn insert_unique(
+        &mut self,
+        hash: HashValue,
+        key: K,
+        value: V,
+    ) -> RawTableEntry<'_, K, V, Idx, A> {
+        let i = self.indices.len();
+        self.assert_index_capacity(1);
+        let raw_bucket =
+            self.indices
+                .insert(hash.get(), Idx::from_usize(i), get_hash(&self.entries));
+        debug_assert_eq!(i, self.entries.len());
+        self.push_entry(hash, key, value);
+        // SAFETY: The entry is created with a live raw bucket, at the same time
+        // we have a &mut reference to the map, so it can not be modified further.
+        RawTableEntry {
+            map: self,
+            raw_bucket,
+        }
+    }
+
     pub(super) fn raw_entry(
         &mut self,
         hash: HashValue,
         mut is_match: impl FnMut(&K) -> bool,
-    ) -> Result<RawTableEntry<'_, K, V>, &mut Self> {
+    ) -> Result<RawTableEntry<'_, K, V, Idx, A>, &mut Self> {
         let entries = &*self.entries;
-        let eq = move |&i: &usize| is_match(&entries[i].key);
+        let eq = move |&i: &Idx| is_match(&entries[i.into_usize()].key);
         match self.indices.find(hash.get(), eq) {
             // SAFETY: The entry is created with a live raw bucket, at the same time
             // we have a &mut reference to the map, so it can not be modified further.
@@ -91,7 +158,7 @@ impl<K, V> IndexMapCore<K, V> {
         }
     }
 
-    pub(super) fn indices_mut(&mut self) -> impl Iterator<Item = &mut usize> {
+    pub(super) fn indices_mut(&mut self) -> impl Iterator<Item = &mut Idx> {
         // SAFETY: we're not letting any of the buckets escape this function,
         // only the item references that are appropriately bound to `&mut self`.
         unsafe { self.indices.iter().map(|bucket| bucket.as_mut()) }
@@ -101,21 +168,24 @@ impl<K, V> IndexMapCore<K, V> {
 /// A view into an occupied raw entry in an `IndexMap`.
 // SAFETY: The lifetime of the map reference also constrains the raw bucket,
 // which is essentially a raw pointer into the map indices.
-pub(super) struct RawTableEntry<'a, K, V> {
-    map: &'a mut IndexMapCore<K, V>,
-    raw_bucket: RawBucket,
+pub(crate) struct RawTableEntry<'a, K, V, Idx, A: Allocator> {
+    map: &'a mut IndexMapCore<K, V, Idx, A>,
+    raw_bucket: RawBucket<Idx>,
 }
 
 // `hashbrown::raw::Bucket` is only `Send`, not `Sync`.
 // SAFETY: `&self` only accesses the bucket to read it.
-unsafe impl<K: Sync, V: Sync> Sync for RawTableEntry<'_, K, V> {}
+unsafe impl<K: Sync, V: Sync, Idx: Sync, A: Sync + Allocator> Sync
+    for RawTableEntry<'_, K, V, Idx, A>
+{
+}
 
-impl<'a, K, V> RawTableEntry<'a, K, V> {
+impl<'a, K, V, Idx: IndexType, A: Allocator> RawTableEntry<'a, K, V, Idx, A> {
     /// Return the index of the key-value pair
     #[inline]
     pub(super) fn index(&self) -> usize {
         // SAFETY: we have `&mut map` keeping the bucket stable
-        unsafe { *self.raw_bucket.as_ref() }
+        unsafe { self.raw_bucket.as_ref().into_usize() }
     }
 
     #[inline]
@@ -135,11 +205,17 @@ impl<'a, K, V> RawTableEntry<'a, K, V> {
         &mut self.map.entries[index]
     }
 
+    /// Return the index and the original map reference, without removing anything.
+    pub(super) fn into_inner(self) -> (&'a mut IndexMapCore<K, V, Idx, A>, usize) {
+        let index = self.index();
+        (self.map, index)
+    }
+
     /// Remove the index from indices, leaving the actual entries to the caller.
-    pub(super) fn remove_index(self) -> (&'a mut IndexMapCore<K, V>, usize) {
+    pub(super) fn remove_index(self) -> (&'a mut IndexMapCore<K, V, Idx, A>, usize) {
         // SAFETY: This is safe because it can only happen once (self is consumed)
         // and map.indices have not been modified since entry construction
         let (index, _slot) = unsafe { self.map.indices.remove(self.raw_bucket) };
-        (self.map, index)
+        (self.map, index.into_usize())
     }
 }
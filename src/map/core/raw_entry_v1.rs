@@ -10,8 +10,9 @@
 //! `IndexMap` without such an opt-in trait.
 
 use super::raw::RawTableEntry;
-use super::{get_hash, IndexMapCore};
-use crate::{Equivalent, HashValue, IndexMap};
+use super::IndexMapCore;
+use crate::allocator::{Allocator, Global};
+use crate::{Equivalent, HashValue, IndexMap, IndexType};
 use core::fmt;
 use core::hash::{BuildHasher, Hash, Hasher};
 use core::marker::PhantomData;
@@ -20,7 +21,7 @@ use core::mem;
 /// Opt-in access to the experimental raw entry API.
 ///
 /// See the [`raw_entry_v1`][self] module documentation for more information.
-pub trait RawEntryApiV1<K, V, S>: private::Sealed {
+pub trait RawEntryApiV1<K, V, S, Idx = usize, A: Allocator = Global>: private::Sealed {
     /// Creates a raw immutable entry builder for the [`IndexMap`].
     ///
     /// Raw entries provide the lowest level of control for searching and
@@ -66,7 +67,7 @@ pub trait RawEntryApiV1<K, V, S>: private::Sealed {
     ///     assert_eq!(map.raw_entry_v1().from_key_hashed_nocheck(hash, k), kv);
     /// }
     /// ```
-    fn raw_entry_v1(&self) -> RawEntryBuilder<'_, K, V, S>;
+    fn raw_entry_v1(&self) -> RawEntryBuilder<'_, K, V, S, Idx, A>;
 
     /// Creates a raw entry builder for the [`IndexMap`].
     ///
@@ -161,15 +162,17 @@ pub trait RawEntryApiV1<K, V, S>: private::Sealed {
     /// assert_eq!(map.get("d"), None);
     /// assert_eq!(map.len(), 2);
     /// ```
-    fn raw_entry_mut_v1(&mut self) -> RawEntryBuilderMut<'_, K, V, S>;
+    fn raw_entry_mut_v1(&mut self) -> RawEntryBuilderMut<'_, K, V, S, Idx, A>;
 }
 
-impl<K, V, S> RawEntryApiV1<K, V, S> for IndexMap<K, V, S> {
-    fn raw_entry_v1(&self) -> RawEntryBuilder<'_, K, V, S> {
+impl<K, V, S, Idx: IndexType, A: Allocator> RawEntryApiV1<K, V, S, Idx, A>
+    for IndexMap<K, V, S, Idx, A>
+{
+    fn raw_entry_v1(&self) -> RawEntryBuilder<'_, K, V, S, Idx, A> {
         RawEntryBuilder { map: self }
     }
 
-    fn raw_entry_mut_v1(&mut self) -> RawEntryBuilderMut<'_, K, V, S> {
+    fn raw_entry_mut_v1(&mut self) -> RawEntryBuilderMut<'_, K, V, S, Idx, A> {
         RawEntryBuilderMut { map: self }
     }
 }
@@ -178,17 +181,17 @@ impl<K, V, S> RawEntryApiV1<K, V, S> for IndexMap<K, V, S> {
 ///
 /// This `struct` is created by the [`IndexMap::raw_entry_v1`] method, provided by the
 /// [`RawEntryApiV1`] trait. See its documentation for more.
-pub struct RawEntryBuilder<'a, K, V, S> {
-    map: &'a IndexMap<K, V, S>,
+pub struct RawEntryBuilder<'a, K, V, S, Idx = usize, A: Allocator = Global> {
+    map: &'a IndexMap<K, V, S, Idx, A>,
 }
 
-impl<K, V, S> fmt::Debug for RawEntryBuilder<'_, K, V, S> {
+impl<K, V, S, Idx: IndexType, A: Allocator> fmt::Debug for RawEntryBuilder<'_, K, V, S, Idx, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RawEntryBuilder").finish_non_exhaustive()
     }
 }
 
-impl<'a, K, V, S> RawEntryBuilder<'a, K, V, S> {
+impl<'a, K, V, S, Idx: IndexType, A: Allocator> RawEntryBuilder<'a, K, V, S, Idx, A> {
     /// Access an entry by key.
     pub fn from_key<Q: ?Sized>(self, key: &Q) -> Option<(&'a K, &'a V)>
     where
@@ -215,9 +218,9 @@ impl<'a, K, V, S> RawEntryBuilder<'a, K, V, S> {
     {
         let hash = HashValue(hash as usize);
         let entries = &*self.map.core.entries;
-        let eq = move |&i: &usize| is_match(&entries[i].key);
-        let i = *self.map.core.indices.get(hash.get(), eq)?;
-        Some(entries[i].refs())
+        let eq = move |&i: &Idx| is_match(&entries[i.into_usize()].key);
+        let i = self.map.core.indices.get(hash.get(), eq)?;
+        Some(entries[i.into_usize()].refs())
     }
 }
 
@@ -225,19 +228,19 @@ impl<'a, K, V, S> RawEntryBuilder<'a, K, V, S> {
 ///
 /// This `struct` is created by the [`IndexMap::raw_entry_mut_v1`] method, provided by the
 /// [`RawEntryApiV1`] trait. See its documentation for more.
-pub struct RawEntryBuilderMut<'a, K, V, S> {
-    map: &'a mut IndexMap<K, V, S>,
+pub struct RawEntryBuilderMut<'a, K, V, S, Idx = usize, A: Allocator = Global> {
+    map: &'a mut IndexMap<K, V, S, Idx, A>,
 }
 
-impl<K, V, S> fmt::Debug for RawEntryBuilderMut<'_, K, V, S> {
+impl<K, V, S, Idx: IndexType, A: Allocator> fmt::Debug for RawEntryBuilderMut<'_, K, V, S, Idx, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RawEntryBuilderMut").finish_non_exhaustive()
     }
 }
 
-impl<'a, K, V, S> RawEntryBuilderMut<'a, K, V, S> {
+impl<'a, K, V, S, Idx: IndexType, A: Allocator> RawEntryBuilderMut<'a, K, V, S, Idx, A> {
     /// Access an entry by key.
-    pub fn from_key<Q: ?Sized>(self, key: &Q) -> RawEntryMut<'a, K, V, S>
+    pub fn from_key<Q: ?Sized>(self, key: &Q) -> RawEntryMut<'a, K, V, S, Idx, A>
     where
         S: BuildHasher,
         Q: Hash + Equivalent<K>,
@@ -247,7 +250,11 @@ impl<'a, K, V, S> RawEntryBuilderMut<'a, K, V, S> {
     }
 
     /// Access an entry by a key and its hash.
-    pub fn from_key_hashed_nocheck<Q: ?Sized>(self, hash: u64, key: &Q) -> RawEntryMut<'a, K, V, S>
+    pub fn from_key_hashed_nocheck<Q: ?Sized>(
+        self,
+        hash: u64,
+        key: &Q,
+    ) -> RawEntryMut<'a, K, V, S, Idx, A>
     where
         Q: Equivalent<K>,
     {
@@ -255,7 +262,7 @@ impl<'a, K, V, S> RawEntryBuilderMut<'a, K, V, S> {
     }
 
     /// Access an entry by hash.
-    pub fn from_hash<F>(self, hash: u64, is_match: F) -> RawEntryMut<'a, K, V, S>
+    pub fn from_hash<F>(self, hash: u64, is_match: F) -> RawEntryMut<'a, K, V, S, Idx, A>
     where
         F: FnMut(&K) -> bool,
     {
@@ -275,14 +282,16 @@ impl<'a, K, V, S> RawEntryBuilderMut<'a, K, V, S> {
 
 /// Raw entry for an existing key-value pair or a vacant location to
 /// insert one.
-pub enum RawEntryMut<'a, K, V, S> {
+pub enum RawEntryMut<'a, K, V, S, Idx = usize, A: Allocator = Global> {
     /// Existing slot with equivalent key.
-    Occupied(RawOccupiedEntryMut<'a, K, V, S>),
+    Occupied(RawOccupiedEntryMut<'a, K, V, S, Idx, A>),
     /// Vacant slot (no equivalent key in the map).
-    Vacant(RawVacantEntryMut<'a, K, V, S>),
+    Vacant(RawVacantEntryMut<'a, K, V, S, Idx, A>),
 }
 
-impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for RawEntryMut<'_, K, V, S> {
+impl<K: fmt::Debug, V: fmt::Debug, S, Idx: IndexType, A: Allocator> fmt::Debug
+    for RawEntryMut<'_, K, V, S, Idx, A>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut tuple = f.debug_tuple("RawEntryMut");
         match self {
@@ -293,7 +302,7 @@ impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for RawEntryMut<'_, K, V, S> {
     }
 }
 
-impl<'a, K, V, S> RawEntryMut<'a, K, V, S> {
+impl<'a, K, V, S, Idx: IndexType, A: Allocator> RawEntryMut<'a, K, V, S, Idx, A> {
     /// Inserts the given default key and value in the entry if it is vacant and returns mutable
     /// references to them. Otherwise mutable references to an already existent pair are returned.
     pub fn or_insert(self, default_key: K, default_value: V) -> (&'a mut K, &'a mut V)
@@ -339,12 +348,14 @@ impl<'a, K, V, S> RawEntryMut<'a, K, V, S> {
 
 /// A raw view into an occupied entry in an [`IndexMap`].
 /// It is part of the [`RawEntryMut`] enum.
-pub struct RawOccupiedEntryMut<'a, K, V, S> {
-    raw: RawTableEntry<'a, K, V>,
+pub struct RawOccupiedEntryMut<'a, K, V, S, Idx = usize, A: Allocator = Global> {
+    raw: RawTableEntry<'a, K, V, Idx, A>,
     hash_builder: PhantomData<&'a S>,
 }
 
-impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for RawOccupiedEntryMut<'_, K, V, S> {
+impl<K: fmt::Debug, V: fmt::Debug, S, Idx: IndexType, A: Allocator> fmt::Debug
+    for RawOccupiedEntryMut<'_, K, V, S, Idx, A>
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RawOccupiedEntryMut")
             .field("key", self.key())
@@ -353,7 +364,7 @@ impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for RawOccupiedEntryMut<'_, K,
     }
 }
 
-impl<'a, K, V, S> RawOccupiedEntryMut<'a, K, V, S> {
+impl<'a, K, V, S, Idx: IndexType, A: Allocator> RawOccupiedEntryMut<'a, K, V, S, Idx, A> {
     /// Return the index of the key-value pair
     #[inline]
     pub fn index(&self) -> usize {
@@ -447,7 +458,7 @@ impl<'a, K, V, S> RawOccupiedEntryMut<'a, K, V, S> {
 
     /// Remove the key, value pair stored in the map for this entry, and return the value.
     ///
-    /// Like [`Vec::swap_remove`][crate::Vec::swap_remove], the pair is removed by swapping it with
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is removed by swapping it with
     /// the last element of the map and popping it off.
     /// **This perturbs the position of what used to be the last element!**
     ///
@@ -458,7 +469,7 @@ impl<'a, K, V, S> RawOccupiedEntryMut<'a, K, V, S> {
 
     /// Remove the key, value pair stored in the map for this entry, and return the value.
     ///
-    /// Like [`Vec::remove`][crate::Vec::remove], the pair is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -481,7 +492,7 @@ impl<'a, K, V, S> RawOccupiedEntryMut<'a, K, V, S> {
 
     /// Remove and return the key, value pair stored in the map for this entry
     ///
-    /// Like [`Vec::swap_remove`][crate::Vec::swap_remove], the pair is removed by swapping it with
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is removed by swapping it with
     /// the last element of the map and popping it off.
     /// **This perturbs the position of what used to be the last element!**
     ///
@@ -493,7 +504,7 @@ impl<'a, K, V, S> RawOccupiedEntryMut<'a, K, V, S> {
 
     /// Remove and return the key, value pair stored in the map for this entry
     ///
-    /// Like [`Vec::remove`][crate::Vec::remove], the pair is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -506,18 +517,18 @@ impl<'a, K, V, S> RawOccupiedEntryMut<'a, K, V, S> {
 
 /// A view into a vacant raw entry in an [`IndexMap`].
 /// It is part of the [`RawEntryMut`] enum.
-pub struct RawVacantEntryMut<'a, K, V, S> {
-    map: &'a mut IndexMapCore<K, V>,
+pub struct RawVacantEntryMut<'a, K, V, S, Idx = usize, A: Allocator = Global> {
+    map: &'a mut IndexMapCore<K, V, Idx, A>,
     hash_builder: &'a S,
 }
 
-impl<K, V, S> fmt::Debug for RawVacantEntryMut<'_, K, V, S> {
+impl<K, V, S, Idx: IndexType, A: Allocator> fmt::Debug for RawVacantEntryMut<'_, K, V, S, Idx, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("RawVacantEntryMut").finish_non_exhaustive()
     }
 }
 
-impl<'a, K, V, S> RawVacantEntryMut<'a, K, V, S> {
+impl<'a, K, V, S, Idx: IndexType, A: Allocator> RawVacantEntryMut<'a, K, V, S, Idx, A> {
     /// Return the index where a key-value pair may be inserted.
     pub fn index(&self) -> usize {
         self.map.indices.len()
@@ -538,18 +549,16 @@ impl<'a, K, V, S> RawVacantEntryMut<'a, K, V, S> {
     /// Inserts the given key and value into the map with the provided hash,
     /// and returns mutable references to them.
     pub fn insert_hashed_nocheck(self, hash: u64, key: K, value: V) -> (&'a mut K, &'a mut V) {
-        let i = self.index();
-        let map = self.map;
         let hash = HashValue(hash as usize);
-        map.indices.insert(hash.get(), i, get_hash(&map.entries));
-        debug_assert_eq!(i, map.entries.len());
-        map.push_entry(hash, key, value);
-        map.entries[i].muts()
+        self.map
+            .insert_unique(hash, key, value)
+            .into_bucket()
+            .muts()
     }
 }
 
 mod private {
     pub trait Sealed {}
 
-    impl<K, V, S> Sealed for super::IndexMap<K, V, S> {}
+    impl<K, V, S, Idx, A: super::Allocator> Sealed for super::IndexMap<K, V, S, Idx, A> {}
 }
@@ -0,0 +1,290 @@
+//! Structured differences between two [`IndexMap`]s, which can be replayed
+//! on another copy of the original map.
+
+use super::{Entries, IndexMap, IndexType};
+
+use crate::allocator::Allocator;
+use alloc::vec::{self, Vec};
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::iter::FusedIterator;
+
+/// One step of the difference between two [`IndexMap`]s.
+///
+/// This `enum` is produced by the [`IndexMap::diff`] method.
+/// See its documentation for more.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DiffOp<'a, K, V> {
+    /// The key is only in the original map, where it was at `index`.
+    Removed {
+        /// The index of the key in the original map.
+        index: usize,
+        /// The removed key.
+        key: &'a K,
+        /// The removed value.
+        value: &'a V,
+    },
+    /// The key is only in the target map, where it is at `index`.
+    Added {
+        /// The index of the key in the target map.
+        index: usize,
+        /// The added key.
+        key: &'a K,
+        /// The added value.
+        value: &'a V,
+    },
+    /// The key is in both maps, with different values.
+    Changed {
+        /// The changed key.
+        key: &'a K,
+        /// The value in the original map.
+        old: &'a V,
+        /// The value in the target map.
+        new: &'a V,
+    },
+    /// The key is in both maps, but its position changed relative to the
+    /// other keys that are in both.
+    Moved {
+        /// The moved key.
+        key: &'a K,
+        /// The index of the key in the original map.
+        from: usize,
+        /// The index of the key in the target map.
+        to: usize,
+    },
+}
+
+impl<K, V> Clone for DiffOp<'_, K, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, V> Copy for DiffOp<'_, K, V> {}
+
+/// An iterator over the steps of the difference between two [`IndexMap`]s.
+///
+/// This `struct` is created by the [`IndexMap::diff`] method.
+/// See its documentation for more.
+pub struct Diff<'a, K, V> {
+    iter: vec::IntoIter<DiffOp<'a, K, V>>,
+}
+
+impl<'a, K, V> Iterator for Diff<'a, K, V> {
+    type Item = DiffOp<'a, K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Diff<'_, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<K, V> FusedIterator for Diff<'_, K, V> {}
+
+impl<K, V> Clone for Diff<'_, K, V> {
+    fn clone(&self) -> Self {
+        Diff {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Diff<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter.as_slice()).finish()
+    }
+}
+
+/// Mark the positions of a longest increasing subsequence of `seq`.
+fn longest_increasing(seq: &[usize]) -> Vec<bool> {
+    // `tails[len]` is the position of the smallest tail of any increasing
+    // subsequence of length `len + 1` found so far.
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev: Vec<Option<usize>> = Vec::with_capacity(seq.len());
+    for (i, &x) in seq.iter().enumerate() {
+        let len = tails.partition_point(|&t| seq[t] < x);
+        prev.push(len.checked_sub(1).map(|p| tails[p]));
+        if len == tails.len() {
+            tails.push(i);
+        } else {
+            tails[len] = i;
+        }
+    }
+
+    let mut marked = alloc::vec![false; seq.len()];
+    let mut cursor = tails.last().copied();
+    while let Some(i) = cursor {
+        marked[i] = true;
+        cursor = prev[i];
+    }
+    marked
+}
+
+impl<K, V, S, Idx, A> IndexMap<K, V, S, Idx, A>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    /// Return the steps that turn `self` into `other`, as an iterator of [`DiffOp`]s.
+    ///
+    /// The steps are all of the [`Removed`][DiffOp::Removed] keys in their
+    /// order in `self`, followed by the [`Added`][DiffOp::Added],
+    /// [`Moved`][DiffOp::Moved] and [`Changed`][DiffOp::Changed] keys in their
+    /// order in `other`. Keys are only reported as moved if they are not part
+    /// of the largest set of common keys that kept their relative order, so
+    /// a single key that moves across the map is a single `Moved` step.
+    ///
+    /// Computes in **O(n + m log m)** time (average), where *m* is the number
+    /// of common keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    /// use indexmap::map::DiffOp;
+    ///
+    /// let old = IndexMap::from([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+    /// let new = IndexMap::from([("b", 2), ("c", 30), ("a", 1), ("e", 5)]);
+    ///
+    /// let diff: Vec<_> = old.diff(&new).collect();
+    /// assert_eq!(
+    ///     diff,
+    ///     [
+    ///         DiffOp::Removed { index: 3, key: &"d", value: &4 },
+    ///         DiffOp::Changed { key: &"c", old: &3, new: &30 },
+    ///         DiffOp::Moved { key: &"a", from: 0, to: 2 },
+    ///         DiffOp::Added { index: 3, key: &"e", value: &5 },
+    ///     ]
+    /// );
+    ///
+    /// let mut replica = old.clone();
+    /// replica.apply_diff(diff);
+    /// assert!(replica.iter().eq(&new));
+    /// ```
+    pub fn diff<'a, S2, Idx2, A2>(
+        &'a self,
+        other: &'a IndexMap<K, V, S2, Idx2, A2>,
+    ) -> Diff<'a, K, V>
+    where
+        V: PartialEq,
+        S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
+    {
+        let mut ops = Vec::new();
+
+        // The common keys, as their indices in `self` and `other`.
+        let mut common = Vec::new();
+        for (index, (key, value)) in self.iter().enumerate() {
+            match other.get_index_of(key) {
+                Some(to) => common.push((index, to)),
+                None => ops.push(DiffOp::Removed { index, key, value }),
+            }
+        }
+
+        let targets: Vec<usize> = common.iter().map(|&(_, to)| to).collect();
+        let mut sources = alloc::vec![None; other.len()];
+        for (&(from, to), stays) in common.iter().zip(longest_increasing(&targets)) {
+            sources[to] = Some((from, stays));
+        }
+
+        for ((to, (key, new)), source) in other.iter().enumerate().zip(sources) {
+            let (from, stays) = match source {
+                Some(source) => source,
+                None => {
+                    ops.push(DiffOp::Added {
+                        index: to,
+                        key,
+                        value: new,
+                    });
+                    continue;
+                }
+            };
+            if !stays {
+                ops.push(DiffOp::Moved { key, from, to });
+            }
+            let old = &self.as_entries()[from].value;
+            if old != new {
+                ops.push(DiffOp::Changed { key, old, new });
+            }
+        }
+
+        Diff {
+            iter: ops.into_iter(),
+        }
+    }
+
+    /// Apply the steps of a [`diff`][Self::diff], turning a map that is equal
+    /// to the original (in order too) into a copy of the target.
+    ///
+    /// Removed keys are removed with [`shift_remove`][Self::shift_remove],
+    /// added keys are inserted with [`shift_insert`][Self::shift_insert],
+    /// and moved keys are repositioned with [`move_index`][Self::move_index].
+    /// The steps may come in any order, so a diff can be filtered or
+    /// reordered before it is replayed, but if `self` does not match the
+    /// original map of the diff, then the result is unspecified, and this may
+    /// panic on an out-of-bounds index.
+    ///
+    /// Computes in **O(n × d)** time (average), where *d* is the number of steps.
+    pub fn apply_diff<'a, I>(&mut self, diff: I)
+    where
+        I: IntoIterator<Item = DiffOp<'a, K, V>>,
+        K: Clone + 'a,
+        V: Clone + 'a,
+    {
+        let mut moved = Vec::new();
+        let mut placed = Vec::new();
+        for op in diff {
+            match op {
+                DiffOp::Removed { key, .. } => {
+                    self.shift_remove(key);
+                }
+                DiffOp::Changed { key, new, .. } => {
+                    if let Some(value) = self.get_mut(key) {
+                        value.clone_from(new);
+                    }
+                }
+                DiffOp::Moved { key, from, to } => {
+                    moved.push((from, key));
+                    placed.push((to, key, None));
+                }
+                DiffOp::Added { index, key, value } => placed.push((index, key, Some(value))),
+            }
+        }
+
+        // Set the moved keys aside at the end, in their original order, so the
+        // keys that stay are contiguous and already in their relative order.
+        moved.sort_unstable_by_key(|&(from, _)| from);
+        for (_, key) in moved {
+            if let Some(index) = self.get_index_of(key) {
+                self.move_index(index, self.len() - 1);
+            }
+        }
+
+        // Filling each position in increasing order keeps the prefix complete.
+        placed.sort_unstable_by_key(|&(to, ..)| to);
+        for (to, key, value) in placed {
+            match value {
+                Some(value) => {
+                    self.shift_insert(to, key.clone(), value.clone());
+                }
+                None => {
+                    if let Some(index) = self.get_index_of(key) {
+                        self.move_index(index, to);
+                    }
+                }
+            }
+        }
+    }
+}
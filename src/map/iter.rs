@@ -1,14 +1,14 @@
 use super::core::IndexMapCore;
-use super::{Bucket, Entries, IndexMap, Slice};
+use super::{Bucket, Entries, IndexMap, IndexType, Slice};
 
-use alloc::vec::{self, Vec};
+use crate::allocator::{self, Allocator, Global, Vec};
 use core::fmt;
 use core::hash::{BuildHasher, Hash};
-use core::iter::FusedIterator;
+use core::iter::{Chain, FusedIterator};
 use core::ops::{Index, RangeBounds};
 use core::slice;
 
-impl<'a, K, V, S> IntoIterator for &'a IndexMap<K, V, S> {
+impl<'a, K, V, S, Idx: IndexType, A: Allocator> IntoIterator for &'a IndexMap<K, V, S, Idx, A> {
     type Item = (&'a K, &'a V);
     type IntoIter = Iter<'a, K, V>;
 
@@ -17,7 +17,7 @@ impl<'a, K, V, S> IntoIterator for &'a IndexMap<K, V, S> {
     }
 }
 
-impl<'a, K, V, S> IntoIterator for &'a mut IndexMap<K, V, S> {
+impl<'a, K, V, S, Idx: IndexType, A: Allocator> IntoIterator for &'a mut IndexMap<K, V, S, Idx, A> {
     type Item = (&'a K, &'a mut V);
     type IntoIter = IterMut<'a, K, V>;
 
@@ -26,9 +26,9 @@ impl<'a, K, V, S> IntoIterator for &'a mut IndexMap<K, V, S> {
     }
 }
 
-impl<K, V, S> IntoIterator for IndexMap<K, V, S> {
+impl<K, V, S, Idx: IndexType, A: Allocator> IntoIterator for IndexMap<K, V, S, Idx, A> {
     type Item = (K, V);
-    type IntoIter = IntoIter<K, V>;
+    type IntoIter = IntoIter<K, V, A>;
 
     fn into_iter(self) -> Self::IntoIter {
         IntoIter::new(self.into_entries())
@@ -160,12 +160,12 @@ impl<K, V> Default for IterMut<'_, K, V> {
 ///
 /// This `struct` is created by the [`IndexMap::into_iter`] method
 /// (provided by the [`IntoIterator`] trait). See its documentation for more.
-pub struct IntoIter<K, V> {
-    iter: vec::IntoIter<Bucket<K, V>>,
+pub struct IntoIter<K, V, A: Allocator = Global> {
+    iter: allocator::IntoIter<Bucket<K, V>, A>,
 }
 
-impl<K, V> IntoIter<K, V> {
-    pub(super) fn new(entries: Vec<Bucket<K, V>>) -> Self {
+impl<K, V, A: Allocator> IntoIter<K, V, A> {
+    pub(super) fn new(entries: Vec<Bucket<K, V>, A>) -> Self {
         Self {
             iter: entries.into_iter(),
         }
@@ -182,25 +182,25 @@ impl<K, V> IntoIter<K, V> {
     }
 }
 
-impl<K, V> Iterator for IntoIter<K, V> {
+impl<K, V, A: Allocator> Iterator for IntoIter<K, V, A> {
     type Item = (K, V);
 
     iterator_methods!(Bucket::key_value);
 }
 
-impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+impl<K, V, A: Allocator> DoubleEndedIterator for IntoIter<K, V, A> {
     double_ended_iterator_methods!(Bucket::key_value);
 }
 
-impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+impl<K, V, A: Allocator> ExactSizeIterator for IntoIter<K, V, A> {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<K, V> FusedIterator for IntoIter<K, V> {}
+impl<K, V, A: Allocator> FusedIterator for IntoIter<K, V, A> {}
 
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for IntoIter<K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let iter = self.iter.as_slice().iter().map(Bucket::refs);
         f.debug_list().entries(iter).finish()
@@ -219,12 +219,12 @@ impl<K, V> Default for IntoIter<K, V> {
 ///
 /// This `struct` is created by the [`IndexMap::drain`] method.
 /// See its documentation for more.
-pub struct Drain<'a, K, V> {
-    iter: vec::Drain<'a, Bucket<K, V>>,
+pub struct Drain<'a, K, V, A: Allocator = Global> {
+    iter: allocator::Drain<'a, Bucket<K, V>, A>,
 }
 
-impl<'a, K, V> Drain<'a, K, V> {
-    pub(super) fn new(iter: vec::Drain<'a, Bucket<K, V>>) -> Self {
+impl<'a, K, V, A: Allocator> Drain<'a, K, V, A> {
+    pub(super) fn new(iter: allocator::Drain<'a, Bucket<K, V>, A>) -> Self {
         Self { iter }
     }
 
@@ -234,25 +234,25 @@ impl<'a, K, V> Drain<'a, K, V> {
     }
 }
 
-impl<K, V> Iterator for Drain<'_, K, V> {
+impl<K, V, A: Allocator> Iterator for Drain<'_, K, V, A> {
     type Item = (K, V);
 
     iterator_methods!(Bucket::key_value);
 }
 
-impl<K, V> DoubleEndedIterator for Drain<'_, K, V> {
+impl<K, V, A: Allocator> DoubleEndedIterator for Drain<'_, K, V, A> {
     double_ended_iterator_methods!(Bucket::key_value);
 }
 
-impl<K, V> ExactSizeIterator for Drain<'_, K, V> {
+impl<K, V, A: Allocator> ExactSizeIterator for Drain<'_, K, V, A> {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<K, V> FusedIterator for Drain<'_, K, V> {}
+impl<K, V, A: Allocator> FusedIterator for Drain<'_, K, V, A> {}
 
-impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Drain<'_, K, V> {
+impl<K: fmt::Debug, V: fmt::Debug, A: Allocator> fmt::Debug for Drain<'_, K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let iter = self.iter.as_slice().iter().map(Bucket::refs);
         f.debug_list().entries(iter).finish()
@@ -385,37 +385,37 @@ impl<'a, K, V> Index<usize> for Keys<'a, K, V> {
 ///
 /// This `struct` is created by the [`IndexMap::into_keys`] method.
 /// See its documentation for more.
-pub struct IntoKeys<K, V> {
-    iter: vec::IntoIter<Bucket<K, V>>,
+pub struct IntoKeys<K, V, A: Allocator = Global> {
+    iter: allocator::IntoIter<Bucket<K, V>, A>,
 }
 
-impl<K, V> IntoKeys<K, V> {
-    pub(super) fn new(entries: Vec<Bucket<K, V>>) -> Self {
+impl<K, V, A: Allocator> IntoKeys<K, V, A> {
+    pub(super) fn new(entries: Vec<Bucket<K, V>, A>) -> Self {
         Self {
             iter: entries.into_iter(),
         }
     }
 }
 
-impl<K, V> Iterator for IntoKeys<K, V> {
+impl<K, V, A: Allocator> Iterator for IntoKeys<K, V, A> {
     type Item = K;
 
     iterator_methods!(Bucket::key);
 }
 
-impl<K, V> DoubleEndedIterator for IntoKeys<K, V> {
+impl<K, V, A: Allocator> DoubleEndedIterator for IntoKeys<K, V, A> {
     double_ended_iterator_methods!(Bucket::key);
 }
 
-impl<K, V> ExactSizeIterator for IntoKeys<K, V> {
+impl<K, V, A: Allocator> ExactSizeIterator for IntoKeys<K, V, A> {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<K, V> FusedIterator for IntoKeys<K, V> {}
+impl<K, V, A: Allocator> FusedIterator for IntoKeys<K, V, A> {}
 
-impl<K: fmt::Debug, V> fmt::Debug for IntoKeys<K, V> {
+impl<K: fmt::Debug, V, A: Allocator> fmt::Debug for IntoKeys<K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let iter = self.iter.as_slice().iter().map(Bucket::key_ref);
         f.debug_list().entries(iter).finish()
@@ -538,37 +538,37 @@ impl<K, V> Default for ValuesMut<'_, K, V> {
 ///
 /// This `struct` is created by the [`IndexMap::into_values`] method.
 /// See its documentation for more.
-pub struct IntoValues<K, V> {
-    iter: vec::IntoIter<Bucket<K, V>>,
+pub struct IntoValues<K, V, A: Allocator = Global> {
+    iter: allocator::IntoIter<Bucket<K, V>, A>,
 }
 
-impl<K, V> IntoValues<K, V> {
-    pub(super) fn new(entries: Vec<Bucket<K, V>>) -> Self {
+impl<K, V, A: Allocator> IntoValues<K, V, A> {
+    pub(super) fn new(entries: Vec<Bucket<K, V>, A>) -> Self {
         Self {
             iter: entries.into_iter(),
         }
     }
 }
 
-impl<K, V> Iterator for IntoValues<K, V> {
+impl<K, V, A: Allocator> Iterator for IntoValues<K, V, A> {
     type Item = V;
 
     iterator_methods!(Bucket::value);
 }
 
-impl<K, V> DoubleEndedIterator for IntoValues<K, V> {
+impl<K, V, A: Allocator> DoubleEndedIterator for IntoValues<K, V, A> {
     double_ended_iterator_methods!(Bucket::value);
 }
 
-impl<K, V> ExactSizeIterator for IntoValues<K, V> {
+impl<K, V, A: Allocator> ExactSizeIterator for IntoValues<K, V, A> {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<K, V> FusedIterator for IntoValues<K, V> {}
+impl<K, V, A: Allocator> FusedIterator for IntoValues<K, V, A> {}
 
-impl<K, V: fmt::Debug> fmt::Debug for IntoValues<K, V> {
+impl<K, V: fmt::Debug, A: Allocator> fmt::Debug for IntoValues<K, V, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let iter = self.iter.as_slice().iter().map(Bucket::value_ref);
         f.debug_list().entries(iter).finish()
@@ -587,25 +587,29 @@ impl<K, V> Default for IntoValues<K, V> {
 ///
 /// This `struct` is created by [`IndexMap::splice()`].
 /// See its documentation for more.
-pub struct Splice<'a, I, K, V, S>
+pub struct Splice<'a, I, K, V, S, Idx = usize, A: Allocator = Global>
 where
     I: Iterator<Item = (K, V)>,
     K: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
-    map: &'a mut IndexMap<K, V, S>,
-    tail: IndexMapCore<K, V>,
-    drain: vec::IntoIter<Bucket<K, V>>,
+    map: &'a mut IndexMap<K, V, S, Idx, A>,
+    tail: IndexMapCore<K, V, Idx, A>,
+    drain: allocator::IntoIter<Bucket<K, V>, A>,
     replace_with: I,
 }
 
-impl<'a, I, K, V, S> Splice<'a, I, K, V, S>
+impl<'a, I, K, V, S, Idx, A> Splice<'a, I, K, V, S, Idx, A>
 where
     I: Iterator<Item = (K, V)>,
     K: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator + Clone,
 {
-    pub(super) fn new<R>(map: &'a mut IndexMap<K, V, S>, range: R, replace_with: I) -> Self
+    pub(super) fn new<R>(map: &'a mut IndexMap<K, V, S, Idx, A>, range: R, replace_with: I) -> Self
     where
         R: RangeBounds<usize>,
     {
@@ -619,11 +623,13 @@ where
     }
 }
 
-impl<I, K, V, S> Drop for Splice<'_, I, K, V, S>
+impl<I, K, V, S, Idx, A> Drop for Splice<'_, I, K, V, S, Idx, A>
 where
     I: Iterator<Item = (K, V)>,
     K: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn drop(&mut self) {
         // Finish draining unconsumed items. We don't strictly *have* to do this
@@ -649,11 +655,13 @@ where
     }
 }
 
-impl<I, K, V, S> Iterator for Splice<'_, I, K, V, S>
+impl<I, K, V, S, Idx, A> Iterator for Splice<'_, I, K, V, S, Idx, A>
 where
     I: Iterator<Item = (K, V)>,
     K: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Item = (K, V);
 
@@ -666,42 +674,50 @@ where
     }
 }
 
-impl<I, K, V, S> DoubleEndedIterator for Splice<'_, I, K, V, S>
+impl<I, K, V, S, Idx, A> DoubleEndedIterator for Splice<'_, I, K, V, S, Idx, A>
 where
     I: Iterator<Item = (K, V)>,
     K: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.drain.next_back().map(Bucket::key_value)
     }
 }
 
-impl<I, K, V, S> ExactSizeIterator for Splice<'_, I, K, V, S>
+impl<I, K, V, S, Idx, A> ExactSizeIterator for Splice<'_, I, K, V, S, Idx, A>
 where
     I: Iterator<Item = (K, V)>,
     K: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn len(&self) -> usize {
         self.drain.len()
     }
 }
 
-impl<I, K, V, S> FusedIterator for Splice<'_, I, K, V, S>
+impl<I, K, V, S, Idx, A> FusedIterator for Splice<'_, I, K, V, S, Idx, A>
 where
     I: Iterator<Item = (K, V)>,
     K: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
 }
 
-impl<'a, I, K, V, S> fmt::Debug for Splice<'a, I, K, V, S>
+impl<'a, I, K, V, S, Idx, A> fmt::Debug for Splice<'a, I, K, V, S, Idx, A>
 where
     I: fmt::Debug + Iterator<Item = (K, V)>,
     K: fmt::Debug + Hash + Eq,
     V: fmt::Debug,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Follow `vec::Splice` in only printing the drain and replacement
@@ -711,3 +727,321 @@ where
             .finish()
     }
 }
+
+/// A lazy iterator producing the entries of an [`IndexMap`] whose keys are not in another map.
+///
+/// This `struct` is created by the [`IndexMap::difference`] method.
+/// See its documentation for more.
+pub struct Difference<'a, K, V, V2, S, Idx = usize, A: Allocator = Global> {
+    iter: Iter<'a, K, V>,
+    other: &'a IndexMap<K, V2, S, Idx, A>,
+}
+
+impl<'a, K, V, V2, S, Idx, A: Allocator> Difference<'a, K, V, V2, S, Idx, A> {
+    pub(super) fn new<S1, Idx1, A1>(
+        map: &'a IndexMap<K, V, S1, Idx1, A1>,
+        other: &'a IndexMap<K, V2, S, Idx, A>,
+    ) -> Self
+    where
+        Idx1: IndexType,
+        A1: Allocator,
+    {
+        Self {
+            iter: map.iter(),
+            other,
+        }
+    }
+}
+
+impl<'a, K, V, V2, S, Idx, A> Iterator for Difference<'a, K, V, V2, S, Idx, A>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.find(|&(key, _)| !other.contains_key(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<K, V, V2, S, Idx, A> DoubleEndedIterator for Difference<'_, K, V, V2, S, Idx, A>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter.rfind(|&(key, _)| !other.contains_key(key))
+    }
+}
+
+impl<K, V, V2, S, Idx, A> FusedIterator for Difference<'_, K, V, V2, S, Idx, A>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+}
+
+impl<K, V, V2, S, Idx, A: Allocator> Clone for Difference<'_, K, V, V2, S, Idx, A> {
+    fn clone(&self) -> Self {
+        Difference {
+            iter: self.iter.clone(),
+            ..*self
+        }
+    }
+}
+
+impl<K, V, V2, S, Idx, A> fmt::Debug for Difference<'_, K, V, V2, S, Idx, A>
+where
+    K: fmt::Debug + Eq + Hash,
+    V: fmt::Debug,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// A lazy iterator producing the entries of an [`IndexMap`] whose keys are also in another map,
+/// together with the other map's values.
+///
+/// This `struct` is created by the [`IndexMap::intersection_with`] method.
+/// See its documentation for more.
+pub struct Intersection<'a, K, V, V2, S, Idx = usize, A: Allocator = Global> {
+    iter: Iter<'a, K, V>,
+    other: &'a IndexMap<K, V2, S, Idx, A>,
+}
+
+impl<'a, K, V, V2, S, Idx, A: Allocator> Intersection<'a, K, V, V2, S, Idx, A> {
+    pub(super) fn new<S1, Idx1, A1>(
+        map: &'a IndexMap<K, V, S1, Idx1, A1>,
+        other: &'a IndexMap<K, V2, S, Idx, A>,
+    ) -> Self
+    where
+        Idx1: IndexType,
+        A1: Allocator,
+    {
+        Self {
+            iter: map.iter(),
+            other,
+        }
+    }
+}
+
+impl<'a, K, V, V2, S, Idx, A> Iterator for Intersection<'a, K, V, V2, S, Idx, A>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    type Item = (&'a K, &'a V, &'a V2);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter
+            .find_map(|(key, value)| Some((key, value, other.get(key)?)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<K, V, V2, S, Idx, A> DoubleEndedIterator for Intersection<'_, K, V, V2, S, Idx, A>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let other = self.other;
+        self.iter
+            .by_ref()
+            .rev()
+            .find_map(|(key, value)| Some((key, value, other.get(key)?)))
+    }
+}
+
+impl<K, V, V2, S, Idx, A> FusedIterator for Intersection<'_, K, V, V2, S, Idx, A>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+}
+
+impl<K, V, V2, S, Idx, A: Allocator> Clone for Intersection<'_, K, V, V2, S, Idx, A> {
+    fn clone(&self) -> Self {
+        Intersection {
+            iter: self.iter.clone(),
+            ..*self
+        }
+    }
+}
+
+impl<K, V, V2, S, Idx, A> fmt::Debug for Intersection<'_, K, V, V2, S, Idx, A>
+where
+    K: fmt::Debug + Eq + Hash,
+    V: fmt::Debug,
+    V2: fmt::Debug,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// A lazy iterator producing the entries of two [`IndexMap`]s whose keys are in only one of them.
+///
+/// This `struct` is created by the [`IndexMap::symmetric_difference`] method.
+/// See its documentation for more.
+pub struct SymmetricDifference<
+    'a,
+    K,
+    V,
+    S1,
+    S2,
+    Idx1 = usize,
+    Idx2 = usize,
+    A1: Allocator = Global,
+    A2: Allocator = Global,
+> {
+    #[allow(clippy::type_complexity)]
+    iter: Chain<Difference<'a, K, V, V, S2, Idx2, A2>, Difference<'a, K, V, V, S1, Idx1, A1>>,
+}
+
+impl<'a, K, V, S1, S2, Idx1, Idx2, A1, A2> SymmetricDifference<'a, K, V, S1, S2, Idx1, Idx2, A1, A2>
+where
+    K: Eq + Hash,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
+{
+    pub(super) fn new(
+        map1: &'a IndexMap<K, V, S1, Idx1, A1>,
+        map2: &'a IndexMap<K, V, S2, Idx2, A2>,
+    ) -> Self {
+        let diff1 = Difference::new(map1, map2);
+        let diff2 = Difference::new(map2, map1);
+        Self {
+            iter: diff1.chain(diff2),
+        }
+    }
+}
+
+impl<'a, K, V, S1, S2, Idx1, Idx2, A1, A2> Iterator
+    for SymmetricDifference<'a, K, V, S1, S2, Idx1, Idx2, A1, A2>
+where
+    K: Eq + Hash,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn fold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.iter.fold(init, f)
+    }
+}
+
+impl<K, V, S1, S2, Idx1, Idx2, A1, A2> DoubleEndedIterator
+    for SymmetricDifference<'_, K, V, S1, S2, Idx1, Idx2, A1, A2>
+where
+    K: Eq + Hash,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+
+    fn rfold<B, F>(self, init: B, f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        self.iter.rfold(init, f)
+    }
+}
+
+impl<K, V, S1, S2, Idx1, Idx2, A1, A2> FusedIterator
+    for SymmetricDifference<'_, K, V, S1, S2, Idx1, Idx2, A1, A2>
+where
+    K: Eq + Hash,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
+{
+}
+
+impl<K, V, S1, S2, Idx1, Idx2, A1, A2> Clone
+    for SymmetricDifference<'_, K, V, S1, S2, Idx1, Idx2, A1, A2>
+where
+    A1: Allocator,
+    A2: Allocator,
+{
+    fn clone(&self) -> Self {
+        SymmetricDifference {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<K, V, S1, S2, Idx1, Idx2, A1, A2> fmt::Debug
+    for SymmetricDifference<'_, K, V, S1, S2, Idx1, Idx2, A1, A2>
+where
+    K: fmt::Debug + Eq + Hash,
+    V: fmt::Debug,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
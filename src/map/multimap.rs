@@ -0,0 +1,751 @@
+//! [`IndexMultiMap`] is a hash table that may hold many values for the same key,
+//! where the iteration order of all the key-value pairs is the order they were
+//! appended.
+
+use super::core::IndexMapCore;
+use super::{IntoIter, Iter, IterMut, Keys, Values, ValuesMut};
+
+use crate::allocator;
+use crate::{Bucket, Entries, Equivalent, HashValue};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::{Index, IndexMut};
+use core::slice;
+
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+
+/// A hash table where each key may have any number of values, and every
+/// key-value pair is kept as its own entry in the order it was appended.
+///
+/// This preserves the interleaving of values for different keys, like the
+/// fields of HTTP headers or the parameters of a query string, while still
+/// finding all of the values for one key through the hash table.
+///
+/// The key-value pairs are indexed in a compact range without holes in the
+/// range `0..self.len()`, just like an [`IndexMap`][crate::IndexMap].
+///
+/// # Examples
+///
+/// ```
+/// use indexmap::map::IndexMultiMap;
+///
+/// let mut query = IndexMultiMap::new();
+/// query.insert_append("tag", "rust");
+/// query.insert_append("page", "2");
+/// query.insert_append("tag", "hash");
+///
+/// assert!(query.get_all("tag").eq(&["rust", "hash"]));
+/// assert_eq!(query.get_index(1), Some((&"page", &"2")));
+/// assert!(query.keys().eq(&["tag", "page", "tag"]));
+///
+/// assert_eq!(query.remove_all("tag"), ["rust", "hash"]);
+/// assert_eq!(query.len(), 1);
+/// ```
+#[cfg(feature = "std")]
+pub struct IndexMultiMap<K, V, S = RandomState> {
+    pairs: Pairs<K, V>,
+    hash_builder: S,
+}
+#[cfg(not(feature = "std"))]
+pub struct IndexMultiMap<K, V, S> {
+    pairs: Pairs<K, V>,
+    hash_builder: S,
+}
+
+/// The storage of an [`IndexMultiMap`], which doesn't depend on the hasher.
+///
+/// Every distinct key is indexed once in `keys`, by the sorted positions of its
+/// pairs in `entries`. The first of those positions holds the key to compare,
+/// so lookups and appends only probe the distinct keys, no matter how many
+/// values a key has.
+struct Pairs<K, V> {
+    entries: Vec<Bucket<K, V>>,
+    keys: IndexMapCore<Vec<usize>, ()>,
+}
+
+/// Look up the positions of a key, comparing it with the key at the first position.
+struct KeyOf<'a, Q: ?Sized, K, V> {
+    key: &'a Q,
+    entries: &'a [Bucket<K, V>],
+}
+
+impl<Q, K, V> Equivalent<Vec<usize>> for KeyOf<'_, Q, K, V>
+where
+    Q: ?Sized + Equivalent<K>,
+{
+    fn equivalent(&self, positions: &Vec<usize>) -> bool {
+        self.key.equivalent(&self.entries[positions[0]].key)
+    }
+}
+
+/// Look up the positions that include a particular pair, without comparing keys.
+struct PositionOf(usize);
+
+impl Equivalent<Vec<usize>> for PositionOf {
+    fn equivalent(&self, positions: &Vec<usize>) -> bool {
+        positions.binary_search(&self.0).is_ok()
+    }
+}
+
+impl<K, V> Pairs<K, V> {
+    /// Return the index in `keys` of the key of the pair at `index`.
+    fn key_index_of(&self, index: usize) -> usize {
+        let hash = self.entries[index].hash;
+        let found = self.keys.get_index_of(hash, &PositionOf(index));
+        found.expect("every pair has an indexed key")
+    }
+
+    fn positions_mut(&mut self) -> impl Iterator<Item = &mut Vec<usize>> {
+        self.keys
+            .as_entries_mut()
+            .iter_mut()
+            .map(|bucket| &mut bucket.key)
+    }
+
+    /// Remove the pair at `index` from the positions of its key,
+    /// and the key itself if that was its last pair.
+    fn unlink(&mut self, index: usize) {
+        let k = self.key_index_of(index);
+        let positions = &mut self.keys.as_entries_mut()[k].key;
+        if positions.len() == 1 {
+            self.keys.swap_remove_index(k);
+        } else if let Ok(i) = positions.binary_search(&index) {
+            positions.remove(i);
+        }
+    }
+}
+
+impl<K, V, S> Clone for IndexMultiMap<K, V, S>
+where
+    K: Clone,
+    V: Clone,
+    S: Clone,
+{
+    fn clone(&self) -> Self {
+        IndexMultiMap {
+            pairs: Pairs {
+                entries: self.pairs.entries.clone(),
+                keys: self.pairs.keys.clone(),
+            },
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+
+    fn clone_from(&mut self, other: &Self) {
+        self.pairs.entries.clone_from(&other.pairs.entries);
+        self.pairs.keys.clone_from(&other.pairs.keys);
+        self.hash_builder.clone_from(&other.hash_builder);
+    }
+}
+
+impl<K, V, S> fmt::Debug for IndexMultiMap<K, V, S>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<K, V> IndexMultiMap<K, V> {
+    /// Create a new multimap. (Does not allocate.)
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create a new multimap with capacity for `n` key-value pairs. (Does not
+    /// allocate if `n` is zero.)
+    ///
+    /// Computes in **O(n)** time.
+    #[inline]
+    pub fn with_capacity(n: usize) -> Self {
+        Self::with_capacity_and_hasher(n, <_>::default())
+    }
+}
+
+impl<K, V, S> IndexMultiMap<K, V, S> {
+    /// Create a new multimap with capacity for `n` key-value pairs. (Does not
+    /// allocate if `n` is zero.)
+    ///
+    /// Computes in **O(n)** time.
+    #[inline]
+    pub fn with_capacity_and_hasher(n: usize, hash_builder: S) -> Self {
+        if n == 0 {
+            Self::with_hasher(hash_builder)
+        } else {
+            IndexMultiMap {
+                pairs: Pairs {
+                    entries: Vec::with_capacity(n),
+                    keys: IndexMapCore::with_capacity(n),
+                },
+                hash_builder,
+            }
+        }
+    }
+
+    /// Create a new multimap with `hash_builder`.
+    ///
+    /// This function is `const`, so it
+    /// can be called in `static` contexts.
+    pub const fn with_hasher(hash_builder: S) -> Self {
+        IndexMultiMap {
+            pairs: Pairs {
+                entries: Vec::new(),
+                keys: IndexMapCore::new(),
+            },
+            hash_builder,
+        }
+    }
+
+    /// Return the number of key-value pairs the multimap can hold without reallocating.
+    ///
+    /// Computes in **O(1)** time.
+    pub fn capacity(&self) -> usize {
+        Ord::min(self.pairs.entries.capacity(), self.pairs.keys.capacity())
+    }
+
+    /// Return a reference to the multimap's `BuildHasher`.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Return the number of key-value pairs in the multimap, counting each
+    /// value of a key separately.
+    ///
+    /// Computes in **O(1)** time.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pairs.entries.len()
+    }
+
+    /// Returns true if the multimap contains no elements.
+    ///
+    /// Computes in **O(1)** time.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return an iterator over all of the key-value pairs, in their order
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter::new(&self.pairs.entries)
+    }
+
+    /// Return an iterator over all of the key-value pairs, in their order
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut::new(&mut self.pairs.entries)
+    }
+
+    /// Return an iterator over the keys of all the key-value pairs, in their order
+    ///
+    /// A key is repeated for each of its values.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys::new(&self.pairs.entries)
+    }
+
+    /// Return an iterator over all of the values, in their order
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values::new(&self.pairs.entries)
+    }
+
+    /// Return an iterator over mutable references to all of the values, in their order
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut::new(&mut self.pairs.entries)
+    }
+
+    /// Remove all key-value pairs in the multimap, while preserving its capacity.
+    ///
+    /// Computes in **O(n)** time.
+    pub fn clear(&mut self) {
+        self.pairs.entries.clear();
+        self.pairs.keys.clear();
+    }
+
+    /// Reserve capacity for `additional` more key-value pairs.
+    ///
+    /// Computes in **O(n)** time.
+    pub fn reserve(&mut self, additional: usize) {
+        self.pairs.entries.reserve(additional);
+        self.pairs.keys.reserve(additional);
+    }
+
+    /// Get a key-value pair by index
+    ///
+    /// Valid indices are *0 <= index < self.len()*
+    ///
+    /// Computes in **O(1)** time.
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.pairs.entries.get(index).map(Bucket::refs)
+    }
+
+    /// Get a key-value pair by index, with mutable access to the value
+    ///
+    /// Valid indices are *0 <= index < self.len()*
+    ///
+    /// Computes in **O(1)** time.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        self.pairs.entries.get_mut(index).map(Bucket::ref_mut)
+    }
+
+    /// Get the first key-value pair
+    ///
+    /// Computes in **O(1)** time.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.pairs.entries.first().map(Bucket::refs)
+    }
+
+    /// Get the last key-value pair
+    ///
+    /// Computes in **O(1)** time.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.pairs.entries.last().map(Bucket::refs)
+    }
+
+    /// Remove the key-value pair by index
+    ///
+    /// Valid indices are *0 <= index < self.len()*
+    ///
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by shifting all of the
+    /// elements that follow it, preserving their relative order.
+    /// **This perturbs the index of all of those elements!**
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.len() {
+            return None;
+        }
+        self.pairs.unlink(index);
+        for positions in self.pairs.positions_mut() {
+            let start = positions.partition_point(|&i| i < index);
+            for i in &mut positions[start..] {
+                *i -= 1;
+            }
+        }
+        Some(self.pairs.entries.remove(index).key_value())
+    }
+
+    /// Remove the last key-value pair
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let index = self.len().checked_sub(1)?;
+        self.pairs.unlink(index);
+        self.pairs.entries.pop().map(Bucket::key_value)
+    }
+
+    /// Scan through each key-value pair in the multimap and keep those where the
+    /// closure `keep` returns `true`.
+    ///
+    /// The elements are visited in order, and remaining elements keep their
+    /// order.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut key_indices = vec![0; self.len()];
+        for (k, positions) in self.pairs.positions_mut().enumerate() {
+            for &i in &*positions {
+                key_indices[i] = k;
+            }
+            positions.clear();
+        }
+
+        let keys = self.pairs.keys.as_entries_mut();
+        let mut key_indices = key_indices.into_iter();
+        let mut kept = 0;
+        self.pairs.entries.retain_mut(|entry| {
+            let k = key_indices.next().unwrap();
+            let keep = keep(&entry.key, &mut entry.value);
+            if keep {
+                keys[k].key.push(kept);
+                kept += 1;
+            }
+            keep
+        });
+        self.pairs
+            .keys
+            .retain_in_order(|positions, ()| !positions.is_empty());
+    }
+}
+
+impl<K, V, S> IndexMultiMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn hash<Q: ?Sized + Hash>(&self, key: &Q) -> HashValue {
+        let mut h = self.hash_builder.build_hasher();
+        key.hash(&mut h);
+        HashValue(h.finish() as usize)
+    }
+
+    /// Return the positions of all of the pairs for `key`, in order.
+    fn positions_of<Q>(&self, key: &Q) -> &[usize]
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        if self.is_empty() {
+            return &[];
+        }
+        let hash = self.hash(key);
+        let entries = &*self.pairs.entries;
+        match self.pairs.keys.get_index_of(hash, &KeyOf { key, entries }) {
+            Some(k) => &self.pairs.keys.as_entries()[k].key,
+            None => &[],
+        }
+    }
+
+    /// Append a key-value pair to the multimap, and return its index.
+    ///
+    /// This never replaces an existing value, even if an equivalent key
+    /// is already present, so the new pair is always last in order.
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn insert_append(&mut self, key: K, value: V) -> usize {
+        let hash = self.hash(&key);
+        let index = self.pairs.entries.len();
+        let entries = &*self.pairs.entries;
+        let keys = &mut self.pairs.keys;
+        match keys.get_index_of(hash, &KeyOf { key: &key, entries }) {
+            Some(k) => keys.as_entries_mut()[k].key.push(index),
+            None => {
+                keys.push(hash, vec![index], ());
+            }
+        }
+        self.pairs.entries.push(Bucket { hash, key, value });
+        index
+    }
+
+    /// Return `true` if an equivalent to `key` exists in the multimap.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        !self.positions_of(key).is_empty()
+    }
+
+    /// Return the number of values for `key`.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn count<Q>(&self, key: &Q) -> usize
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        self.positions_of(key).len()
+    }
+
+    /// Return a reference to the first value for `key`, if it is present,
+    /// else `None`.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let &i = self.positions_of(key).first()?;
+        Some(&self.pairs.entries[i].value)
+    }
+
+    /// Return an iterator over all of the values for `key`, in their order.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_all<Q>(&self, key: &Q) -> GetAll<'_, K, V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        GetAll {
+            entries: &self.pairs.entries,
+            indices: self.positions_of(key).iter(),
+        }
+    }
+
+    /// Return an iterator over mutable references to all of the values for `key`,
+    /// in their order.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_all_mut<Q>(&mut self, key: &Q) -> GetAllMut<'_, K, V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let k = if self.is_empty() {
+            None
+        } else {
+            let hash = self.hash(key);
+            let entries = &*self.pairs.entries;
+            self.pairs.keys.get_index_of(hash, &KeyOf { key, entries })
+        };
+        let indices = match k {
+            Some(k) => self.pairs.keys.as_entries()[k].key.iter(),
+            None => [].iter(),
+        };
+        GetAllMut {
+            entries: &mut self.pairs.entries,
+            offset: 0,
+            indices,
+        }
+    }
+
+    /// Remove all of the key-value pairs for `key`, and return their values in order.
+    ///
+    /// Like [`IndexMap::shift_remove`][crate::IndexMap::shift_remove], this
+    /// preserves the order of the remaining elements.
+    /// **This perturbs the index of all of the elements that followed them!**
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn remove_all<Q>(&mut self, key: &Q) -> Vec<V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let hash = self.hash(key);
+        let entries = &*self.pairs.entries;
+        let removed = match self.pairs.keys.get_index_of(hash, &KeyOf { key, entries }) {
+            Some(k) => self.pairs.keys.swap_remove_index(k).unwrap().0,
+            None => return Vec::new(),
+        };
+
+        // Shift the positions of the other keys down past the removed pairs.
+        for positions in self.pairs.positions_mut() {
+            for i in positions.iter_mut() {
+                *i -= removed.partition_point(|&r| r < *i);
+            }
+        }
+
+        // Take out everything from the first match, then put back the rest.
+        let start = removed[0];
+        let tail: Vec<_> = self.pairs.entries.drain(start..).collect();
+        let mut values = Vec::with_capacity(removed.len());
+        let mut removed = removed.iter().peekable();
+        for (i, entry) in (start..).zip(tail) {
+            if removed.next_if_eq(&&i).is_some() {
+                values.push(entry.value);
+            } else {
+                self.pairs.entries.push(entry);
+            }
+        }
+        values
+    }
+}
+
+/// Access [`IndexMultiMap`] values at indexed positions.
+impl<K, V, S> Index<usize> for IndexMultiMap<K, V, S> {
+    type Output = V;
+
+    /// Returns a reference to the value at the supplied `index`.
+    ///
+    /// ***Panics*** if `index` is out of bounds.
+    fn index(&self, index: usize) -> &V {
+        self.get_index(index)
+            .expect("IndexMultiMap: index out of bounds")
+            .1
+    }
+}
+
+/// Access [`IndexMultiMap`] values at indexed positions.
+impl<K, V, S> IndexMut<usize> for IndexMultiMap<K, V, S> {
+    /// Returns a mutable reference to the value at the supplied `index`.
+    ///
+    /// ***Panics*** if `index` is out of bounds.
+    fn index_mut(&mut self, index: usize) -> &mut V {
+        self.get_index_mut(index)
+            .expect("IndexMultiMap: index out of bounds")
+            .1
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a IndexMultiMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut IndexMultiMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V, S> IntoIterator for IndexMultiMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(allocator::from_std_vec(self.pairs.entries))
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for IndexMultiMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    /// Create an `IndexMultiMap` from the sequence of key-value pairs in the
+    /// iterable, keeping all of them in order.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
+        let iter = iterable.into_iter();
+        let (low, _) = iter.size_hint();
+        let mut map = Self::with_capacity_and_hasher(low, <_>::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for IndexMultiMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Extend the multimap with all key-value pairs in the iterable.
+    ///
+    /// This is equivalent to calling [`insert_append`][IndexMultiMap::insert_append]
+    /// for each of them in order.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+        let iter = iterable.into_iter();
+        let (low, _) = iter.size_hint();
+        self.reserve(low);
+        iter.for_each(move |(k, v)| {
+            self.insert_append(k, v);
+        });
+    }
+}
+
+impl<K, V, S> Default for IndexMultiMap<K, V, S>
+where
+    S: Default,
+{
+    /// Return an empty [`IndexMultiMap`]
+    fn default() -> Self {
+        Self::with_capacity_and_hasher(0, S::default())
+    }
+}
+
+/// An iterator over the values for one key of an [`IndexMultiMap`].
+///
+/// This `struct` is created by the [`IndexMultiMap::get_all`] method.
+/// See its documentation for more.
+pub struct GetAll<'a, K, V> {
+    entries: &'a [Bucket<K, V>],
+    indices: slice::Iter<'a, usize>,
+}
+
+impl<'a, K, V> Iterator for GetAll<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &i = self.indices.next()?;
+        Some(&self.entries[i].value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for GetAll<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let &i = self.indices.next_back()?;
+        Some(&self.entries[i].value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for GetAll<'_, K, V> {
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+}
+
+impl<K, V> FusedIterator for GetAll<'_, K, V> {}
+
+impl<K, V> Clone for GetAll<'_, K, V> {
+    fn clone(&self) -> Self {
+        GetAll {
+            entries: self.entries,
+            indices: self.indices.clone(),
+        }
+    }
+}
+
+impl<K, V: fmt::Debug> fmt::Debug for GetAll<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// A mutable iterator over the values for one key of an [`IndexMultiMap`].
+///
+/// This `struct` is created by the [`IndexMultiMap::get_all_mut`] method.
+/// See its documentation for more.
+pub struct GetAllMut<'a, K, V> {
+    /// The entries that may still be yielded, starting at index `offset`.
+    entries: &'a mut [Bucket<K, V>],
+    offset: usize,
+    indices: slice::Iter<'a, usize>,
+}
+
+impl<'a, K, V> Iterator for GetAllMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let &i = self.indices.next()?;
+        let entries = mem::take(&mut self.entries);
+        let (entry, rest) = entries[i - self.offset..].split_first_mut()?;
+        self.entries = rest;
+        self.offset = i + 1;
+        Some(&mut entry.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for GetAllMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let &i = self.indices.next_back()?;
+        let entries = mem::take(&mut self.entries);
+        let (rest, tail) = entries.split_at_mut(i - self.offset);
+        self.entries = rest;
+        Some(&mut tail[0].value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for GetAllMut<'_, K, V> {
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+}
+
+impl<K, V> FusedIterator for GetAllMut<'_, K, V> {}
+
+impl<K, V: fmt::Debug> fmt::Debug for GetAllMut<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let offset = self.offset;
+        let iter = self
+            .indices
+            .as_slice()
+            .iter()
+            .map(|&i| &self.entries[i - offset].value);
+        f.debug_list().entries(iter).finish()
+    }
+}
@@ -0,0 +1,156 @@
+//! Functions to serialize and deserialize an [`IndexMap`] with string keys that
+//! borrow from the input when possible.
+//!
+//! An `IndexMap<&'de str, V>` already deserializes without allocating its keys,
+//! but it fails for any key that the format can't borrow, like a JSON string
+//! with escape sequences. The `Deserialize` implementation of `Cow<'de, str>`
+//! always allocates instead. This module deserializes an
+//! `IndexMap<Cow<'de, str>, V>` whose keys borrow from the input whenever the
+//! format allows it, and only allocate otherwise.
+//!
+//! This module may be used in a field attribute for derived implementations:
+//!
+//! ```
+//! # use indexmap::IndexMap;
+//! # use serde_derive::{Deserialize, Serialize};
+//! # use std::borrow::Cow;
+//! #[derive(Deserialize, Serialize)]
+//! struct Data<'a> {
+//!     #[serde(borrow, with = "indexmap::map::serde_borrow")]
+//!     map: IndexMap<Cow<'a, str>, u64>,
+//!     // ...
+//! }
+//! ```
+
+use serde::de::{Deserialize, Deserializer, Error, MapAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::fmt::{self, Formatter};
+use core::hash::BuildHasher;
+use core::marker::PhantomData;
+
+use crate::allocator::Allocator;
+use crate::map::IndexMapSeed;
+use crate::{IndexMap, IndexType};
+
+/// Serializes an [`IndexMap`] with string keys as a map, like its [`Serialize`]
+/// implementation.
+///
+/// This function may be used in a field attribute for deriving [`Serialize`]:
+///
+/// ```
+/// # use indexmap::IndexMap;
+/// # use serde_derive::Serialize;
+/// # use std::borrow::Cow;
+/// #[derive(Serialize)]
+/// struct Data<'a> {
+///     #[serde(serialize_with = "indexmap::map::serde_borrow::serialize")]
+///     map: IndexMap<Cow<'a, str>, u64>,
+///     // ...
+/// }
+/// ```
+pub fn serialize<V, S, T, Idx, A>(
+    map: &IndexMap<Cow<'_, str>, V, S, Idx, A>,
+    serializer: T,
+) -> Result<T::Ok, T::Error>
+where
+    V: Serialize,
+    T: Serializer,
+    Idx: IndexType,
+    A: Allocator,
+{
+    serializer.collect_map(map.iter().map(|(key, value)| (&**key, value)))
+}
+
+/// A string key that borrows from the input when possible
+struct BorrowedKey<'de>(Cow<'de, str>);
+
+impl<'de> Deserialize<'de> for BorrowedKey<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(BorrowedKeyVisitor)
+    }
+}
+
+struct BorrowedKeyVisitor;
+
+impl<'de> Visitor<'de> for BorrowedKeyVisitor {
+    type Value = BorrowedKey<'de>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a string")
+    }
+
+    fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(BorrowedKey(Cow::Borrowed(v)))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(BorrowedKey(Cow::Owned(String::from(v))))
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(BorrowedKey(Cow::Owned(v)))
+    }
+}
+
+/// Visitor to deserialize an `IndexMap` with borrowed string keys
+struct BorrowVisitor<V, S, Idx>(PhantomData<(V, S, Idx)>);
+
+impl<'de, V, S, Idx> Visitor<'de> for BorrowVisitor<V, S, Idx>
+where
+    V: Deserialize<'de>,
+    S: Default + BuildHasher,
+    Idx: IndexType,
+{
+    type Value = IndexMap<Cow<'de, str>, V, S, Idx>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a map with string keys")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let size_hint = map.size_hint();
+        let next_entry = || {
+            Ok(map
+                .next_entry()?
+                .map(|(BorrowedKey(key), value)| (key, value)))
+        };
+        IndexMapSeed::new(S::default()).build(size_hint, next_entry)
+    }
+}
+
+/// Deserializes an [`IndexMap`] from a map, borrowing its string keys from
+/// the input when possible.
+///
+/// This function may be used in a field attribute for deriving [`Deserialize`]:
+///
+/// ```
+/// # use indexmap::IndexMap;
+/// # use serde_derive::Deserialize;
+/// # use std::borrow::Cow;
+/// #[derive(Deserialize)]
+/// struct Data<'a> {
+///     #[serde(borrow, deserialize_with = "indexmap::map::serde_borrow::deserialize")]
+///     map: IndexMap<Cow<'a, str>, u64>,
+///     // ...
+/// }
+/// ```
+pub fn deserialize<'de, D, V, S, Idx>(
+    deserializer: D,
+) -> Result<IndexMap<Cow<'de, str>, V, S, Idx>, D::Error>
+where
+    D: Deserializer<'de>,
+    V: Deserialize<'de>,
+    S: Default + BuildHasher,
+    Idx: IndexType,
+{
+    deserializer.deserialize_map(BorrowVisitor(PhantomData))
+}
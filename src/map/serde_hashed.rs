@@ -0,0 +1,237 @@
+//! Functions to serialize and deserialize an [`IndexMap`] together with the
+//! hash values of its keys.
+//!
+//! The default `serde` implementation only stores the keys and values, so
+//! deserializing has to hash every key again. This module also stores the
+//! hash value of each entry, and a fingerprint of the hasher that computed
+//! them. If the deserializing map's hasher has the same fingerprint, then the
+//! hash table is rebuilt from the stored hash values without hashing any keys.
+//! Otherwise, the entries are inserted one by one, like the default
+//! implementation. This is mostly useful for large maps in binary formats,
+//! with a hasher that is deterministic across processes, since a randomly
+//! seeded hasher like `RandomState` will never match.
+//!
+//! The serialized form is a tuple of the fingerprint, as a `u64`, and a
+//! sequence of `(hash, key, value)` tuples, where each hash is a `u64`. The
+//! fingerprint is the hash of the string `"indexmap::map::serde_hashed"`,
+//! truncated to a `usize` like the stored hashes, so maps serialized on a
+//! target with a different pointer width are also rehashed.
+//!
+//! **Note:** when the fingerprint matches, the stored hash values are trusted,
+//! and so is the uniqueness of the keys. Deserializing data that was not
+//! produced by [`serialize`] with an equivalent hasher may then result in a
+//! map that can't find its own keys, or has duplicate keys. The behavior
+//! resulting from either of these is not specified, but will be encapsulated
+//! to the `IndexMap` and will not result in undefined behavior.
+//!
+//! This module may be used in a field attribute for derived implementations:
+//!
+//! ```
+//! # use indexmap::IndexMap;
+//! # use serde_derive::{Deserialize, Serialize};
+//! # type FixedState = std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>;
+//! #[derive(Deserialize, Serialize)]
+//! struct Data {
+//!     #[serde(with = "indexmap::map::serde_hashed")]
+//!     map: IndexMap<i32, u64, FixedState>,
+//!     // ...
+//! }
+//! ```
+
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeTuple, Serializer};
+
+use core::fmt::{self, Formatter};
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::marker::PhantomData;
+
+use super::core::IndexMapCore;
+use crate::allocator::{Allocator, Global, Vec};
+use crate::serde::cautious_capacity;
+use crate::{Bucket, Entries, HashValue, IndexMap, IndexType};
+
+/// Returns the fingerprint of a hasher, which is the hash of a fixed probe.
+fn fingerprint<S: BuildHasher>(hash_builder: &S) -> u64 {
+    let mut hasher = hash_builder.build_hasher();
+    "indexmap::map::serde_hashed".hash(&mut hasher);
+    hasher.finish() as usize as u64
+}
+
+/// Serializes the entries of an `IndexMap` with their hash values
+struct HashedEntries<'a, K, V>(&'a [Bucket<K, V>]);
+
+impl<K, V> Serialize for HashedEntries<'_, K, V>
+where
+    K: Serialize,
+    V: Serialize,
+{
+    fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
+    where
+        T: Serializer,
+    {
+        serializer.collect_seq(
+            self.0
+                .iter()
+                .map(|bucket| (bucket.hash.get(), &bucket.key, &bucket.value)),
+        )
+    }
+}
+
+/// Serializes an [`IndexMap`] with the hash values of its keys and the
+/// fingerprint of its hasher.
+///
+/// This function may be used in a field attribute for deriving [`Serialize`]:
+///
+/// ```
+/// # use indexmap::IndexMap;
+/// # use serde_derive::Serialize;
+/// # type FixedState = std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>;
+/// #[derive(Serialize)]
+/// struct Data {
+///     #[serde(serialize_with = "indexmap::map::serde_hashed::serialize")]
+///     map: IndexMap<i32, u64, FixedState>,
+///     // ...
+/// }
+/// ```
+pub fn serialize<K, V, S, T, Idx, A>(
+    map: &IndexMap<K, V, S, Idx, A>,
+    serializer: T,
+) -> Result<T::Ok, T::Error>
+where
+    K: Serialize,
+    V: Serialize,
+    S: BuildHasher,
+    T: Serializer,
+    Idx: IndexType,
+    A: Allocator,
+{
+    let mut tuple = serializer.serialize_tuple(2)?;
+    tuple.serialize_element(&fingerprint(map.hasher()))?;
+    tuple.serialize_element(&HashedEntries(map.as_entries()))?;
+    tuple.end()
+}
+
+/// Seed to deserialize the entries of an `IndexMap` with their hash values
+struct HashedEntriesSeed<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K, V> DeserializeSeed<'de> for HashedEntriesSeed<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = Vec<Bucket<K, V>, Global>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, K, V> Visitor<'de> for HashedEntriesSeed<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = Vec<Bucket<K, V>, Global>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a sequence of hashed entries")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let capacity = cautious_capacity::<Bucket<K, V>>(seq.size_hint());
+        let mut entries = Vec::with_capacity_in(capacity, Global);
+
+        while let Some((hash, key, value)) = seq.next_element::<(u64, K, V)>()? {
+            let hash = HashValue(hash as usize);
+            entries.push(Bucket { hash, key, value });
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Visitor to deserialize an `IndexMap` with the hash values of its keys
+struct HashedVisitor<K, V, S, Idx>(PhantomData<(K, V, S, Idx)>);
+
+impl<'de, K, V, S, Idx> Visitor<'de> for HashedVisitor<K, V, S, Idx>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: Default + BuildHasher,
+    Idx: IndexType,
+{
+    type Value = IndexMap<K, V, S, Idx>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "a hasher fingerprint and a sequence of hashed entries"
+        )
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let stored: u64 = seq
+            .next_element()?
+            .ok_or_else(|| Error::invalid_length(0, &self))?;
+        let entries = seq
+            .next_element_seed(HashedEntriesSeed(PhantomData))?
+            .ok_or_else(|| Error::invalid_length(1, &self))?;
+
+        if entries.len() > Idx::MAX_LEN {
+            return Err(Error::custom(format_args!(
+                "too many entries for the index type: {}",
+                entries.len()
+            )));
+        }
+
+        let hash_builder = S::default();
+        if stored == fingerprint(&hash_builder) {
+            let core = IndexMapCore::from_entries_unchecked(entries);
+            return Ok(IndexMap { core, hash_builder });
+        }
+
+        let mut map = IndexMap::with_capacity_and_hasher_idx(entries.len(), hash_builder);
+        for bucket in entries {
+            map.insert(bucket.key, bucket.value);
+        }
+        Ok(map)
+    }
+}
+
+/// Deserializes an [`IndexMap`] with the hash values of its keys, which are
+/// used as-is if the fingerprint of the hasher matches.
+///
+/// This function may be used in a field attribute for deriving [`Deserialize`]:
+///
+/// ```
+/// # use indexmap::IndexMap;
+/// # use serde_derive::Deserialize;
+/// # type FixedState = std::hash::BuildHasherDefault<std::collections::hash_map::DefaultHasher>;
+/// #[derive(Deserialize)]
+/// struct Data {
+///     #[serde(deserialize_with = "indexmap::map::serde_hashed::deserialize")]
+///     map: IndexMap<i32, u64, FixedState>,
+///     // ...
+/// }
+/// ```
+pub fn deserialize<'de, D, K, V, S, Idx>(
+    deserializer: D,
+) -> Result<IndexMap<K, V, S, Idx>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: Default + BuildHasher,
+    Idx: IndexType,
+{
+    deserializer.deserialize_tuple(2, HashedVisitor(PhantomData))
+}
@@ -18,16 +18,20 @@
 //! }
 //! ```
 
-use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, SeqAccess, Visitor};
 use serde::ser::{Serialize, Serializer};
 
+use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::fmt::{self, Formatter};
 use core::hash::{BuildHasher, Hash};
 use core::marker::PhantomData;
 
+use crate::allocator::Allocator;
 use crate::map::Slice as MapSlice;
+use crate::serde::cautious_capacity;
 use crate::set::Slice as SetSlice;
-use crate::IndexMap;
+use crate::{Bucket, IndexMap, IndexType};
 
 /// Serializes a [`map::Slice`][MapSlice] as an ordered sequence.
 ///
@@ -59,6 +63,99 @@ where
     }
 }
 
+/// Deserializes a boxed [`map::Slice`][MapSlice] from an ordered sequence.
+///
+/// This is the counterpart of its [`Serialize`] implementation, reading a
+/// sequence of `(key, value)` pairs. Since a slice has no hash table, the keys
+/// are not required to be unique, and they are not hashed. See
+/// [`crate::map::serde_slice`] to use the map format instead.
+impl<'de, K, V> Deserialize<'de> for Box<MapSlice<K, V>>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(MapSliceVisitor(PhantomData))
+    }
+}
+
+/// Visitor to deserialize a *sequenced* boxed `map::Slice`
+struct MapSliceVisitor<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K, V> Visitor<'de> for MapSliceVisitor<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = Box<MapSlice<K, V>>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a sequenced map slice")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let capacity = cautious_capacity::<Bucket<K, V>>(seq.size_hint());
+        let mut entries = Vec::with_capacity(capacity);
+
+        while let Some((key, value)) = seq.next_element()? {
+            entries.push(Bucket::unhashed(key, value));
+        }
+
+        Ok(MapSlice::from_entries(entries))
+    }
+}
+
+/// Deserializes a boxed [`set::Slice`][SetSlice] from an ordered sequence.
+///
+/// This is the counterpart of its [`Serialize`] implementation, and the same
+/// format as an [`IndexSet`][crate::IndexSet]. Since a slice has no hash table,
+/// the values are not required to be unique, and they are not hashed.
+impl<'de, T> Deserialize<'de> for Box<SetSlice<T>>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(SetSliceVisitor(PhantomData))
+    }
+}
+
+/// Visitor to deserialize a boxed `set::Slice`
+struct SetSliceVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for SetSliceVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Box<SetSlice<T>>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a set slice")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let capacity = cautious_capacity::<Bucket<T, ()>>(seq.size_hint());
+        let mut entries = Vec::with_capacity(capacity);
+
+        while let Some(value) = seq.next_element()? {
+            entries.push(Bucket::unhashed(value, ()));
+        }
+
+        Ok(SetSlice::from_entries(entries))
+    }
+}
+
 /// Serializes an [`IndexMap`] as an ordered sequence.
 ///
 /// This function may be used in a field attribute for deriving [`Serialize`]:
@@ -73,25 +170,92 @@ where
 ///     // ...
 /// }
 /// ```
-pub fn serialize<K, V, S, T>(map: &IndexMap<K, V, S>, serializer: T) -> Result<T::Ok, T::Error>
+pub fn serialize<K, V, S, T, Idx, A>(
+    map: &IndexMap<K, V, S, Idx, A>,
+    serializer: T,
+) -> Result<T::Ok, T::Error>
 where
     K: Serialize,
     V: Serialize,
     T: Serializer,
+    Idx: IndexType,
+    A: Allocator,
 {
     serializer.collect_seq(map)
 }
 
+/// A [`DeserializeSeed`] that creates an [`IndexMap`] with a given hasher,
+/// from an ordered sequence.
+///
+/// This is like [`deserialize`], but it takes the hasher instance instead of
+/// needing `S: Default`. See [`map::IndexMapSeed`][crate::map::IndexMapSeed]
+/// for the normal map format.
+pub struct IndexMapSeed<K, V, S, Idx = usize>(crate::map::IndexMapSeed<K, V, S, Idx>);
+
+impl<K, V, S, Idx> IndexMapSeed<K, V, S, Idx> {
+    /// Create a seed for an `IndexMap` that will use the given hasher.
+    pub fn new(hash_builder: S) -> Self {
+        IndexMapSeed(crate::map::IndexMapSeed::new(hash_builder))
+    }
+
+    /// Limit the capacity that is allocated up front to `limit` entries.
+    ///
+    /// By default, the map preallocates as many entries as the size hint of
+    /// the format, up to about 1 MiB. This doesn't limit how many entries can
+    /// be deserialized, since the map still grows as usual past its initial
+    /// capacity.
+    pub fn with_capacity_limit(self, limit: usize) -> Self {
+        IndexMapSeed(self.0.with_capacity_limit(limit))
+    }
+
+    /// Fail to deserialize a map with more than `max_len` entries.
+    ///
+    /// See [`map::IndexMapSeed::with_max_len`][crate::map::IndexMapSeed::with_max_len].
+    pub fn with_max_len(self, max_len: usize) -> Self {
+        IndexMapSeed(self.0.with_max_len(max_len))
+    }
+}
+
+impl<K, V, S: Clone, Idx> Clone for IndexMapSeed<K, V, S, Idx> {
+    fn clone(&self) -> Self {
+        IndexMapSeed(self.0.clone())
+    }
+}
+
+impl<K, V, S: fmt::Debug, Idx> fmt::Debug for IndexMapSeed<K, V, S, Idx> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IndexMapSeed").field(&self.0).finish()
+    }
+}
+
+impl<'de, K, V, S, Idx> DeserializeSeed<'de> for IndexMapSeed<K, V, S, Idx>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: BuildHasher,
+    Idx: IndexType,
+{
+    type Value = IndexMap<K, V, S, Idx>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(SeqVisitor(self.0))
+    }
+}
+
 /// Visitor to deserialize a *sequenced* `IndexMap`
-struct SeqVisitor<K, V, S>(PhantomData<(K, V, S)>);
+struct SeqVisitor<K, V, S, Idx>(crate::map::IndexMapSeed<K, V, S, Idx>);
 
-impl<'de, K, V, S> Visitor<'de> for SeqVisitor<K, V, S>
+impl<'de, K, V, S, Idx> Visitor<'de> for SeqVisitor<K, V, S, Idx>
 where
     K: Deserialize<'de> + Eq + Hash,
     V: Deserialize<'de>,
-    S: Default + BuildHasher,
+    S: BuildHasher,
+    Idx: IndexType,
 {
-    type Value = IndexMap<K, V, S>;
+    type Value = IndexMap<K, V, S, Idx>;
 
     fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         write!(formatter, "a sequenced map")
@@ -101,14 +265,7 @@ where
     where
         A: SeqAccess<'de>,
     {
-        let capacity = seq.size_hint().unwrap_or(0);
-        let mut map = IndexMap::with_capacity_and_hasher(capacity, S::default());
-
-        while let Some((key, value)) = seq.next_element()? {
-            map.insert(key, value);
-        }
-
-        Ok(map)
+        self.0.build(seq.size_hint(), || seq.next_element())
     }
 }
 
@@ -126,12 +283,15 @@ where
 ///     // ...
 /// }
 /// ```
-pub fn deserialize<'de, D, K, V, S>(deserializer: D) -> Result<IndexMap<K, V, S>, D::Error>
+pub fn deserialize<'de, D, K, V, S, Idx>(
+    deserializer: D,
+) -> Result<IndexMap<K, V, S, Idx>, D::Error>
 where
     D: Deserializer<'de>,
     K: Deserialize<'de> + Eq + Hash,
     V: Deserialize<'de>,
     S: Default + BuildHasher,
+    Idx: IndexType,
 {
-    deserializer.deserialize_seq(SeqVisitor(PhantomData))
+    IndexMapSeed::new(S::default()).deserialize(deserializer)
 }
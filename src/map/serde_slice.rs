@@ -0,0 +1,110 @@
+//! Functions to serialize and deserialize a [`map::Slice`][Slice] as a map.
+//!
+//! The [`Serialize`] implementation of `map::Slice`, and the [`Deserialize`]
+//! implementation of `Box<map::Slice>`, use the ordered sequence format of
+//! [`crate::map::serde_seq`]. This module uses the map format of the default
+//! `IndexMap` implementation instead, so for example a map that was serialized
+//! from an `IndexMap` can be deserialized as a boxed slice.
+//!
+//! Since a slice has no hash table, the keys are not hashed when they are
+//! deserialized, and they are not required to be unique either.
+//!
+//! This module may be used in a field attribute for derived implementations:
+//!
+//! ```
+//! # use indexmap::map::Slice;
+//! # use serde_derive::{Deserialize, Serialize};
+//! #[derive(Deserialize, Serialize)]
+//! struct Data {
+//!     #[serde(with = "indexmap::map::serde_slice")]
+//!     map: Box<Slice<i32, u64>>,
+//!     // ...
+//! }
+//! ```
+
+use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt::{self, Formatter};
+use core::marker::PhantomData;
+
+use super::Slice;
+use crate::serde::cautious_capacity;
+use crate::Bucket;
+
+/// Serializes a [`map::Slice`][Slice] as a map.
+///
+/// This function may be used in a field attribute for deriving [`Serialize`]:
+///
+/// ```
+/// # use indexmap::map::Slice;
+/// # use serde_derive::Serialize;
+/// #[derive(Serialize)]
+/// struct Data {
+///     #[serde(serialize_with = "indexmap::map::serde_slice::serialize")]
+///     map: Box<Slice<i32, u64>>,
+///     // ...
+/// }
+/// ```
+pub fn serialize<K, V, T>(slice: &Slice<K, V>, serializer: T) -> Result<T::Ok, T::Error>
+where
+    K: Serialize,
+    V: Serialize,
+    T: Serializer,
+{
+    serializer.collect_map(slice)
+}
+
+/// Visitor to deserialize a boxed `map::Slice` from a map
+struct SliceVisitor<K, V>(PhantomData<(K, V)>);
+
+impl<'de, K, V> Visitor<'de> for SliceVisitor<K, V>
+where
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = Box<Slice<K, V>>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let capacity = cautious_capacity::<Bucket<K, V>>(map.size_hint());
+        let mut entries = Vec::with_capacity(capacity);
+
+        while let Some((key, value)) = map.next_entry()? {
+            entries.push(Bucket::unhashed(key, value));
+        }
+
+        Ok(Slice::from_entries(entries))
+    }
+}
+
+/// Deserializes a boxed [`map::Slice`][Slice] from a map.
+///
+/// This function may be used in a field attribute for deriving [`Deserialize`]:
+///
+/// ```
+/// # use indexmap::map::Slice;
+/// # use serde_derive::Deserialize;
+/// #[derive(Deserialize)]
+/// struct Data {
+///     #[serde(deserialize_with = "indexmap::map::serde_slice::deserialize")]
+///     map: Box<Slice<i32, u64>>,
+///     // ...
+/// }
+/// ```
+pub fn deserialize<'de, D, K, V>(deserializer: D) -> Result<Box<Slice<K, V>>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    deserializer.deserialize_map(SliceVisitor(PhantomData))
+}
@@ -0,0 +1,182 @@
+//! Functions to serialize and deserialize an [`IndexMap`] as a map that must
+//! not contain duplicate keys.
+//!
+//! The default `serde` implementation deserializes a map by inserting each
+//! key-value pair in turn, so a key that occurs more than once keeps the
+//! position of its first occurrence but the value of its last. This module
+//! fails to deserialize such a map instead, with an error that names the
+//! duplicate key. Serialization is the same as the default implementation.
+//!
+//! This module may be used in a field attribute for derived implementations:
+//!
+//! ```
+//! # use indexmap::IndexMap;
+//! # use serde_derive::{Deserialize, Serialize};
+//! #[derive(Deserialize, Serialize)]
+//! struct Data {
+//!     #[serde(with = "indexmap::map::serde_strict")]
+//!     map: IndexMap<i32, u64>,
+//!     // ...
+//! }
+//! ```
+
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, MapAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{BuildHasher, Hash};
+
+use super::Entry;
+use crate::allocator::Allocator;
+use crate::{IndexMap, IndexType};
+
+/// Serializes an [`IndexMap`] as a map, like its [`Serialize`] implementation.
+///
+/// This function may be used in a field attribute for deriving [`Serialize`]:
+///
+/// ```
+/// # use indexmap::IndexMap;
+/// # use serde_derive::Serialize;
+/// #[derive(Serialize)]
+/// struct Data {
+///     #[serde(serialize_with = "indexmap::map::serde_strict::serialize")]
+///     map: IndexMap<i32, u64>,
+///     // ...
+/// }
+/// ```
+pub fn serialize<K, V, S, T, Idx, A>(
+    map: &IndexMap<K, V, S, Idx, A>,
+    serializer: T,
+) -> Result<T::Ok, T::Error>
+where
+    K: Serialize,
+    V: Serialize,
+    T: Serializer,
+    Idx: IndexType,
+    A: Allocator,
+{
+    serializer.collect_map(map)
+}
+
+/// A [`DeserializeSeed`] that creates an [`IndexMap`] with a given hasher,
+/// failing on any duplicate key.
+///
+/// This is like [`deserialize`], but it takes the hasher instance instead of
+/// needing `S: Default`. See [`map::IndexMapSeed`][crate::map::IndexMapSeed]
+/// for the bounds it can also put on untrusted input.
+pub struct IndexMapSeed<K, V, S, Idx = usize>(crate::map::IndexMapSeed<K, V, S, Idx>);
+
+impl<K, V, S, Idx> IndexMapSeed<K, V, S, Idx> {
+    /// Create a seed for an `IndexMap` that will use the given hasher.
+    pub fn new(hash_builder: S) -> Self {
+        IndexMapSeed(crate::map::IndexMapSeed::new(hash_builder))
+    }
+
+    /// Limit the capacity that is allocated up front to `limit` entries.
+    ///
+    /// See [`map::IndexMapSeed::with_capacity_limit`][crate::map::IndexMapSeed::with_capacity_limit].
+    pub fn with_capacity_limit(self, limit: usize) -> Self {
+        IndexMapSeed(self.0.with_capacity_limit(limit))
+    }
+
+    /// Fail to deserialize a map with more than `max_len` entries.
+    ///
+    /// See [`map::IndexMapSeed::with_max_len`][crate::map::IndexMapSeed::with_max_len].
+    pub fn with_max_len(self, max_len: usize) -> Self {
+        IndexMapSeed(self.0.with_max_len(max_len))
+    }
+}
+
+impl<K, V, S: Clone, Idx> Clone for IndexMapSeed<K, V, S, Idx> {
+    fn clone(&self) -> Self {
+        IndexMapSeed(self.0.clone())
+    }
+}
+
+impl<K, V, S: fmt::Debug, Idx> fmt::Debug for IndexMapSeed<K, V, S, Idx> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IndexMapSeed").field(&self.0).finish()
+    }
+}
+
+impl<'de, K, V, S, Idx> DeserializeSeed<'de> for IndexMapSeed<K, V, S, Idx>
+where
+    K: Deserialize<'de> + Eq + Hash + Debug,
+    V: Deserialize<'de>,
+    S: BuildHasher,
+    Idx: IndexType,
+{
+    type Value = IndexMap<K, V, S, Idx>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(StrictVisitor(self.0))
+    }
+}
+
+/// Visitor to deserialize an `IndexMap` without duplicate keys
+struct StrictVisitor<K, V, S, Idx>(crate::map::IndexMapSeed<K, V, S, Idx>);
+
+impl<'de, K, V, S, Idx> Visitor<'de> for StrictVisitor<K, V, S, Idx>
+where
+    K: Deserialize<'de> + Eq + Hash + Debug,
+    V: Deserialize<'de>,
+    S: BuildHasher,
+    Idx: IndexType,
+{
+    type Value = IndexMap<K, V, S, Idx>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a map with unique keys")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let size_hint = map.size_hint();
+        self.0.build_with(
+            size_hint,
+            || map.next_entry(),
+            |values, key, value| match values.entry(key) {
+                Entry::Occupied(entry) => Err(Error::custom(format_args!(
+                    "duplicate map key {:?}",
+                    entry.key()
+                ))),
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                    Ok(())
+                }
+            },
+        )
+    }
+}
+
+/// Deserializes an [`IndexMap`] from a map, failing on any duplicate key.
+///
+/// This function may be used in a field attribute for deriving [`Deserialize`]:
+///
+/// ```
+/// # use indexmap::IndexMap;
+/// # use serde_derive::Deserialize;
+/// #[derive(Deserialize)]
+/// struct Data {
+///     #[serde(deserialize_with = "indexmap::map::serde_strict::deserialize")]
+///     map: IndexMap<i32, u64>,
+///     // ...
+/// }
+/// ```
+pub fn deserialize<'de, D, K, V, S, Idx>(
+    deserializer: D,
+) -> Result<IndexMap<K, V, S, Idx>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: Deserialize<'de> + Eq + Hash + Debug,
+    V: Deserialize<'de>,
+    S: Default + BuildHasher,
+    Idx: IndexType,
+{
+    IndexMapSeed::new(S::default()).deserialize(deserializer)
+}
@@ -1,14 +1,18 @@
 use super::{
-    Bucket, Entries, IndexMap, IntoIter, IntoKeys, IntoValues, Iter, IterMut, Keys, Values,
-    ValuesMut,
+    Bucket, Entries, IndexMap, IndexType, IntoIter, IntoKeys, IntoValues, Iter, IterMut, Keys,
+    Values, ValuesMut,
 };
-use crate::util::try_simplify_range;
+use crate::allocator::{self, Allocator};
+use crate::util::{sorted_key_range, try_simplify_range};
+use crate::GetDisjointMutError;
 
 use alloc::boxed::Box;
 use alloc::vec::Vec;
+use core::borrow::Borrow;
 use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{Hash, Hasher};
+use core::mem;
 use core::ops::{self, Bound, Index, IndexMut, RangeBounds};
 
 /// A dynamically-sized slice of key-value pairs in an [`IndexMap`].
@@ -42,6 +46,14 @@ impl<K, V> Slice<K, V> {
     fn into_boxed(self: Box<Self>) -> Box<[Bucket<K, V>]> {
         unsafe { Box::from_raw(Box::into_raw(self) as *mut [Bucket<K, V>]) }
     }
+
+    #[cfg(feature = "allocator-api2")]
+    pub(super) fn from_boxed_in<A: Allocator>(
+        entries: allocator::Box<[Bucket<K, V>], A>,
+    ) -> allocator::Box<Self, A> {
+        let (entries, alloc) = allocator::Box::into_raw_with_allocator(entries);
+        unsafe { allocator::Box::from_raw_in(entries as *mut Self, alloc) }
+    }
 }
 
 impl<K, V> Slice<K, V> {
@@ -49,6 +61,13 @@ impl<K, V> Slice<K, V> {
         self.into_boxed().into_vec()
     }
 
+    /// Create a boxed slice from entries that may have duplicate keys, or
+    /// arbitrary hash values, since neither is used by the slice.
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_entries(entries: Vec<Bucket<K, V>>) -> Box<Self> {
+        Self::from_boxed(entries.into_boxed_slice())
+    }
+
     /// Returns an empty slice.
     pub const fn new<'a>() -> &'a Self {
         Self::from_slice(&[])
@@ -85,6 +104,68 @@ impl<K, V> Slice<K, V> {
         self.entries.get_mut(index).map(Bucket::ref_mut)
     }
 
+    /// Get an array of `N` key-value pairs by `N` indices, with mutable access to the values.
+    ///
+    /// Valid indices are *0 <= index < self.len()* and each index needs to be unique.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::{GetDisjointMutError, IndexMap};
+    ///
+    /// let mut map = IndexMap::from([(1, 'a'), (3, 'b'), (2, 'c')]);
+    /// let slice = map.as_mut_slice();
+    /// assert_eq!(
+    ///     slice.get_disjoint_indices_mut([2, 0]),
+    ///     Ok([(&2, &mut 'c'), (&1, &mut 'a')]),
+    /// );
+    /// assert_eq!(
+    ///     slice.get_disjoint_indices_mut([0, 3]),
+    ///     Err(GetDisjointMutError::IndexOutOfBounds),
+    /// );
+    /// assert_eq!(
+    ///     slice.get_disjoint_indices_mut([1, 1]),
+    ///     Err(GetDisjointMutError::OverlappingIndices),
+    /// );
+    /// ```
+    pub fn get_disjoint_indices_mut<const N: usize>(
+        &mut self,
+        indices: [usize; N],
+    ) -> Result<[(&K, &mut V); N], GetDisjointMutError> {
+        let indices = indices.map(Some);
+        let key_values = self.get_disjoint_opt_mut(indices)?;
+        Ok(key_values.map(Option::unwrap))
+    }
+
+    /// Get an array of `N` key-value pairs by `N` optional indices, with mutable access to the
+    /// values, passing through `None` as-is.
+    #[allow(unsafe_code)]
+    pub(crate) fn get_disjoint_opt_mut<const N: usize>(
+        &mut self,
+        indices: [Option<usize>; N],
+    ) -> Result<[Option<(&K, &mut V)>; N], GetDisjointMutError> {
+        // SAFETY: Can't allow duplicate indices as we would return several mutable refs to the same data.
+        let len = self.len();
+        for i in 0..N {
+            if let Some(idx) = indices[i] {
+                if idx >= len {
+                    return Err(GetDisjointMutError::IndexOutOfBounds);
+                } else if indices[..i].contains(&Some(idx)) {
+                    return Err(GetDisjointMutError::OverlappingIndices);
+                }
+            }
+        }
+
+        let entries_ptr = self.entries.as_mut_ptr();
+        let out = indices.map(|idx_opt| {
+            // SAFETY: The base pointer is valid as it comes from a slice and the reference is always
+            // in-bounds & unique as we've already checked the indices above.
+            idx_opt.map(|idx| unsafe { (*entries_ptr.add(idx)).ref_mut() })
+        });
+
+        Ok(out)
+    }
+
     /// Returns a slice of key-value pairs in the given range of indices.
     ///
     /// Valid indices are *0 <= index < self.len()*
@@ -194,7 +275,7 @@ impl<K, V> Slice<K, V> {
 
     /// Return an owning iterator over the keys of the map slice.
     pub fn into_keys(self: Box<Self>) -> IntoKeys<K, V> {
-        IntoKeys::new(self.into_entries())
+        IntoKeys::new(allocator::from_std_vec(self.into_entries()))
     }
 
     /// Return an iterator over the values of the map slice.
@@ -209,7 +290,7 @@ impl<K, V> Slice<K, V> {
 
     /// Return an owning iterator over the values of the map slice.
     pub fn into_values(self: Box<Self>) -> IntoValues<K, V> {
-        IntoValues::new(self.into_entries())
+        IntoValues::new(allocator::from_std_vec(self.into_entries()))
     }
 
     /// Search over a sorted map for a key.
@@ -270,6 +351,132 @@ impl<K, V> Slice<K, V> {
         self.entries
             .partition_point(move |a| pred(&a.key, &a.value))
     }
+
+    /// Returns a subslice of the key-value pairs of a sorted slice whose keys are within `range`.
+    ///
+    /// The bounds are found with [`partition_point`][Self::partition_point], so this is
+    /// like [`BTreeMap::range`][alloc::collections::BTreeMap::range] for a slice that is
+    /// sorted by its keys. If the keys are **not** sorted, the returned slice is
+    /// unspecified. A range whose start is after its end returns an empty slice.
+    ///
+    /// Computes in **O(log(n))** time.
+    pub fn range_keys<Q, R>(&self, range: R) -> &Self
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        let range = sorted_key_range(&self.entries, range);
+        Self::from_slice(&self.entries[range])
+    }
+
+    /// Returns a mutable subslice of the key-value pairs of a sorted slice whose keys are
+    /// within `range`.
+    ///
+    /// See [`range_keys`][Self::range_keys] for how the bounds are found.
+    ///
+    /// Computes in **O(log(n))** time.
+    pub fn range_keys_mut<Q, R>(&mut self, range: R) -> &mut Self
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        let range = sorted_key_range(&self.entries, range);
+        Self::from_mut_slice(&mut self.entries[range])
+    }
+
+    /// Sort the values of the slice in place using the comparison function
+    /// `cmp`, while every key stays at its position.
+    ///
+    /// Unlike [`IndexMap::sort_by`][crate::IndexMap::sort_by], this does not
+    /// move the keys with their values. The keys can't be reordered through a
+    /// slice, since the map's hash table would lose track of them, but the
+    /// values are free to move.
+    ///
+    /// Computes in **O(n log n)** time and **O(n)** space. The sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexMap;
+    ///
+    /// let mut map = IndexMap::from([("a", 3), ("b", 1), ("c", 2), ("d", 0)]);
+    /// map.as_mut_slice()[..3].sort_values_by(|v1, v2| v1.cmp(v2));
+    /// assert!(map.iter().eq([(&"a", &1), (&"b", &2), (&"c", &3), (&"d", &0)]));
+    /// ```
+    pub fn sort_values_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&V, &V) -> Ordering,
+    {
+        let entries = &self.entries;
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        order.sort_by(move |&i, &j| cmp(&entries[i].value, &entries[j].value));
+        permute_values(&mut self.entries, order);
+    }
+
+    /// Sort the values of the slice in place using the comparison function
+    /// `cmp`, while every key stays at its position.
+    ///
+    /// See [`sort_values_by`][Self::sort_values_by] for more details.
+    ///
+    /// Computes in **O(n log n)** time and **O(n)** space. The sort is unstable.
+    pub fn sort_unstable_values_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&V, &V) -> Ordering,
+    {
+        let entries = &self.entries;
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        order.sort_unstable_by(move |&i, &j| cmp(&entries[i].value, &entries[j].value));
+        permute_values(&mut self.entries, order);
+    }
+
+    /// Reverse the order of the values in the slice, while every key stays
+    /// at its position.
+    ///
+    /// Computes in **O(n)** time.
+    pub fn reverse_values(&mut self) {
+        let len = self.entries.len();
+        for i in 0..len / 2 {
+            swap_entry_values(&mut self.entries, i, len - i - 1);
+        }
+    }
+
+    /// Swaps the values at indices `a` and `b`, while the keys stay at their
+    /// positions.
+    ///
+    /// ***Panics*** if `a` or `b` are out of bounds.
+    ///
+    /// Computes in **O(1)** time.
+    pub fn swap_values(&mut self, a: usize, b: usize) {
+        swap_entry_values(&mut self.entries, a, b);
+    }
+}
+
+/// Swap the values of two entries, leaving their keys and hashes in place.
+fn swap_entry_values<K, V>(entries: &mut [Bucket<K, V>], a: usize, b: usize) {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    let (head, tail) = entries.split_at_mut(hi);
+    let high = &mut tail[0];
+    if lo < hi {
+        mem::swap(&mut head[lo].value, &mut high.value);
+    }
+}
+
+/// Move the values so that position `i` gets the value that was at `order[i]`.
+fn permute_values<K, V>(entries: &mut [Bucket<K, V>], mut order: Vec<usize>) {
+    for start in 0..order.len() {
+        // Follow each cycle of the permutation, marking positions as done.
+        let mut i = start;
+        loop {
+            let next = mem::replace(&mut order[i], i);
+            if next == start || next == i {
+                break;
+            }
+            swap_entry_values(entries, i, next);
+            i = next;
+        }
+    }
 }
 
 impl<'a, K, V> IntoIterator for &'a Slice<K, V> {
@@ -295,7 +502,7 @@ impl<K, V> IntoIterator for Box<Slice<K, V>> {
     type Item = (K, V);
 
     fn into_iter(self) -> Self::IntoIter {
-        IntoIter::new(self.into_entries())
+        IntoIter::new(allocator::from_std_vec(self.into_entries()))
     }
 }
 
@@ -384,7 +591,7 @@ impl<K, V> IndexMut<usize> for Slice<K, V> {
 // Instead, we repeat the implementations for all the core range types.
 macro_rules! impl_index {
     ($($range:ty),*) => {$(
-        impl<K, V, S> Index<$range> for IndexMap<K, V, S> {
+        impl<K, V, S, Idx: IndexType, A: Allocator> Index<$range> for IndexMap<K, V, S, Idx, A> {
             type Output = Slice<K, V>;
 
             fn index(&self, range: $range) -> &Self::Output {
@@ -392,7 +599,7 @@ macro_rules! impl_index {
             }
         }
 
-        impl<K, V, S> IndexMut<$range> for IndexMap<K, V, S> {
+        impl<K, V, S, Idx: IndexType, A: Allocator> IndexMut<$range> for IndexMap<K, V, S, Idx, A> {
             fn index_mut(&mut self, range: $range) -> &mut Self::Output {
                 Slice::from_mut_slice(&mut self.as_entries_mut()[range])
             }
@@ -1,5 +1,6 @@
 use super::*;
 use std::string::String;
+use std::vec::Vec;
 
 #[test]
 fn it_works() {
@@ -108,6 +109,75 @@ fn insert_order() {
     }
 }
 
+#[test]
+fn shift_insert() {
+    let insert = [0, 4, 2, 12, 8, 7, 11, 5];
+    let mut map = IndexMap::with_capacity(insert.len());
+
+    for &elt in &insert {
+        map.shift_insert(0, elt, ());
+    }
+
+    assert_eq!(map.keys().count(), insert.len());
+    for (a, b) in insert.iter().rev().zip(map.keys()) {
+        assert_eq!(a, b);
+    }
+
+    // Moving existing keys shifts the others, and replaces the value.
+    assert_eq!(map.shift_insert(7, 5, ()), Some(()));
+    assert_eq!(map.get_index_of(&5), Some(7));
+    assert_eq!(map.get_index_of(&0), Some(6));
+    assert_eq!(map.shift_insert(0, 0, ()), Some(()));
+    assert_eq!(map.get_index_of(&0), Some(0));
+    assert_eq!(map.get_index_of(&11), Some(1));
+
+    // Every key is still found by hash after all the shifting.
+    for (i, k) in map.keys().enumerate() {
+        assert_eq!(map.get_index_of(k), Some(i));
+    }
+}
+
+#[test]
+#[should_panic]
+fn shift_insert_out_of_bounds() {
+    let mut map: IndexMap<i32, ()> = (0..4).map(|i| (i, ())).collect();
+    map.shift_insert(5, 10, ());
+}
+
+#[test]
+#[should_panic]
+fn shift_insert_existing_at_len() {
+    let mut map: IndexMap<i32, ()> = (0..4).map(|i| (i, ())).collect();
+    map.shift_insert(4, 0, ());
+}
+
+#[test]
+fn insert_before() {
+    let mut map: IndexMap<i32, i32> = (0..5).map(|i| (i, i)).collect();
+
+    // New keys go exactly at the index, including the end.
+    assert_eq!(map.insert_before(2, 10, 10), (2, None));
+    assert_eq!(map.insert_before(map.len(), 11, 11), (6, None));
+    assert!(map.keys().eq(&[0, 1, 10, 2, 3, 4, 11]));
+
+    // Existing keys moving up land just before the entry at `index`.
+    assert_eq!(map.insert_before(5, 0, 100), (4, Some(0)));
+    assert!(map.keys().eq(&[1, 10, 2, 3, 0, 4, 11]));
+    assert_eq!(map[&0], 100);
+
+    // Existing keys moving down land exactly at `index`.
+    assert_eq!(map.insert_before(1, 11, 111), (1, Some(11)));
+    assert!(map.keys().eq(&[1, 11, 10, 2, 3, 0, 4]));
+
+    // Inserting before its own successor is a no-op move.
+    assert_eq!(map.insert_before(4, 2, 2), (3, Some(2)));
+    assert!(map.keys().eq(&[1, 11, 10, 2, 3, 0, 4]));
+
+    for (i, k) in map.keys().enumerate() {
+        assert_eq!(map.get_index_of(k), Some(i));
+    }
+}
+
 #[test]
 fn grow() {
     let insert = [0, 4, 2, 12, 8, 7, 11];
@@ -389,6 +459,86 @@ fn get_index_entry() {
     assert_eq!(*map.get(&3).unwrap(), "4");
 }
 
+#[test]
+fn entry_shift_insert() {
+    let mut map: IndexMap<i32, &str> = IndexMap::new();
+    map.insert(0, "0");
+    map.insert(1, "1");
+
+    match map.entry(2) {
+        Entry::Occupied(_) => panic!(),
+        Entry::Vacant(e) => {
+            assert_eq!(e.index(), 2);
+            assert_eq!(*e.shift_insert(1, "2"), "2");
+        }
+    }
+    assert!(map.keys().eq(&[0, 2, 1]));
+
+    match map.entry(0) {
+        Entry::Occupied(e) => e.move_index(2),
+        Entry::Vacant(_) => panic!(),
+    }
+    assert!(map.keys().eq(&[2, 1, 0]));
+
+    map.get_index_entry(2).unwrap().move_index(1);
+    assert!(map.keys().eq(&[2, 0, 1]));
+
+    for (i, k) in map.keys().enumerate() {
+        assert_eq!(map.get_index_of(k), Some(i));
+    }
+}
+
+#[test]
+fn get_disjoint_mut() {
+    let mut map: IndexMap<u32, u32> = IndexMap::default();
+    assert_eq!(map.get_disjoint_mut([&0, &1]), [None, None]);
+
+    map.insert(1, 10);
+    map.insert(2, 20);
+    map.insert(3, 30);
+    assert_eq!(
+        map.get_disjoint_mut([&3, &0, &1]),
+        [Some(&mut 30), None, Some(&mut 10)]
+    );
+
+    if let [Some(a), Some(b)] = map.get_disjoint_mut([&1, &3]) {
+        std::mem::swap(a, b);
+    }
+    assert!(map.values().eq(&[30, 20, 10]));
+}
+
+#[test]
+#[should_panic]
+fn get_disjoint_mut_duplicate() {
+    let mut map: IndexMap<u32, u32> = (0..4).map(|i| (i, i)).collect();
+    map.get_disjoint_mut([&1, &2, &1]);
+}
+
+#[test]
+fn get_disjoint_indices_mut() {
+    let mut map: IndexMap<u32, u32> = (0..4).map(|i| (i, i * 10)).collect();
+    assert_eq!(map.get_disjoint_indices_mut([]), Ok([]));
+    assert_eq!(
+        map.get_disjoint_indices_mut([3, 1]),
+        Ok([(&3, &mut 30), (&1, &mut 10)])
+    );
+    assert_eq!(
+        map.get_disjoint_indices_mut([1, 4]),
+        Err(crate::GetDisjointMutError::IndexOutOfBounds)
+    );
+    assert_eq!(
+        map.get_disjoint_indices_mut([2, 0, 2]),
+        Err(crate::GetDisjointMutError::OverlappingIndices)
+    );
+
+    let slice = &mut map[1..];
+    if let Ok([(_, a), (_, b)]) = slice.get_disjoint_indices_mut([0, 2]) {
+        *a += 1;
+        *b += 1;
+    }
+    assert!(map.values().eq(&[0, 11, 20, 31]));
+}
+
 #[test]
 fn keys() {
     let vec = vec![(1, 'a'), (2, 'b'), (3, 'c')];
@@ -698,3 +848,645 @@ fn test_partition_point() {
     assert_eq!(b.partition_point(|_, &x| x < 7), 4);
     assert_eq!(b.partition_point(|_, &x| x < 8), 5);
 }
+
+#[test]
+fn compact_index_type() {
+    type Map = IndexMap<u16, u16, RandomState, u8>;
+
+    let mut map = Map::default();
+    map.extend((0..255).map(|i| (i, i)));
+    assert_eq!(map.len(), 255);
+    assert!(map.capacity() <= 255);
+    assert!(map.try_reserve(1).is_err());
+    assert!(map.try_reserve_exact(1).is_err());
+    assert!(map.try_reserve(0).is_ok());
+
+    // existing keys can still be updated when full
+    assert_eq!(map.insert(7, 70), Some(7));
+    assert_eq!(map.get_index_of(&254), Some(254));
+
+    // shifting indices must stay within the index type, even at the limit
+    map.move_index(0, 254);
+    assert_eq!(map.get_index_of(&0), Some(254));
+    assert_eq!(map.get_index_of(&1), Some(0));
+    map.reverse();
+    assert_eq!(map.get_index_of(&0), Some(0));
+    assert_eq!(map.shift_remove(&0), Some(0));
+    assert_eq!(map.get_index_of(&254), Some(0));
+    assert_eq!(map.swap_remove(&254), Some(254));
+    assert_eq!(map.get_index_of(&1), Some(0));
+
+    assert!(map.try_reserve(2).is_ok());
+    map.shift_insert(0, 1000, 1000);
+    map.insert(1001, 1001);
+    assert_eq!(map.len(), 255);
+    for (i, k) in map.keys().enumerate() {
+        assert_eq!(map.get_index_of(k), Some(i));
+    }
+}
+
+#[test]
+fn hasher_constructors_infer_index_type() {
+    // These must keep inferring the default `usize` index type.
+    let mut map = IndexMap::with_hasher(RandomState::new());
+    map.insert(1, 'a');
+    let mut map2 = IndexMap::with_capacity_and_hasher(4, RandomState::new());
+    map2.insert(1, 'a');
+    assert_eq!(map, map2);
+
+    let map: IndexMap<u16, (), _, u8> =
+        IndexMap::with_capacity_and_hasher_idx(4, RandomState::new());
+    assert!(map.capacity() >= 4);
+    let map = IndexMap::<u16, (), _, u8>::with_hasher_idx(RandomState::new());
+    assert_eq!(map.capacity(), 0);
+}
+
+#[test]
+#[should_panic(expected = "capacity overflow")]
+fn compact_index_type_overflow() {
+    let mut map: IndexMap<u16, (), RandomState, u8> = IndexMap::default();
+    map.extend((0..256).map(|i| (i, ())));
+}
+
+#[cfg(feature = "allocator-api2")]
+#[derive(Clone, Default)]
+struct CountingAllocator(std::rc::Rc<std::cell::Cell<usize>>);
+
+#[cfg(feature = "allocator-api2")]
+#[allow(unsafe_code)]
+unsafe impl allocator_api2::alloc::Allocator for CountingAllocator {
+    fn allocate(
+        &self,
+        layout: allocator_api2::alloc::Layout,
+    ) -> Result<std::ptr::NonNull<[u8]>, allocator_api2::alloc::AllocError> {
+        self.0.set(self.0.get() + 1);
+        allocator_api2::alloc::Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: std::ptr::NonNull<u8>, layout: allocator_api2::alloc::Layout) {
+        self.0.set(self.0.get() - 1);
+        allocator_api2::alloc::Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+#[cfg(feature = "allocator-api2")]
+fn custom_allocator() {
+    let alloc = CountingAllocator::default();
+    let live = alloc.0.clone();
+    {
+        let mut map = IndexMap::new_in(alloc);
+        assert_eq!(live.get(), 0);
+        map.extend((0..100).map(|i| (i, i * 10)));
+        // one allocation each for the entries and the indices
+        assert_eq!(live.get(), 2);
+
+        let tail = map.split_off(50);
+        assert_eq!(live.get(), 4);
+        assert_eq!(map.len(), 50);
+        assert_eq!(tail.get(&75), Some(&750));
+
+        let clone = map.clone();
+        assert_eq!(clone, map);
+        assert!(std::rc::Rc::ptr_eq(&clone.allocator().0, &live));
+        drop(clone);
+        assert_eq!(live.get(), 4);
+
+        assert!(map.drain(..10).map(|(k, _)| k).eq(0..10));
+        assert!(map.into_iter().map(|(k, _)| k).eq(10..50));
+        assert_eq!(live.get(), 2);
+
+        let slice = tail.into_boxed_slice_in();
+        assert_eq!(live.get(), 1);
+        assert_eq!(slice.len(), 50);
+        assert_eq!(slice.get_index(0), Some((&50, &500)));
+    }
+    assert_eq!(live.get(), 0);
+}
+
+#[test]
+fn tombstone_remove_keeps_order() {
+    let mut map = TombstoneMap::new();
+    map.set_max_tombstone_ratio(1.0);
+    map.extend((0..10).map(|i| (i, i * 10)));
+
+    assert_eq!(map.remove(&3), Some(30));
+    assert_eq!(map.remove(&5), Some(50));
+    assert_eq!(map.remove(&5), None);
+    assert_eq!(map.len(), 8);
+    assert_eq!(map.tombstones(), 2);
+    assert!(map.keys().copied().eq([0, 1, 2, 4, 6, 7, 8, 9]));
+    assert!(map
+        .values()
+        .rev()
+        .copied()
+        .eq([90, 80, 70, 60, 40, 20, 10, 0]));
+    assert_eq!(map.get(&4), Some(&40));
+    assert!(!map.contains_key(&3));
+
+    // A removed key is inserted again at the end.
+    assert_eq!(map.insert(3, 300), None);
+    assert_eq!(map.last(), Some((&3, &300)));
+    assert_eq!(map.insert(3, 33), Some(300));
+    assert_eq!(map.last(), Some((&3, &33)));
+
+    map.compact();
+    assert_eq!(map.tombstones(), 0);
+    assert!(map.keys().copied().eq([0, 1, 2, 4, 6, 7, 8, 9, 3]));
+    for (k, v) in &map {
+        assert_eq!(map.get_key_value(k), Some((k, v)));
+    }
+}
+
+#[test]
+fn tombstone_ends() {
+    let mut map: TombstoneMap<i32, ()> = (0..6).map(|i| (i, ())).collect();
+    map.set_max_tombstone_ratio(1.0);
+
+    // Removing from either end doesn't leave any tombstones behind.
+    assert_eq!(map.pop(), Some((5, ())));
+    assert_eq!(map.remove_entry(&4), Some((4, ())));
+    assert_eq!(map.tombstones(), 0);
+
+    // Tombstones at the front are skipped when they become the first.
+    map.remove(&1);
+    assert_eq!(map.tombstones(), 1);
+    assert_eq!(map.pop_first(), Some((0, ())));
+    assert_eq!(map.first(), Some((&2, &())));
+    assert!(map.iter().map(|(&k, _)| k).eq([2, 3]));
+    assert_eq!(map.iter().len(), 2);
+    assert!(map.clone().into_iter().map(|(k, _)| k).eq([2, 3]));
+
+    // Removing the last live entry drops the trailing tombstones too.
+    map.remove(&2);
+    map.remove(&3);
+    assert!(map.is_empty());
+    assert_eq!(map.tombstones(), 0);
+    assert_eq!(map.first(), None);
+    assert_eq!(map.pop_first(), None);
+    assert_eq!(map.pop(), None);
+
+    map.insert(7, ());
+    assert_eq!(map.first(), Some((&7, &())));
+}
+
+#[test]
+fn tombstone_ratio_compacts() {
+    let mut map: TombstoneMap<i32, i32> = (0..100).map(|i| (i, i)).collect();
+    assert_eq!(map.max_tombstone_ratio(), 0.5);
+
+    for i in (1..99).step_by(2) {
+        map.remove(&i);
+        assert!(map.tombstones() * 2 <= map.len() + map.tombstones());
+    }
+    assert!(map.keys().copied().eq((0..99).step_by(2).chain([99])));
+
+    map.set_max_tombstone_ratio(0.0);
+    assert_eq!(map.tombstones(), 0);
+    map.remove(&50);
+    assert_eq!(map.tombstones(), 0);
+    assert_eq!(map.get(&52), Some(&52));
+
+    map.retain(|&k, v| {
+        *v *= 2;
+        k % 4 == 0
+    });
+    assert!(map.iter().all(|(&k, &v)| k % 4 == 0 && v == k * 2));
+    assert_eq!(map.get(&48), Some(&96));
+    assert_eq!(map.get(&52), Some(&104));
+}
+
+#[test]
+#[should_panic(expected = "tombstone ratio")]
+fn tombstone_ratio_out_of_range() {
+    let mut map = TombstoneMap::<i32, i32>::new();
+    map.set_max_tombstone_ratio(1.5);
+}
+
+#[test]
+fn multimap_get_all() {
+    let mut map = IndexMultiMap::new();
+    for (i, key) in "abcabca".chars().enumerate() {
+        assert_eq!(map.insert_append(key, i), i);
+    }
+    assert_eq!(map.len(), 7);
+    assert_eq!(map.count(&'a'), 3);
+    assert_eq!(map.count(&'d'), 0);
+    assert_eq!(map.get(&'b'), Some(&1));
+    assert_eq!(map.get(&'d'), None);
+    assert!(map.get_all(&'a').copied().eq([0, 3, 6]));
+    assert!(map.get_all(&'a').rev().copied().eq([6, 3, 0]));
+    assert_eq!(map.get_all(&'c').len(), 2);
+    assert_eq!(map.get_all(&'d').next(), None);
+    assert!(map.keys().copied().eq("abcabca".chars()));
+    assert_eq!(map.get_index(4), Some((&'b', &4)));
+    assert_eq!(map[5], 5);
+
+    for value in map.get_all_mut(&'b') {
+        *value *= 10;
+    }
+    let mut iter = map.get_all_mut(&'a');
+    *iter.next_back().unwrap() += 100;
+    *iter.next().unwrap() += 100;
+    *iter.next_back().unwrap() += 200;
+    assert_eq!(iter.next(), None);
+    assert!(map.values().copied().eq([100, 10, 2, 203, 40, 5, 106]));
+}
+
+#[test]
+fn multimap_remove() {
+    let mut map: IndexMultiMap<_, _> = "abcabcaxb".chars().zip(0..).collect();
+    assert_eq!(map.remove_all(&'x'), [7]);
+    assert_eq!(map.remove_all(&'x'), []);
+    assert_eq!(map.remove_all(&'b'), [1, 4, 8]);
+    assert!(map.keys().copied().eq("acaca".chars()));
+    assert!(map.values().copied().eq([0, 2, 3, 5, 6]));
+    assert!(map.get_all(&'c').copied().eq([2, 5]));
+    assert!(!map.contains_key(&'b'));
+
+    assert_eq!(map.shift_remove_index(0), Some(('a', 0)));
+    assert!(map.get_all(&'a').copied().eq([3, 6]));
+    assert_eq!(map.pop(), Some(('a', 6)));
+    assert!(map.get_all(&'a').copied().eq([3]));
+
+    map.insert_append('c', 7);
+    map.retain(|_, v| *v != 5);
+    assert!(map.iter().eq([(&'c', &2), (&'a', &3), (&'c', &7)]));
+    assert!(map.get_all(&'c').copied().eq([2, 7]));
+    assert!(map.into_iter().eq([('c', 2), ('a', 3), ('c', 7)]));
+}
+
+#[test]
+fn multimap_many_values_for_one_key() {
+    // Each distinct key is indexed once, so repeating a key doesn't lengthen
+    // the probe sequence, and this stays fast even in debug builds.
+    const N: usize = 200_000;
+    let mut map = IndexMultiMap::with_capacity(N);
+    for i in 0..N {
+        map.insert_append(i % 2 == 0 || i % 1000 == 1, i);
+    }
+    assert_eq!(map.count(&true), N / 2 + N / 1000);
+    assert_eq!(map.count(&false), N / 2 - N / 1000);
+    assert_eq!(map.get(&false), Some(&3));
+    assert_eq!(map.get_all(&true).nth(2), Some(&2));
+    assert_eq!(map.get_all(&true).len(), map.count(&true));
+
+    assert_eq!(map.pop(), Some((false, N - 1)));
+    assert_eq!(map.shift_remove_index(1), Some((true, 1)));
+    assert_eq!(map.get_all(&true).nth(1), Some(&2));
+    let removed = map.remove_all(&true);
+    assert_eq!(removed.len(), N / 2 + N / 1000 - 1);
+    assert!(map
+        .get_all(&false)
+        .copied()
+        .eq((3..N - 1).step_by(2).filter(|i| i % 1000 != 1)));
+    assert_eq!(map.get(&true), None);
+    map.retain(|_, v| *v % 4 == 3);
+    assert!(map.get_all(&false).copied().eq(map.values().copied()));
+    assert_eq!(map.count(&false), map.len());
+}
+
+#[test]
+fn bimap_remove_keeps_sides_in_sync() {
+    let mut map = IndexBiMap::new();
+    for (i, c) in "abcdefg".chars().enumerate() {
+        assert_eq!(map.try_insert(i, c), Ok(i));
+    }
+    assert_eq!(map.try_insert(0, 'z'), Err((0, 'z')));
+    assert_eq!(map.try_insert(9, 'a'), Err((9, 'a')));
+    assert_eq!(map.len(), 7);
+
+    assert_eq!(map.swap_remove_by_left(&1), Some((1, 'b')));
+    assert_eq!(map.get_index(1), Some((&6, &'g')));
+    assert_eq!(map.get_index_of_right(&'g'), Some(1));
+    assert_eq!(map.get_by_right(&'g'), Some(&6));
+
+    assert_eq!(map.shift_remove_by_right(&'c'), Some((2, 'c')));
+    assert!(map.lefts().copied().eq([0, 6, 3, 4, 5]));
+    for (i, (left, right)) in map.iter().enumerate() {
+        assert_eq!(map.get_index_of_left(left), Some(i));
+        assert_eq!(map.get_index_of_right(right), Some(i));
+    }
+
+    assert_eq!(map.pop(), Some((5, 'f')));
+    assert_eq!(map.shift_remove_by_left(&5), None);
+    assert!(!map.contains_right(&'f'));
+    assert_eq!(map.try_insert(5, 'b'), Ok(4));
+}
+
+#[test]
+fn bimap_sort_and_retain() {
+    let mut map = IndexBiMap::new();
+    for (i, c) in "hello world".char_indices() {
+        let _ = map.try_insert(c, i);
+    }
+    assert!(map.lefts().copied().eq("helo wrd".chars()));
+
+    map.sort_by(|l1, _, l2, _| l1.cmp(l2));
+    assert!(map.lefts().copied().eq(" dehlorw".chars()));
+    assert_eq!(map.get_by_left(&'w'), Some(&6));
+    assert_eq!(map.get_index_of_right(&6), Some(7));
+
+    map.retain(|l, _| l.is_alphabetic() && *l != 'l');
+    assert!(map.rights().copied().eq([10, 1, 0, 4, 8, 6]));
+    for (i, (left, right)) in map.iter().enumerate() {
+        assert_eq!(map.get_index_of_left(left), Some(i));
+        assert_eq!(map.get_index_of_right(right), Some(i));
+    }
+    assert_eq!(map.get_by_right(&2), None);
+}
+
+#[test]
+fn bimap_eq_ignores_order() {
+    let mut map = IndexBiMap::new();
+    for (i, c) in (0..5).zip("abcde".chars()) {
+        assert!(map.try_insert(i, c).is_ok());
+    }
+    let mut other = map.clone();
+    other.sort_by(|_, r1, _, r2| r2.cmp(r1));
+    assert!(other.lefts().eq(&[4, 3, 2, 1, 0]));
+    assert_eq!(map, other);
+
+    map.shift_remove_by_left(&2);
+    assert_ne!(map, other);
+    other.shift_remove_by_right(&'c');
+    assert_eq!(map, other);
+    assert!(map.try_insert(2, 'x').is_ok());
+    assert!(other.try_insert(2, 'y').is_ok());
+    assert_ne!(map, other);
+}
+
+#[test]
+fn range_keys_bounds() {
+    use std::ops::Bound::*;
+
+    let map: IndexMap<u32, ()> = (0..20).step_by(2).map(|k| (k, ())).collect();
+    let keys = |slice: &Slice<u32, ()>| slice.keys().copied().collect::<Vec<_>>();
+    assert_eq!(keys(map.range_keys(3..9)), [4, 6, 8]);
+    assert_eq!(keys(map.range_keys(4..=8)), [4, 6, 8]);
+    assert_eq!(keys(map.range_keys((Excluded(4), Excluded(8)))), [6]);
+    assert_eq!(keys(map.range_keys(15..)), [16, 18]);
+    assert_eq!(keys(map.range_keys(..2)), [0]);
+    assert_eq!(keys(map.range_keys((Included(9), Excluded(3)))), []);
+    assert_eq!(keys(map.range_keys(30..40)), []);
+    assert_eq!(map.range_keys(..).len(), map.len());
+}
+
+#[test]
+fn range_keys_on_slices() {
+    use std::ops::Bound::*;
+
+    let mut map: IndexMap<String, usize> = ["ant", "bee", "cat", "dog", "eel", "fox"]
+        .iter()
+        .map(|&s| (String::from(s), 0))
+        .collect();
+
+    // Borrowed bounds work on both the map and its subslices.
+    let slice = map.get_range(1..5).unwrap();
+    let keys = slice
+        .range_keys::<str, _>((Included("c"), Unbounded))
+        .keys();
+    assert!(keys.eq(["cat", "dog", "eel"]));
+    assert!(slice
+        .range_keys::<str, _>((Unbounded, Excluded("b")))
+        .is_empty());
+
+    for (_, value) in map.range_keys_mut::<str, _>((Included("bee"), Included("dog"))) {
+        *value += 1;
+    }
+    let slice = map
+        .as_mut_slice()
+        .range_keys_mut::<str, _>((Included("d"), Unbounded));
+    slice[0] += 10;
+    assert!(map.values().copied().eq([0, 1, 1, 11, 0, 0]));
+}
+
+#[test]
+fn map_set_algebra() {
+    let a: IndexMap<_, _> = (0..6).map(|k| (k, k * 10)).collect();
+    let b: IndexMap<_, _> = (3..9).rev().map(|k| (k, char::from(b'a' + k))).collect();
+    let c: IndexMap<_, _> = (3..9).rev().map(|k| (k, k)).collect();
+
+    let both: Vec<_> = a.intersection_with(&b).collect();
+    assert_eq!(both, [(&3, &30, &'d'), (&4, &40, &'e'), (&5, &50, &'f')]);
+    assert!(a.intersection_with(&b).rev().eq(both.into_iter().rev()));
+    assert!(b.intersection_with(&a).map(|(k, ..)| k).eq(&[5, 4, 3]));
+
+    assert!(a.difference(&b).eq([(&0, &0), (&1, &10), (&2, &20)]));
+    assert!(b.difference(&a).rev().map(|(k, _)| k).eq(&[6, 7, 8]));
+
+    let sym: Vec<_> = a.symmetric_difference(&c).map(|(&k, &v)| (k, v)).collect();
+    assert_eq!(sym, [(0, 0), (1, 10), (2, 20), (8, 8), (7, 7), (6, 6)]);
+    assert_eq!(a.symmetric_difference(&a).count(), 0);
+
+    let mut merged = a.clone();
+    let mut calls = Vec::new();
+    merged.merge_with(c.clone(), |&k, v, other| {
+        calls.push(k);
+        *v += other;
+    });
+    assert_eq!(calls, [5, 4, 3]);
+    assert!(merged.keys().eq(&[0, 1, 2, 3, 4, 5, 8, 7, 6]));
+    assert!(merged.values().eq(&[0, 10, 20, 33, 44, 55, 8, 7, 6]));
+    assert_eq!(merged.get_index_of(&7), Some(7));
+}
+
+#[test]
+fn hash_ignores_order() {
+    use crate::Ordered;
+    use std::collections::hash_map::{DefaultHasher, RandomState};
+    use std::hash::{BuildHasher, Hash, Hasher};
+
+    fn hash_of<T: ?Sized + Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let a: IndexMap<_, _> = (0..100).map(|i| (i, i * i)).collect();
+    let mut b: IndexMap<_, _, _> = IndexMap::with_hasher(RandomState::new());
+    b.extend((0..100).rev().map(|i| (i, i * i)));
+    assert_eq!(a, b);
+    assert_eq!(hash_of(&a), hash_of(&b));
+
+    b[&50] += 1;
+    assert_ne!(a, b);
+    assert_ne!(hash_of(&a), hash_of(&b));
+
+    // Moving a single value between keys must change the hash too.
+    let c = IndexMap::from([(1, 2), (2, 1)]);
+    let d = IndexMap::from([(1, 1), (2, 2)]);
+    assert_ne!(hash_of(&c), hash_of(&d));
+
+    let b = b.into_iter().rev().map(|(k, v)| (k, v - (k == 50) as i32));
+    let b: IndexMap<_, _> = b.collect();
+    assert_eq!(Ordered(&a), Ordered(&b));
+    assert_eq!(hash_of(&Ordered(&a)), hash_of(&Ordered(&b)));
+    let mut e = a.clone();
+    e.swap_indices(0, 1);
+    assert_eq!(a, e);
+    assert_ne!(Ordered(&a), Ordered(&e));
+    assert!(Ordered(&a) < Ordered(&e));
+    assert_eq!(hash_of(&Ordered(&a)), hash_of(a.as_slice()));
+
+    // The entries are hashed with keys from the caller's hasher, so equal maps
+    // only hash equally with the same hasher, like any other value.
+    let state = RandomState::new();
+    let keyed_hash_of = |state: &RandomState, map: &IndexMap<i32, i32>| {
+        let mut hasher = state.build_hasher();
+        Hash::hash(map, &mut hasher);
+        hasher.finish()
+    };
+    let reversed: IndexMap<_, _> = a.iter().rev().map(|(&k, &v)| (k, v)).collect();
+    assert_eq!(keyed_hash_of(&state, &a), keyed_hash_of(&state, &reversed));
+    assert_ne!(
+        keyed_hash_of(&state, &a),
+        keyed_hash_of(&RandomState::new(), &a)
+    );
+}
+
+#[test]
+fn slice_value_mutation() {
+    let mut map: IndexMap<i32, char> = (0..5).zip("abcde".chars()).collect();
+    let slice = map.as_mut_slice();
+    slice.swap_values(0, 4);
+    slice.swap_values(2, 2);
+    slice[1..4].reverse_values();
+    assert!(map
+        .iter()
+        .eq([(&0, &'e'), (&1, &'d'), (&2, &'c'), (&3, &'b'), (&4, &'a')]));
+
+    map.as_mut_slice()[1..].sort_unstable_values_by(|a, b| a.cmp(b));
+    assert!(map.values().eq(&['e', 'a', 'b', 'c', 'd']));
+    for key in 0..5 {
+        assert_eq!(map.get_index_of(&key), Some(key as usize));
+    }
+}
+
+#[test]
+#[should_panic]
+fn slice_swap_out_of_bounds() {
+    let mut map: IndexMap<i32, i32> = (0..3).map(|i| (i, i)).collect();
+    map.as_mut_slice().swap_values(1, 3);
+}
+
+#[test]
+fn sort_range_by() {
+    // Both a small range, which updates its indices one by one, and a large
+    // one, which rebuilds the table.
+    for range in [40..50, 5..95] {
+        let mut map: IndexMap<u32, u32> = (0..100).map(|i| (i, 100 - i)).collect();
+        map.sort_range_by(range.clone(), |_, a, _, b| a.cmp(b));
+        let mut expected: Vec<u32> = (0..100).collect();
+        expected[range].reverse();
+        assert!(map.keys().eq(&expected));
+        for (i, key) in expected.iter().enumerate() {
+            assert_eq!(map.get_index_of(key), Some(i));
+            assert_eq!(map[key], 100 - key);
+        }
+    }
+}
+
+#[test]
+fn range_reorder() {
+    // Small ranges update their indices one by one, while large ranges sweep
+    // the whole table.
+    let mut map: IndexMap<u32, ()> = (0..100).map(|i| (i, ())).collect();
+    map.rotate_left(10..20, 3);
+    map.rotate_right(0..90, 7);
+    map.reverse_range(40..45);
+    map.reverse_range(1..99);
+    map.move_range(50..55, 2);
+    map.move_range(0..80, 20);
+
+    let mut expected: Vec<u32> = (0..100).collect();
+    expected[10..20].rotate_left(3);
+    expected[0..90].rotate_right(7);
+    expected[40..45].reverse();
+    expected[1..99].reverse();
+    expected[2..55].rotate_right(5);
+    expected[0..100].rotate_right(20);
+    assert!(map.keys().eq(&expected));
+    for (i, key) in expected.iter().enumerate() {
+        assert_eq!(map.get_index_of(key), Some(i));
+    }
+
+    // Empty ranges and full rotations are no-ops.
+    map.rotate_left(5..5, 0);
+    map.rotate_right(5..8, 3);
+    map.move_range(100..100, 0);
+    assert!(map.keys().eq(&expected));
+}
+
+#[test]
+#[should_panic(expected = "rotation 4 should be <= range length 3")]
+fn rotate_too_far() {
+    let mut map: IndexMap<u32, ()> = (0..10).map(|i| (i, ())).collect();
+    map.rotate_left(2..5, 4);
+}
+
+#[test]
+#[should_panic(expected = "destination 8 should be <= 7")]
+fn move_range_out_of_bounds() {
+    let mut map: IndexMap<u32, ()> = (0..10).map(|i| (i, ())).collect();
+    map.move_range(2..5, 8);
+}
+
+#[test]
+fn try_from_iter_duplicates() {
+    let map: IndexMap<&str, i32> = IndexMap::try_from_iter([("a", 1), ("b", 2)]).unwrap();
+    assert_eq!(map.len(), 2);
+
+    let result: Result<IndexMap<&str, i32>, _> =
+        IndexMap::try_from_iter([("a", 1), ("b", 2), ("c", 3), ("b", 4), ("d", 5)]);
+    let error = result.unwrap_err();
+    assert_eq!(error.index(), 1);
+    assert_eq!(error.key(), &"b");
+    assert_eq!(error.value(), &4);
+    assert_eq!(std::format!("{}", error), "duplicate of the key at index 1");
+
+    let mut map = map;
+    map.extend_with([("c", 3), ("a", 10)], |old, new| *old = new);
+    assert!(map.into_iter().eq([("a", 10), ("b", 2), ("c", 3)]));
+}
+
+#[test]
+fn try_extend_without_size_hint() {
+    // The filter has no lower bound, so all of these are reserved at once.
+    let mut map: IndexMap<i32, i32> = (0..10).map(|i| (i, i)).collect();
+    map.try_extend((10..1000).filter(|_| true).map(|i| (i, i)))
+        .unwrap();
+    assert_eq!(map.len(), 1000);
+    for (i, (&k, &v)) in map.iter().enumerate() {
+        assert_eq!((k, v), (i as i32, i as i32));
+        assert_eq!(map.get_index_of(&k), Some(i));
+    }
+
+    let iter = (1000..2000).chain([1500, 2000]).filter(|_| true);
+    let error = map.try_extend(iter.map(|i| (i, -i))).unwrap_err();
+    assert_eq!(error.index(), 1500);
+    assert_eq!(error.into_parts(), (1500, -1500));
+    assert_eq!(map.len(), 2000);
+    assert_eq!(map.get_index_of(&1999), Some(1999));
+    assert_eq!(map.get(&2000), None);
+
+    map.extend_with((1990..2010).filter(|_| true).map(|i| (i, 0)), |old, new| {
+        *old += new
+    });
+    assert_eq!(map.len(), 2010);
+    assert_eq!(map[&1995], -1995);
+    assert_eq!(map.get_index_of(&2005), Some(2005));
+}
+
+#[test]
+fn extend_with_many_duplicates() {
+    // Duplicates are merged as they come, so they never take up capacity.
+    let mut map: IndexMap<i32, i32> = IndexMap::new();
+    map.extend_with(
+        (0..100_000).filter(|_| true).map(|i| (i % 10, 1)),
+        |old, new| *old += new,
+    );
+    assert_eq!(map.len(), 10);
+    assert!(map.capacity() < 100);
+    assert!(map.values().all(|&v| v == 10_000));
+}
@@ -0,0 +1,869 @@
+//! [`TombstoneMap`] is an insertion-ordered hash map with amortized **O(1)**
+//! removal that preserves the order of the remaining entries.
+
+use super::core::IndexMapCore;
+use crate::allocator::{self, Global, Vec};
+use crate::{Bucket, Entries, Equivalent, HashValue};
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter::FusedIterator;
+use core::slice;
+
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+
+/// The default for [`TombstoneMap::max_tombstone_ratio`].
+const DEFAULT_MAX_TOMBSTONE_RATIO: f64 = 0.5;
+
+/// A slot of the map, where a tombstone has neither a key nor a value.
+///
+/// Tombstones stay in the hash table with the hash of their former key, so the
+/// core's indices remain contiguous, but they never compare equal to a key.
+type Slot<K, V> = Bucket<Option<K>, Option<V>>;
+
+fn live_refs<K, V>(slot: &Slot<K, V>) -> Option<(&K, &V)> {
+    Some((slot.key.as_ref()?, slot.value.as_ref()?))
+}
+
+fn live_ref_mut<K, V>(slot: &mut Slot<K, V>) -> Option<(&K, &mut V)> {
+    Some((slot.key.as_ref()?, slot.value.as_mut()?))
+}
+
+fn live_key_value<K, V>(slot: Slot<K, V>) -> Option<(K, V)> {
+    Some((slot.key?, slot.value?))
+}
+
+/// Return the key-value pair in a slot that is known to be live.
+#[inline]
+fn live<K, V>(slot: &Slot<K, V>) -> (&K, &V) {
+    live_refs(slot).expect("slot is not a tombstone")
+}
+
+/// Look up a key among the live slots, never matching a tombstone.
+struct LiveKey<'a, Q: ?Sized>(&'a Q);
+
+impl<K, Q> Equivalent<Option<K>> for LiveKey<'_, Q>
+where
+    Q: ?Sized + Equivalent<K>,
+{
+    fn equivalent(&self, key: &Option<K>) -> bool {
+        key.as_ref().map_or(false, |key| self.0.equivalent(key))
+    }
+}
+
+/// A hash map that keeps its entries in insertion order, like [`IndexMap`][crate::IndexMap],
+/// but where removal leaves a tombstone in the removed entry's place.
+///
+/// This makes removal amortized **O(1)** while still preserving the order of the
+/// remaining entries, unlike [`IndexMap::shift_remove`][crate::IndexMap::shift_remove]
+/// which is **O(n)**, or [`IndexMap::swap_remove`][crate::IndexMap::swap_remove]
+/// which perturbs the order. Iteration skips over the tombstones, and they are
+/// cleared out by [`compact`][Self::compact], which runs automatically once the
+/// tombstones exceed [`max_tombstone_ratio`][Self::max_tombstone_ratio] of all slots.
+///
+/// Since the positions of entries change when tombstones are compacted, this map
+/// does not offer access by index.
+///
+/// # Examples
+///
+/// ```
+/// use indexmap::map::TombstoneMap;
+///
+/// let mut queue = TombstoneMap::new();
+/// for (id, event) in [(1, "open"), (2, "read"), (3, "write"), (4, "close")] {
+///     queue.insert(id, event);
+/// }
+///
+/// // Removing from the middle keeps the order of the rest.
+/// assert_eq!(queue.remove(&2), Some("read"));
+/// assert!(queue.keys().eq(&[1, 3, 4]));
+///
+/// // Re-inserting a removed key moves it to the end.
+/// queue.insert(2, "reread");
+/// assert!(queue.keys().eq(&[1, 3, 4, 2]));
+///
+/// assert_eq!(queue.pop_first(), Some((1, "open")));
+/// assert_eq!(queue.first(), Some((&3, &"write")));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct TombstoneMap<K, V, S = RandomState> {
+    /// All of the slots in their order, including the tombstones.
+    core: IndexMapCore<Option<K>, Option<V>>,
+    /// All of the slots before `head` are tombstones.
+    head: usize,
+    tombstones: usize,
+    max_tombstone_ratio: f64,
+    hash_builder: S,
+}
+#[cfg(not(feature = "std"))]
+#[derive(Clone)]
+pub struct TombstoneMap<K, V, S> {
+    /// All of the slots in their order, including the tombstones.
+    core: IndexMapCore<Option<K>, Option<V>>,
+    /// All of the slots before `head` are tombstones.
+    head: usize,
+    tombstones: usize,
+    max_tombstone_ratio: f64,
+    hash_builder: S,
+}
+
+impl<K, V, S> fmt::Debug for TombstoneMap<K, V, S>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<K, V> TombstoneMap<K, V> {
+    /// Create a new map. (Does not allocate.)
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create a new map with capacity for `n` key-value pairs. (Does not
+    /// allocate if `n` is zero.)
+    ///
+    /// Computes in **O(n)** time.
+    #[inline]
+    pub fn with_capacity(n: usize) -> Self {
+        Self::with_capacity_and_hasher(n, <_>::default())
+    }
+}
+
+impl<K, V, S> TombstoneMap<K, V, S> {
+    /// Create a new map with capacity for `n` key-value pairs. (Does not
+    /// allocate if `n` is zero.)
+    ///
+    /// Computes in **O(n)** time.
+    #[inline]
+    pub fn with_capacity_and_hasher(n: usize, hash_builder: S) -> Self {
+        TombstoneMap {
+            core: IndexMapCore::with_capacity(n),
+            ..Self::with_hasher(hash_builder)
+        }
+    }
+
+    /// Create a new map with `hash_builder`.
+    ///
+    /// This function is `const`, so it
+    /// can be called in `static` contexts.
+    pub const fn with_hasher(hash_builder: S) -> Self {
+        TombstoneMap {
+            core: IndexMapCore::new(),
+            head: 0,
+            tombstones: 0,
+            max_tombstone_ratio: DEFAULT_MAX_TOMBSTONE_RATIO,
+            hash_builder,
+        }
+    }
+
+    /// Return a reference to the map's `BuildHasher`.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Return the number of key-value pairs in the map, not counting tombstones.
+    ///
+    /// Computes in **O(1)** time.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.core.len() - self.tombstones
+    }
+
+    /// Returns true if the map contains no elements.
+    ///
+    /// Computes in **O(1)** time.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the number of tombstones left by removed entries
+    /// that have not been compacted yet.
+    ///
+    /// Computes in **O(1)** time.
+    #[inline]
+    pub fn tombstones(&self) -> usize {
+        self.tombstones
+    }
+
+    /// Return the fraction of tombstones among all slots of the map,
+    /// above which a removal will [`compact`][Self::compact] the map.
+    ///
+    /// This is `0.5` by default.
+    #[inline]
+    pub fn max_tombstone_ratio(&self) -> f64 {
+        self.max_tombstone_ratio
+    }
+
+    /// Set the fraction of tombstones among all slots of the map,
+    /// above which a removal will [`compact`][Self::compact] the map.
+    ///
+    /// A ratio of `0.0` compacts on every removal, like `shift_remove` on an
+    /// `IndexMap`, while a ratio of `1.0` never compacts automatically. If the
+    /// map is already above the new ratio, it is compacted right away.
+    ///
+    /// ***Panics*** if `ratio` is not in the range `0.0..=1.0`.
+    pub fn set_max_tombstone_ratio(&mut self, ratio: f64) {
+        assert!(
+            (0.0..=1.0).contains(&ratio),
+            "tombstone ratio must be in 0.0..=1.0, got {ratio}"
+        );
+        self.max_tombstone_ratio = ratio;
+        if self.has_excess_tombstones() {
+            self.compact();
+        }
+    }
+
+    /// Return the live slots, skipping the leading tombstones.
+    fn slots(&self) -> &[Slot<K, V>] {
+        &self.core.as_entries()[self.head..]
+    }
+
+    /// Return an iterator over the key-value pairs of the map, in their order
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: self.slots().iter(),
+            len: self.len(),
+        }
+    }
+
+    /// Return an iterator over the key-value pairs of the map, in their order
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        let len = self.len();
+        IterMut {
+            iter: self.core.as_entries_mut()[self.head..].iter_mut(),
+            len,
+        }
+    }
+
+    /// Return an iterator over the keys of the map, in their order
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            iter: self.slots().iter(),
+            len: self.len(),
+        }
+    }
+
+    /// Return an iterator over the values of the map, in their order
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            iter: self.slots().iter(),
+            len: self.len(),
+        }
+    }
+
+    /// Remove all key-value pairs and tombstones in the map, while preserving its capacity.
+    ///
+    /// Computes in **O(n)** time.
+    pub fn clear(&mut self) {
+        self.core.clear();
+        self.head = 0;
+        self.tombstones = 0;
+    }
+
+    /// Reserve capacity for `additional` more key-value pairs.
+    ///
+    /// Computes in **O(n)** time.
+    pub fn reserve(&mut self, additional: usize) {
+        self.core.reserve(additional);
+    }
+
+    /// Remove all tombstones, shifting the remaining entries down to fill the gaps.
+    ///
+    /// This happens automatically when a removal leaves more than
+    /// [`max_tombstone_ratio`][Self::max_tombstone_ratio] of the slots as tombstones.
+    ///
+    /// Computes in **O(n)** time.
+    pub fn compact(&mut self) {
+        if self.tombstones == 0 {
+            return;
+        }
+        self.core.retain_in_order(|key, _| key.is_some());
+        self.head = 0;
+        self.tombstones = 0;
+    }
+
+    /// Shrink the capacity of the map as much as possible, compacting it first.
+    ///
+    /// Computes in **O(n)** time.
+    pub fn shrink_to_fit(&mut self) {
+        self.compact();
+        self.core.shrink_to(0);
+    }
+
+    /// Get the first key-value pair
+    ///
+    /// Computes in **O(1)** time.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.slots().first().map(live)
+    }
+
+    /// Get the last key-value pair
+    ///
+    /// Computes in **O(1)** time.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.slots().last().map(live)
+    }
+
+    /// Remove and return the first key-value pair
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.remove_slot(self.head))
+    }
+
+    /// Remove and return the last key-value pair
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let index = self.core.len().checked_sub(1)?;
+        Some(self.remove_slot(index))
+    }
+
+    /// Leave a tombstone in the live slot at `index`, and return its key-value pair.
+    fn remove_slot(&mut self, index: usize) -> (K, V) {
+        let slot = &mut self.core.as_entries_mut()[index];
+        let entry = slot.key.take().zip(slot.value.take());
+        let entry = entry.expect("slot is not a tombstone");
+        self.tombstones += 1;
+
+        // Keep `head` at the first live slot, so `first` doesn't need to search.
+        if index == self.head {
+            let entries = self.core.as_entries();
+            self.head += 1;
+            while entries
+                .get(self.head)
+                .map_or(false, |slot| slot.key.is_none())
+            {
+                self.head += 1;
+            }
+        }
+
+        // Trailing tombstones can be dropped right away, as no slots follow them.
+        while self
+            .core
+            .as_entries()
+            .last()
+            .map_or(false, |slot| slot.key.is_none())
+        {
+            self.core.pop();
+            self.tombstones -= 1;
+        }
+        self.head = Ord::min(self.head, self.core.len());
+
+        if self.has_excess_tombstones() {
+            self.compact();
+        }
+        entry
+    }
+
+    fn has_excess_tombstones(&self) -> bool {
+        self.tombstones as f64 > self.max_tombstone_ratio * self.core.len() as f64
+    }
+}
+
+impl<K, V, S> TombstoneMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn hash<Q: ?Sized + Hash>(&self, key: &Q) -> HashValue {
+        let mut h = self.hash_builder.build_hasher();
+        key.hash(&mut h);
+        HashValue(h.finish() as usize)
+    }
+
+    /// Return the slot of an equivalent key, if it's live in the map.
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        let hash = self.hash(key);
+        self.core.get_index_of(hash, &LiveKey(key))
+    }
+
+    /// Insert a key-value pair in the map.
+    ///
+    /// If an equivalent key already exists in the map: the key remains and
+    /// retains in its place in the order, its corresponding value is updated
+    /// with `value`, and the older value is returned inside `Some(_)`.
+    ///
+    /// If no equivalent key existed in the map: the new key-value pair is
+    /// inserted, last in order, and `None` is returned. This includes keys
+    /// that were removed earlier, even if their tombstones remain.
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash = self.hash(&key);
+        let (_, old) = self.core.insert_full(hash, Some(key), Some(value));
+        old.flatten()
+    }
+
+    /// Return `true` if an equivalent to `key` exists in the map.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Return a reference to the value stored for `key`, if it is present,
+    /// else `None`.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let i = self.find(key)?;
+        self.core.as_entries()[i].value.as_ref()
+    }
+
+    /// Return references to the key-value pair stored for `key`,
+    /// if it is present, else `None`.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let i = self.find(key)?;
+        live_refs(&self.core.as_entries()[i])
+    }
+
+    /// Return a mutable reference to the value stored for `key`, if it is present,
+    /// else `None`.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let i = self.find(key)?;
+        self.core.as_entries_mut()[i].value.as_mut()
+    }
+
+    /// Remove the key-value pair equivalent to `key` and return its value.
+    ///
+    /// Like [`IndexMap::shift_remove`][crate::IndexMap::shift_remove], this
+    /// preserves the order of the remaining elements, but it only leaves a
+    /// tombstone behind rather than shifting them.
+    ///
+    /// Return `None` if `key` is not in map.
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        match self.remove_entry(key) {
+            Some((_, value)) => Some(value),
+            None => None,
+        }
+    }
+
+    /// Remove and return the key-value pair equivalent to `key`.
+    ///
+    /// Like [`IndexMap::shift_remove_entry`][crate::IndexMap::shift_remove_entry],
+    /// this preserves the order of the remaining elements, but it only leaves a
+    /// tombstone behind rather than shifting them.
+    ///
+    /// Return `None` if `key` is not in map.
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let index = self.find(key)?;
+        Some(self.remove_slot(index))
+    }
+
+    /// Scan through each key-value pair in the map and keep those where the
+    /// closure `keep` returns `true`.
+    ///
+    /// The elements are visited in order, and remaining elements keep their
+    /// order.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.core.retain_in_order(|key, value| match (key, value) {
+            (Some(key), Some(value)) => keep(key, value),
+            _ => false,
+        });
+        self.head = 0;
+        self.tombstones = 0;
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a TombstoneMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut TombstoneMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<K, V, S> IntoIterator for TombstoneMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let len = self.len();
+        let mut iter = self.core.into_entries().into_iter();
+        if self.head > 0 {
+            iter.nth(self.head - 1);
+        }
+        IntoIter { iter, len }
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for TombstoneMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    /// Create a `TombstoneMap` from the sequence of key-value pairs in the
+    /// iterable.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
+        let iter = iterable.into_iter();
+        let (low, _) = iter.size_hint();
+        let mut map = Self::with_capacity_and_hasher(low, <_>::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for TombstoneMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Extend the map with all key-value pairs in the iterable.
+    ///
+    /// This is equivalent to calling [`insert`][TombstoneMap::insert] for each of
+    /// them in order, which means that for keys that already existed
+    /// in the map, their value is updated but it keeps the existing order.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+        let iter = iterable.into_iter();
+        let (low, _) = iter.size_hint();
+        self.reserve(low);
+        iter.for_each(move |(k, v)| {
+            self.insert(k, v);
+        });
+    }
+}
+
+impl<K, V, S> Default for TombstoneMap<K, V, S>
+where
+    S: Default,
+{
+    /// Return an empty [`TombstoneMap`]
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K, V1, S1, V2, S2> PartialEq<TombstoneMap<K, V2, S2>> for TombstoneMap<K, V1, S1>
+where
+    K: Hash + Eq,
+    V1: PartialEq<V2>,
+    S1: BuildHasher,
+    S2: BuildHasher,
+{
+    fn eq(&self, other: &TombstoneMap<K, V2, S2>) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+
+        self.iter()
+            .all(|(key, value)| other.get(key).map_or(false, |v| *value == *v))
+    }
+}
+
+impl<K, V, S> Eq for TombstoneMap<K, V, S>
+where
+    K: Eq + Hash,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+// generate the iterator methods by forwarding to the underlying self.iter,
+// skipping the tombstones, and keeping count of the live entries in self.len.
+macro_rules! live_iterator_methods {
+    // $map_slot is the mapping function from the underlying iterator's slots,
+    // which returns `None` for tombstones.
+    ($map_slot:expr) => {
+        fn next(&mut self) -> Option<Self::Item> {
+            if self.len == 0 {
+                return None;
+            }
+            let item = self.iter.find_map($map_slot);
+            debug_assert!(item.is_some());
+            self.len -= 1;
+            item
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.len, Some(self.len))
+        }
+
+        fn count(self) -> usize {
+            self.len
+        }
+
+        fn last(mut self) -> Option<Self::Item> {
+            self.next_back()
+        }
+    };
+}
+
+macro_rules! live_double_ended_iterator_methods {
+    ($map_slot:expr) => {
+        fn next_back(&mut self) -> Option<Self::Item> {
+            if self.len == 0 {
+                return None;
+            }
+            let item = self.iter.by_ref().rev().find_map($map_slot);
+            debug_assert!(item.is_some());
+            self.len -= 1;
+            item
+        }
+    };
+}
+
+/// An iterator over the entries of a [`TombstoneMap`].
+///
+/// This `struct` is created by the [`TombstoneMap::iter`] method.
+/// See its documentation for more.
+pub struct Iter<'a, K, V> {
+    iter: slice::Iter<'a, Slot<K, V>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    live_iterator_methods!(|slot: &'a Slot<K, V>| live_refs(slot));
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    live_double_ended_iterator_methods!(|slot: &'a Slot<K, V>| live_refs(slot));
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// A mutable iterator over the entries of a [`TombstoneMap`].
+///
+/// This `struct` is created by the [`TombstoneMap::iter_mut`] method.
+/// See its documentation for more.
+pub struct IterMut<'a, K, V> {
+    iter: slice::IterMut<'a, Slot<K, V>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    live_iterator_methods!(|slot: &'a mut Slot<K, V>| live_ref_mut(slot));
+}
+
+impl<'a, K, V> DoubleEndedIterator for IterMut<'a, K, V> {
+    live_double_ended_iterator_methods!(|slot: &'a mut Slot<K, V>| live_ref_mut(slot));
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for IterMut<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let iter = self.iter.as_slice().iter().filter_map(live_refs);
+        f.debug_list().entries(iter).finish()
+    }
+}
+
+/// An iterator over the keys of a [`TombstoneMap`].
+///
+/// This `struct` is created by the [`TombstoneMap::keys`] method.
+/// See its documentation for more.
+pub struct Keys<'a, K, V> {
+    iter: slice::Iter<'a, Slot<K, V>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    live_iterator_methods!(|slot: &'a Slot<K, V>| slot.key.as_ref());
+}
+
+impl<'a, K, V> DoubleEndedIterator for Keys<'a, K, V> {
+    live_double_ended_iterator_methods!(|slot: &'a Slot<K, V>| slot.key.as_ref());
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<K, V> Clone for Keys<'_, K, V> {
+    fn clone(&self) -> Self {
+        Keys {
+            iter: self.iter.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K: fmt::Debug, V> fmt::Debug for Keys<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the values of a [`TombstoneMap`].
+///
+/// This `struct` is created by the [`TombstoneMap::values`] method.
+/// See its documentation for more.
+pub struct Values<'a, K, V> {
+    iter: slice::Iter<'a, Slot<K, V>>,
+    len: usize,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    live_iterator_methods!(|slot: &'a Slot<K, V>| slot.value.as_ref());
+}
+
+impl<'a, K, V> DoubleEndedIterator for Values<'a, K, V> {
+    live_double_ended_iterator_methods!(|slot: &'a Slot<K, V>| slot.value.as_ref());
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+// FIXME(#26925) Remove in favor of `#[derive(Clone)]`
+impl<K, V> Clone for Values<'_, K, V> {
+    fn clone(&self) -> Self {
+        Values {
+            iter: self.iter.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K, V: fmt::Debug> fmt::Debug for Values<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An owning iterator over the entries of a [`TombstoneMap`].
+///
+/// This `struct` is created by the [`TombstoneMap::into_iter`] method
+/// (provided by the [`IntoIterator`] trait). See its documentation for more.
+pub struct IntoIter<K, V> {
+    iter: allocator::IntoIter<Slot<K, V>, Global>,
+    len: usize,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    live_iterator_methods!(|slot: Slot<K, V>| live_key_value(slot));
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    live_double_ended_iterator_methods!(|slot: Slot<K, V>| live_key_value(slot));
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for IntoIter<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let iter = self.iter.as_slice().iter().filter_map(live_refs);
+        f.debug_list().entries(iter).finish()
+    }
+}
+
+impl<K, V> Default for IntoIter<K, V> {
+    fn default() -> Self {
+        Self {
+            iter: Vec::new().into_iter(),
+            len: 0,
+        }
+    }
+}
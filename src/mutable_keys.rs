@@ -1,6 +1,7 @@
 use core::hash::{BuildHasher, Hash};
 
-use super::{Bucket, Entries, Equivalent, IndexMap};
+use super::{Bucket, Entries, Equivalent, IndexMap, IndexType};
+use crate::allocator::Allocator;
 
 /// Opt-in mutable access to [`IndexMap`] keys.
 ///
@@ -52,9 +53,11 @@ pub trait MutableKeys: private::Sealed {
 /// Opt-in mutable access to keys.
 ///
 /// See [`MutableKeys`] for more information.
-impl<K, V, S> MutableKeys for IndexMap<K, V, S>
+impl<K, V, S, Idx, A> MutableKeys for IndexMap<K, V, S, Idx, A>
 where
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Key = K;
     type Value = V;
@@ -86,5 +89,5 @@ where
 mod private {
     pub trait Sealed {}
 
-    impl<K, V, S> Sealed for super::IndexMap<K, V, S> {}
+    impl<K, V, S, Idx, A: super::Allocator> Sealed for super::IndexMap<K, V, S, Idx, A> {}
 }
@@ -0,0 +1,93 @@
+use crate::allocator::Allocator;
+use crate::{map, set, IndexMap, IndexSet, IndexType};
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+/// A wrapper that compares and hashes an [`IndexMap`] or [`IndexSet`] as a sequence.
+///
+/// The maps and sets themselves compare and hash their entries without
+/// regard for their order. Within `Ordered`, they are compared and hashed
+/// like their [`as_slice`][IndexMap::as_slice], so the order matters, and
+/// they are also ordered lexicographically. This wraps either an owned
+/// collection or a reference to one.
+///
+/// # Examples
+///
+/// ```
+/// use indexmap::{IndexSet, Ordered};
+/// use std::collections::HashSet;
+///
+/// let ab = IndexSet::from(['a', 'b']);
+/// let ba = IndexSet::from(['b', 'a']);
+/// assert_eq!(ab, ba);
+/// assert_ne!(Ordered(&ab), Ordered(&ba));
+/// assert!(Ordered(&ab) < Ordered(&ba));
+///
+/// let seen: HashSet<_> = [Ordered(ab), Ordered(ba)].into_iter().collect();
+/// assert_eq!(seen.len(), 2);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Ordered<T>(pub T);
+
+macro_rules! impl_ordered {
+    ($([$($lt:lifetime)?] $ty:ty => $slice:ty, [$($param:ident),*];)*) => {$(
+        impl<$($lt,)? $($param,)* S, Idx, A> PartialEq for Ordered<$ty>
+        where
+            $slice: PartialEq,
+            Idx: IndexType,
+            A: Allocator,
+        {
+            fn eq(&self, other: &Self) -> bool {
+                self.0.as_slice() == other.0.as_slice()
+            }
+        }
+
+        impl<$($lt,)? $($param,)* S, Idx, A> Eq for Ordered<$ty>
+        where
+            $slice: Eq,
+            Idx: IndexType,
+            A: Allocator,
+        {
+        }
+
+        impl<$($lt,)? $($param,)* S, Idx, A> PartialOrd for Ordered<$ty>
+        where
+            $slice: PartialOrd,
+            Idx: IndexType,
+            A: Allocator,
+        {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.0.as_slice().partial_cmp(other.0.as_slice())
+            }
+        }
+
+        impl<$($lt,)? $($param,)* S, Idx, A> Ord for Ordered<$ty>
+        where
+            $slice: Ord,
+            Idx: IndexType,
+            A: Allocator,
+        {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.as_slice().cmp(other.0.as_slice())
+            }
+        }
+
+        impl<$($lt,)? $($param,)* S, Idx, A> Hash for Ordered<$ty>
+        where
+            $slice: Hash,
+            Idx: IndexType,
+            A: Allocator,
+        {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.as_slice().hash(state);
+            }
+        }
+    )*};
+}
+
+impl_ordered! {
+    [] IndexMap<K, V, S, Idx, A> => map::Slice<K, V>, [K, V];
+    ['a] &'a IndexMap<K, V, S, Idx, A> => map::Slice<K, V>, [K, V];
+    [] IndexSet<T, S, Idx, A> => set::Slice<T>, [T];
+    ['a] &'a IndexSet<T, S, Idx, A> => set::Slice<T>, [T];
+}
@@ -0,0 +1,873 @@
+//! A persistent [`IndexMap`] whose clones share their storage.
+//!
+//! Cloning the map only bumps reference counts, and later updates copy just
+//! the parts of the storage they touch, so keeping old snapshots around is
+//! cheap.
+//!
+//! The storage is shared through [`Arc`], so this module is only available on
+//! targets with atomic pointers.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{Bucket, Equivalent, HashValue};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::iter::FusedIterator;
+use core::mem;
+use core::ops::Index;
+use core::slice;
+use hashbrown::raw::RawTable;
+
+#[cfg(feature = "std")]
+use std::collections::hash_map::RandomState;
+
+/// The number of entries stored together in one shared chunk.
+const CHUNK: usize = 64;
+
+/// The average number of entries per index shard before the shards are split.
+const SHARD_LOAD: usize = 64;
+
+type Chunk<K, V> = Arc<Vec<Bucket<K, V>>>;
+
+/// A hash table where the iteration order of the key-value pairs is
+/// independent of the hash values of the keys, with structurally shared
+/// storage.
+///
+/// This has the same ordering and lookup behavior as
+/// [`IndexMap`][crate::IndexMap], but its entries are kept in shared chunks
+/// of 64, and its hash index is split into shared shards. A `clone` takes
+/// **O(1)** time, and modifying either copy only duplicates the chunks and
+/// index shards it touches (plus the small lists of pointers to them), while
+/// the other copy keeps its own view. This makes it cheap to keep snapshots.
+///
+/// Since shared entries may need to be copied on modification, mutating
+/// methods require `K: Clone` and `V: Clone`.
+///
+/// # Examples
+///
+/// ```
+/// use indexmap::persistent::IndexMap;
+///
+/// let mut config = IndexMap::new();
+/// config.insert("host", "localhost");
+/// config.insert("port", "8080");
+///
+/// let snapshot = config.clone();
+/// config.insert("port", "9090");
+/// config.insert("tls", "on");
+///
+/// assert_eq!(snapshot["port"], "8080");
+/// assert_eq!(snapshot.len(), 2);
+/// assert_eq!(config["port"], "9090");
+/// assert_eq!(config.get_index(2), Some((&"tls", &"on")));
+/// assert!(config.keys().eq(["host", "port", "tls"].iter()));
+/// ```
+#[cfg(feature = "std")]
+pub struct IndexMap<K, V, S = RandomState> {
+    chunks: Arc<Vec<Chunk<K, V>>>,
+    shards: Arc<Vec<Arc<RawTable<usize>>>>,
+    len: usize,
+    hash_builder: S,
+}
+#[cfg(not(feature = "std"))]
+pub struct IndexMap<K, V, S> {
+    chunks: Arc<Vec<Chunk<K, V>>>,
+    shards: Arc<Vec<Arc<RawTable<usize>>>>,
+    len: usize,
+    hash_builder: S,
+}
+
+#[inline]
+fn bucket<K, V>(chunks: &[Chunk<K, V>], index: usize) -> &Bucket<K, V> {
+    &chunks[index / CHUNK][index % CHUNK]
+}
+
+#[inline(always)]
+fn get_hash<K, V>(chunks: &[Chunk<K, V>]) -> impl Fn(&usize) -> u64 + '_ {
+    move |&i| bucket(chunks, i).hash.get()
+}
+
+/// Pick the index shard for a hash out of `count`, a power of two.
+#[inline]
+fn shard_of(hash: HashValue, count: usize) -> usize {
+    // Mix the hash first, because the tables within each shard still depend
+    // on both its low bits (for the bucket) and its high bits (for the tag).
+    let mixed = hash.get().wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    (mixed >> 32) as usize & (count - 1)
+}
+
+impl<K, V, S> Clone for IndexMap<K, V, S>
+where
+    S: Clone,
+{
+    /// Clones the map by sharing its storage, in **O(1)** time.
+    fn clone(&self) -> Self {
+        IndexMap {
+            chunks: Arc::clone(&self.chunks),
+            shards: Arc::clone(&self.shards),
+            len: self.len,
+            hash_builder: self.hash_builder.clone(),
+        }
+    }
+}
+
+impl<K, V, S> fmt::Debug for IndexMap<K, V, S>
+where
+    K: fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<K, V> IndexMap<K, V> {
+    /// Create a new map.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K, V, S> IndexMap<K, V, S> {
+    /// Create a new map with `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        IndexMap {
+            chunks: Arc::new(Vec::new()),
+            shards: Arc::new(alloc::vec![Arc::new(RawTable::new())]),
+            len: 0,
+            hash_builder,
+        }
+    }
+
+    /// Return a reference to the map's `BuildHasher`.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Return the number of key-value pairs in the map.
+    ///
+    /// Computes in **O(1)** time.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the map contains no elements.
+    ///
+    /// Computes in **O(1)** time.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if both maps share all of their storage, like a fresh clone.
+    ///
+    /// Computes in **O(1)** time.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.chunks, &other.chunks) && Arc::ptr_eq(&self.shards, &other.shards)
+    }
+
+    /// Return an iterator over the key-value pairs of the map, in their order
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            iter: Buckets::new(&self.chunks, self.len),
+        }
+    }
+
+    /// Return an iterator over the keys of the map, in their order
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            iter: Buckets::new(&self.chunks, self.len),
+        }
+    }
+
+    /// Return an iterator over the values of the map, in their order
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            iter: Buckets::new(&self.chunks, self.len),
+        }
+    }
+
+    /// Remove all key-value pairs in the map.
+    ///
+    /// Storage that is shared with clones of the map is left to them.
+    pub fn clear(&mut self) {
+        self.chunks = Arc::new(Vec::new());
+        self.shards = Arc::new(alloc::vec![Arc::new(RawTable::new())]);
+        self.len = 0;
+    }
+
+    /// Get a key-value pair by index
+    ///
+    /// Valid indices are *0 <= index < self.len()*
+    ///
+    /// Computes in **O(1)** time.
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        if index < self.len {
+            Some(bucket(&self.chunks, index).refs())
+        } else {
+            None
+        }
+    }
+
+    /// Get the first key-value pair
+    ///
+    /// Computes in **O(1)** time.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.get_index(0)
+    }
+
+    /// Get the last key-value pair
+    ///
+    /// Computes in **O(1)** time.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.get_index(self.len.checked_sub(1)?)
+    }
+
+    fn shard(&self, hash: HashValue) -> &RawTable<usize> {
+        &self.shards[shard_of(hash, self.shards.len())]
+    }
+
+    /// Find the index of `key`, which has the given `hash`.
+    fn find<Q>(&self, hash: HashValue, key: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Equivalent<K>,
+    {
+        let chunks = &*self.chunks;
+        let eq = move |&i: &usize| Q::equivalent(key, &bucket(chunks, i).key);
+        self.shard(hash).get(hash.get(), eq).copied()
+    }
+}
+
+impl<K, V, S> IndexMap<K, V, S>
+where
+    K: Clone,
+    V: Clone,
+{
+    /// Get a unique reference to the index shard for `hash`, copying it if
+    /// it's shared.
+    fn shard_mut(&mut self, hash: HashValue) -> &mut RawTable<usize> {
+        let shards = Arc::make_mut(&mut self.shards);
+        let i = shard_of(hash, shards.len());
+        Arc::make_mut(&mut shards[i])
+    }
+
+    /// Get a unique reference to the entry at `index`, copying its chunk if
+    /// it's shared.
+    fn bucket_mut(&mut self, index: usize) -> &mut Bucket<K, V> {
+        let chunks = Arc::make_mut(&mut self.chunks);
+        &mut Arc::make_mut(&mut chunks[index / CHUNK])[index % CHUNK]
+    }
+
+    fn erase_index(&mut self, hash: HashValue, index: usize) {
+        let erased = self
+            .shard_mut(hash)
+            .erase_entry(hash.get(), move |&i| i == index);
+        debug_assert!(erased);
+    }
+
+    fn update_index(&mut self, hash: HashValue, old: usize, new: usize) {
+        let index = self
+            .shard_mut(hash)
+            .get_mut(hash.get(), move |&i| i == old)
+            .expect("index not found");
+        *index = new;
+    }
+
+    /// Append a new entry, which must not be in the map yet.
+    fn push(&mut self, hash: HashValue, key: K, value: V) -> usize {
+        let index = self.len;
+        if index >= self.shards.len() * SHARD_LOAD {
+            self.rebuild_shards(self.shards.len() * 2);
+        }
+
+        let chunks = Arc::make_mut(&mut self.chunks);
+        if index % CHUNK == 0 {
+            chunks.push(Arc::new(Vec::with_capacity(CHUNK)));
+        }
+        let last = chunks.last_mut().expect("a chunk with room");
+        Arc::make_mut(last).push(Bucket { hash, key, value });
+        self.len += 1;
+
+        let shards = Arc::make_mut(&mut self.shards);
+        let i = shard_of(hash, shards.len());
+        Arc::make_mut(&mut shards[i]).insert(hash.get(), index, get_hash(&self.chunks));
+        index
+    }
+
+    /// Rebuild the index with `count` shards, all unshared.
+    fn rebuild_shards(&mut self, count: usize) {
+        let chunks = &*self.chunks;
+        let mut shards: Vec<RawTable<usize>> = (0..count)
+            .map(|_| RawTable::with_capacity(SHARD_LOAD))
+            .collect();
+        for (i, entry) in Buckets::new(chunks, self.len).enumerate() {
+            let shard = &mut shards[shard_of(entry.hash, count)];
+            shard.insert(entry.hash.get(), i, get_hash(chunks));
+        }
+        self.shards = Arc::new(shards.into_iter().map(Arc::new).collect());
+    }
+
+    /// Get a key-value pair by index, with mutable access to the value
+    ///
+    /// This copies the chunk holding the entry if it's shared with a clone.
+    ///
+    /// Computes in **O(1)** time.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        if index < self.len {
+            Some(self.bucket_mut(index).ref_mut())
+        } else {
+            None
+        }
+    }
+
+    /// Remove the key-value pair by index
+    ///
+    /// Valid indices are *0 <= index < self.len()*
+    ///
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is
+    /// removed by swapping it with the last element of the map and popping it
+    /// off. **This perturbs the position of what used to be the last element!**
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.len {
+            return None;
+        }
+        let last = self.len - 1;
+        let hash = bucket(&self.chunks, index).hash;
+        self.erase_index(hash, index);
+        if index != last {
+            let last_hash = bucket(&self.chunks, last).hash;
+            self.update_index(last_hash, last, index);
+        }
+
+        let mut removed = self.pop_bucket();
+        if index != last {
+            mem::swap(&mut removed, self.bucket_mut(index));
+        }
+        Some(removed.key_value())
+    }
+
+    /// Remove the key-value pair by index
+    ///
+    /// Valid indices are *0 <= index < self.len()*
+    ///
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by
+    /// shifting all of the elements that follow it, preserving their relative
+    /// order. **This perturbs the index of all of those elements!**
+    ///
+    /// Computes in **O(n)** time (average), and copies every shared chunk
+    /// from `index` onward.
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.len {
+            return None;
+        }
+        let hash = bucket(&self.chunks, index).hash;
+        self.erase_index(hash, index);
+        for i in index + 1..self.len {
+            let hash = bucket(&self.chunks, i).hash;
+            self.update_index(hash, i, i - 1);
+        }
+
+        let chunks = Arc::make_mut(&mut self.chunks);
+        let first = index / CHUNK;
+        let removed = Arc::make_mut(&mut chunks[first]).remove(index % CHUNK);
+        for c in first + 1..chunks.len() {
+            // Pull each following chunk's head back into the previous chunk.
+            let moved = Arc::make_mut(&mut chunks[c]).remove(0);
+            Arc::make_mut(&mut chunks[c - 1]).push(moved);
+        }
+        if chunks.last().map_or(false, |chunk| chunk.is_empty()) {
+            chunks.pop();
+        }
+        self.len -= 1;
+        Some(removed.key_value())
+    }
+
+    /// Remove the last key-value pair
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let last = self.len.checked_sub(1)?;
+        let hash = bucket(&self.chunks, last).hash;
+        self.erase_index(hash, last);
+        Some(self.pop_bucket().key_value())
+    }
+
+    /// Pop the last entry out of the chunks, leaving the index alone.
+    fn pop_bucket(&mut self) -> Bucket<K, V> {
+        let chunks = Arc::make_mut(&mut self.chunks);
+        let chunk = chunks.last_mut().expect("a non-empty map");
+        let removed = if chunk.len() > 1 {
+            Arc::make_mut(chunk).pop()
+        } else {
+            // Drop the emptied chunk, copying its entry only if it's shared.
+            let chunk = chunks.pop().expect("a non-empty map");
+            match Arc::try_unwrap(chunk) {
+                Ok(mut chunk) => chunk.pop(),
+                Err(shared) => shared.first().cloned(),
+            }
+        };
+        self.len -= 1;
+        removed.expect("a non-empty map")
+    }
+}
+
+impl<K, V, S> IndexMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    fn hash<Q: ?Sized + Hash>(&self, key: &Q) -> HashValue {
+        let mut h = self.hash_builder.build_hasher();
+        key.hash(&mut h);
+        HashValue(h.finish() as usize)
+    }
+
+    /// Return `true` if an equivalent to `key` exists in the map.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        self.get_index_of(key).is_some()
+    }
+
+    /// Return a reference to the value stored for `key`, if it is present,
+    /// else `None`.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let i = self.get_index_of(key)?;
+        Some(&bucket(&self.chunks, i).value)
+    }
+
+    /// Return references to the key-value pair stored for `key`,
+    /// if it is present, else `None`.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let i = self.get_index_of(key)?;
+        Some(bucket(&self.chunks, i).refs())
+    }
+
+    /// Return item index, key and value
+    pub fn get_full<Q>(&self, key: &Q) -> Option<(usize, &K, &V)>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let i = self.get_index_of(key)?;
+        let (key, value) = bucket(&self.chunks, i).refs();
+        Some((i, key, value))
+    }
+
+    /// Return item index, if it exists in the map
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        if self.is_empty() {
+            return None;
+        }
+        self.find(self.hash(key), key)
+    }
+}
+
+impl<K, V, S> IndexMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher,
+{
+    /// Insert a key-value pair in the map.
+    ///
+    /// If an equivalent key already exists in the map: the key remains and
+    /// retains in its place in the order, its corresponding value is updated
+    /// with `value`, and the older value is returned inside `Some(_)`.
+    ///
+    /// If no equivalent key existed in the map: the new key-value pair is
+    /// inserted, last in order, and `None` is returned.
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert_full(key, value).1
+    }
+
+    /// Insert a key-value pair in the map, and get their index.
+    ///
+    /// If an equivalent key already exists in the map: the key remains and
+    /// retains in its place in the order, its corresponding value is updated
+    /// with `value` and the older value is returned inside `(index, Some(_))`.
+    ///
+    /// If no equivalent key existed in the map: the new key-value pair is
+    /// inserted, last in order, and `(index, None)` is returned.
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn insert_full(&mut self, key: K, value: V) -> (usize, Option<V>) {
+        let hash = self.hash(&key);
+        match self.find(hash, &key) {
+            Some(i) => (i, Some(mem::replace(&mut self.bucket_mut(i).value, value))),
+            None => (self.push(hash, key, value), None),
+        }
+    }
+
+    /// Return a mutable reference to the value stored for `key`, if it is
+    /// present, else `None`.
+    ///
+    /// This copies the chunk holding the entry if it's shared with a clone.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let i = self.get_index_of(key)?;
+        Some(&mut self.bucket_mut(i).value)
+    }
+
+    /// Remove the key-value pair equivalent to `key` and return
+    /// its value.
+    ///
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the pair is
+    /// removed by swapping it with the last element of the map and popping it
+    /// off. **This perturbs the position of what used to be the last element!**
+    ///
+    /// Return `None` if `key` is not in map.
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn swap_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let i = self.get_index_of(key)?;
+        self.swap_remove_index(i).map(|(_, v)| v)
+    }
+
+    /// Remove the key-value pair equivalent to `key` and return
+    /// its value.
+    ///
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the pair is removed by
+    /// shifting all of the elements that follow it, preserving their relative
+    /// order. **This perturbs the index of all of those elements!**
+    ///
+    /// Return `None` if `key` is not in map.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn shift_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        Q: ?Sized + Hash + Equivalent<K>,
+    {
+        let i = self.get_index_of(key)?;
+        self.shift_remove_index(i).map(|(_, v)| v)
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a IndexMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Access [`IndexMap`] values corresponding to a key.
+///
+/// ***Panics*** if `key` is not present in the map.
+impl<K, V, Q: ?Sized, S> Index<&Q> for IndexMap<K, V, S>
+where
+    Q: Hash + Equivalent<K>,
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("IndexMap: key not found")
+    }
+}
+
+/// Access [`IndexMap`] values at indexed positions.
+///
+/// ***Panics*** if `index` is out of bounds.
+impl<K, V, S> Index<usize> for IndexMap<K, V, S> {
+    type Output = V;
+
+    fn index(&self, index: usize) -> &V {
+        self.get_index(index)
+            .expect("IndexMap: index out of bounds")
+            .1
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for IndexMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iterable: I) -> Self {
+        let mut map = Self::with_hasher(S::default());
+        map.extend(iterable);
+        map
+    }
+}
+
+impl<K, V, S> Extend<(K, V)> for IndexMap<K, V, S>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iterable: I) {
+        iterable.into_iter().for_each(move |(k, v)| {
+            self.insert(k, v);
+        });
+    }
+}
+
+impl<K, V, S> Default for IndexMap<K, V, S>
+where
+    S: Default,
+{
+    /// Return an empty [`IndexMap`]
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K, V1, S1, V2, S2> PartialEq<IndexMap<K, V2, S2>> for IndexMap<K, V1, S1>
+where
+    K: Hash + Eq,
+    V1: PartialEq<V2>,
+    S1: BuildHasher,
+    S2: BuildHasher,
+{
+    fn eq(&self, other: &IndexMap<K, V2, S2>) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        self.iter()
+            .all(|(key, value)| other.get(key).map_or(false, |v| *value == *v))
+    }
+}
+
+impl<K, V, S> Eq for IndexMap<K, V, S>
+where
+    K: Eq + Hash,
+    V: Eq,
+    S: BuildHasher,
+{
+}
+
+/// An iterator over the entries of the chunks, front to back.
+struct Buckets<'a, K, V> {
+    chunks: slice::Iter<'a, Chunk<K, V>>,
+    front: slice::Iter<'a, Bucket<K, V>>,
+    back: slice::Iter<'a, Bucket<K, V>>,
+    len: usize,
+}
+
+impl<'a, K, V> Buckets<'a, K, V> {
+    fn new(chunks: &'a [Chunk<K, V>], len: usize) -> Self {
+        Buckets {
+            chunks: chunks.iter(),
+            front: [].iter(),
+            back: [].iter(),
+            len,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Buckets<'a, K, V> {
+    type Item = &'a Bucket<K, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.front.next() {
+                self.len -= 1;
+                return Some(entry);
+            }
+            match self.chunks.next() {
+                Some(chunk) => self.front = chunk.iter(),
+                None => {
+                    let entry = self.back.next()?;
+                    self.len -= 1;
+                    return Some(entry);
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Buckets<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.back.next_back() {
+                self.len -= 1;
+                return Some(entry);
+            }
+            match self.chunks.next_back() {
+                Some(chunk) => self.back = chunk.iter(),
+                None => {
+                    let entry = self.front.next_back()?;
+                    self.len -= 1;
+                    return Some(entry);
+                }
+            }
+        }
+    }
+}
+
+impl<K, V> ExactSizeIterator for Buckets<'_, K, V> {}
+
+impl<K, V> Clone for Buckets<'_, K, V> {
+    fn clone(&self) -> Self {
+        Buckets {
+            chunks: self.chunks.clone(),
+            front: self.front.clone(),
+            back: self.back.clone(),
+            len: self.len,
+        }
+    }
+}
+
+/// An iterator over the entries of an [`IndexMap`].
+///
+/// This `struct` is created by the [`IndexMap::iter`] method.
+/// See its documentation for more.
+pub struct Iter<'a, K, V> {
+    iter: Buckets<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    iterator_methods!(Bucket::refs);
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    double_ended_iterator_methods!(Bucket::refs);
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for Iter<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the keys of an [`IndexMap`].
+///
+/// This `struct` is created by the [`IndexMap::keys`] method.
+/// See its documentation for more.
+pub struct Keys<'a, K, V> {
+    iter: Buckets<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    iterator_methods!(Bucket::key_ref);
+}
+
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V> {
+    double_ended_iterator_methods!(Bucket::key_ref);
+}
+
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+
+impl<K, V> Clone for Keys<'_, K, V> {
+    fn clone(&self) -> Self {
+        Keys {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V> fmt::Debug for Keys<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+/// An iterator over the values of an [`IndexMap`].
+///
+/// This `struct` is created by the [`IndexMap::values`] method.
+/// See its documentation for more.
+pub struct Values<'a, K, V> {
+    iter: Buckets<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    iterator_methods!(Bucket::value_ref);
+}
+
+impl<K, V> DoubleEndedIterator for Values<'_, K, V> {
+    double_ended_iterator_methods!(Bucket::value_ref);
+}
+
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+impl<K, V> Clone for Values<'_, K, V> {
+    fn clone(&self) -> Self {
+        Values {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<K, V: fmt::Debug> fmt::Debug for Values<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
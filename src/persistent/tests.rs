@@ -0,0 +1,75 @@
+use super::*;
+use std::vec::Vec;
+
+#[test]
+fn snapshots_are_isolated() {
+    let mut map: IndexMap<u32, u32> = (0..200).map(|i| (i, i)).collect();
+    let snapshot = map.clone();
+    assert!(map.ptr_eq(&snapshot));
+
+    for i in 0..200 {
+        *map.get_mut(&i).unwrap() += 1000;
+    }
+    map.insert(500, 500);
+    assert!(!map.ptr_eq(&snapshot));
+
+    assert_eq!(snapshot.len(), 200);
+    assert!(snapshot.iter().all(|(k, v)| k == v));
+    assert!(map.values().take(200).all(|&v| v >= 1000));
+    assert_eq!(map.get_index_of(&500), Some(200));
+    assert_eq!(snapshot.get(&500), None);
+}
+
+#[test]
+fn chunks_are_shared_until_written() {
+    let mut map: IndexMap<u32, ()> = (0..300).map(|i| (i, ())).collect();
+    let snapshot = map.clone();
+    map.insert(300, ());
+
+    // Only the last chunk was copied for the append.
+    let shared = map
+        .chunks
+        .iter()
+        .zip(snapshot.chunks.iter())
+        .filter(|(a, b)| Arc::ptr_eq(a, b))
+        .count();
+    assert_eq!(shared, snapshot.chunks.len() - 1);
+}
+
+#[test]
+fn removal_keeps_index_consistent() {
+    let mut map: IndexMap<u32, u32> = (0..500).map(|i| (i, i * 2)).collect();
+    let snapshot = map.clone();
+
+    assert_eq!(map.shift_remove(&10), Some(20));
+    assert_eq!(map.swap_remove(&100), Some(200));
+    assert_eq!(map.pop(), Some((498, 996)));
+    assert_eq!(map.swap_remove_index(0), Some((0, 0)));
+    assert_eq!(map.shift_remove(&10), None);
+    assert_eq!(map.len(), 496);
+
+    let expected: Vec<u32> = {
+        let mut v: Vec<u32> = (0..500).filter(|&i| i != 10).collect();
+        let i = v.iter().position(|&k| k == 100).unwrap();
+        v.swap_remove(i);
+        v.pop();
+        v.swap_remove(0);
+        v
+    };
+    assert!(map.keys().copied().eq(expected.iter().copied()));
+    for (i, (k, v)) in map.iter().enumerate() {
+        assert_eq!(map.get_full(k), Some((i, k, v)));
+    }
+    assert!(map
+        .iter()
+        .rev()
+        .eq(map.iter().collect::<Vec<_>>().into_iter().rev()));
+
+    assert_eq!(snapshot.len(), 500);
+    assert!(snapshot.keys().copied().eq(0..500));
+
+    while map.pop().is_some() {}
+    assert!(map.is_empty());
+    assert!(map.chunks.is_empty());
+    assert_eq!(snapshot[499], 998);
+}
@@ -7,29 +7,31 @@ use super::collect;
 use rayon::iter::plumbing::{Consumer, ProducerCallback, UnindexedConsumer};
 use rayon::prelude::*;
 
-use crate::vec::Vec;
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{BuildHasher, Hash};
 use core::ops::RangeBounds;
 
+use crate::allocator::{self, Allocator};
 use crate::map::Slice;
 use crate::Bucket;
 use crate::Entries;
-use crate::IndexMap;
+use crate::{IndexMap, IndexType};
 
-impl<K, V, S> IntoParallelIterator for IndexMap<K, V, S>
+impl<K, V, S, Idx> IntoParallelIterator for IndexMap<K, V, S, Idx>
 where
     K: Send,
     V: Send,
+    Idx: IndexType,
 {
     type Item = (K, V);
     type Iter = IntoParIter<K, V>;
 
     fn into_par_iter(self) -> Self::Iter {
         IntoParIter {
-            entries: self.into_entries(),
+            entries: allocator::into_std_vec(self.into_entries()),
         }
     }
 }
@@ -74,10 +76,12 @@ impl<K: Send, V: Send> IndexedParallelIterator for IntoParIter<K, V> {
     indexed_parallel_iterator_methods!(Bucket::key_value);
 }
 
-impl<'a, K, V, S> IntoParallelIterator for &'a IndexMap<K, V, S>
+impl<'a, K, V, S, Idx, A> IntoParallelIterator for &'a IndexMap<K, V, S, Idx, A>
 where
     K: Sync,
     V: Sync,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Item = (&'a K, &'a V);
     type Iter = ParIter<'a, K, V>;
@@ -137,10 +141,12 @@ impl<K: Sync, V: Sync> IndexedParallelIterator for ParIter<'_, K, V> {
     indexed_parallel_iterator_methods!(Bucket::refs);
 }
 
-impl<'a, K, V, S> IntoParallelIterator for &'a mut IndexMap<K, V, S>
+impl<'a, K, V, S, Idx, A> IntoParallelIterator for &'a mut IndexMap<K, V, S, Idx, A>
 where
     K: Sync + Send,
     V: Send,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Item = (&'a K, &'a mut V);
     type Iter = ParIterMut<'a, K, V>;
@@ -194,10 +200,12 @@ impl<K: Sync + Send, V: Send> IndexedParallelIterator for ParIterMut<'_, K, V> {
     indexed_parallel_iterator_methods!(Bucket::ref_mut);
 }
 
-impl<'a, K, V, S> ParallelDrainRange<usize> for &'a mut IndexMap<K, V, S>
+impl<'a, K, V, S, Idx, A> ParallelDrainRange<usize> for &'a mut IndexMap<K, V, S, Idx, A>
 where
     K: Send,
     V: Send,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Item = (K, V);
     type Iter = ParDrain<'a, K, V>;
@@ -216,7 +224,7 @@ where
 ///
 /// [`IndexMap::par_drain`]: ../struct.IndexMap.html#method.par_drain
 pub struct ParDrain<'a, K: Send, V: Send> {
-    entries: rayon::vec::Drain<'a, Bucket<K, V>>,
+    entries: allocator::ParDrain<'a, Bucket<K, V>>,
 }
 
 impl<K: Send, V: Send> ParallelIterator for ParDrain<'_, K, V> {
@@ -234,10 +242,12 @@ impl<K: Send, V: Send> IndexedParallelIterator for ParDrain<'_, K, V> {
 /// The following methods **require crate feature `"rayon"`**.
 ///
 /// See also the `IntoParallelIterator` implementations.
-impl<K, V, S> IndexMap<K, V, S>
+impl<K, V, S, Idx, A> IndexMap<K, V, S, Idx, A>
 where
     K: Sync,
     V: Sync,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Return a parallel iterator over the keys of the map.
     ///
@@ -291,19 +301,23 @@ where
     }
 }
 
-impl<K, V, S> IndexMap<K, V, S>
+impl<K, V, S, Idx, A> IndexMap<K, V, S, Idx, A>
 where
     K: Hash + Eq + Sync,
     V: Sync,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Returns `true` if `self` contains all of the same key-value pairs as `other`,
     /// regardless of each map's indexed order, determined in parallel.
-    pub fn par_eq<V2, S2>(&self, other: &IndexMap<K, V2, S2>) -> bool
+    pub fn par_eq<V2, S2, Idx2, A2>(&self, other: &IndexMap<K, V2, S2, Idx2, A2>) -> bool
     where
         V: PartialEq<V2>,
         V2: Sync,
         S2: BuildHasher + Sync,
+        Idx2: IndexType,
+        A2: Allocator + Sync,
     {
         self.len() == other.len()
             && self
@@ -374,10 +388,12 @@ impl<K: Sync, V: Sync> IndexedParallelIterator for ParValues<'_, K, V> {
     indexed_parallel_iterator_methods!(Bucket::value_ref);
 }
 
-impl<K, V, S> IndexMap<K, V, S>
+impl<K, V, S, Idx, A> IndexMap<K, V, S, Idx, A>
 where
     K: Send,
     V: Send,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Return a parallel iterator over mutable references to the values of the map
     ///
@@ -406,10 +422,12 @@ where
     }
 }
 
-impl<K, V, S> IndexMap<K, V, S>
+impl<K, V, S, Idx, A> IndexMap<K, V, S, Idx, A>
 where
     K: Send,
     V: Send,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Sort the map’s key-value pairs in parallel, by the default ordering of the keys.
     pub fn par_sort_keys(&mut self)
@@ -435,17 +453,6 @@ where
         });
     }
 
-    /// Sort the key-value pairs of the map in parallel and return a by-value parallel
-    /// iterator of the key-value pairs with the result.
-    pub fn par_sorted_by<F>(self, cmp: F) -> IntoParIter<K, V>
-    where
-        F: Fn(&K, &V, &K, &V) -> Ordering + Sync,
-    {
-        let mut entries = self.into_entries();
-        entries.par_sort_by(move |a, b| cmp(&a.key, &a.value, &b.key, &b.value));
-        IntoParIter { entries }
-    }
-
     /// Sort the map's key-value pairs in parallel, by the default ordering of the keys.
     pub fn par_sort_unstable_keys(&mut self)
     where
@@ -470,17 +477,6 @@ where
         });
     }
 
-    /// Sort the key-value pairs of the map in parallel and return a by-value parallel
-    /// iterator of the key-value pairs with the result.
-    pub fn par_sorted_unstable_by<F>(self, cmp: F) -> IntoParIter<K, V>
-    where
-        F: Fn(&K, &V, &K, &V) -> Ordering + Sync,
-    {
-        let mut entries = self.into_entries();
-        entries.par_sort_unstable_by(move |a, b| cmp(&a.key, &a.value, &b.key, &b.value));
-        IntoParIter { entries }
-    }
-
     /// Sort the map’s key-value pairs in place and in parallel, using a sort-key extraction
     /// function.
     pub fn par_sort_by_cached_key<T, F>(&mut self, sort_key: F)
@@ -494,6 +490,35 @@ where
     }
 }
 
+impl<K, V, S, Idx> IndexMap<K, V, S, Idx>
+where
+    K: Send,
+    V: Send,
+    Idx: IndexType,
+{
+    /// Sort the key-value pairs of the map in parallel and return a by-value parallel
+    /// iterator of the key-value pairs with the result.
+    pub fn par_sorted_by<F>(self, cmp: F) -> IntoParIter<K, V>
+    where
+        F: Fn(&K, &V, &K, &V) -> Ordering + Sync,
+    {
+        let mut entries = allocator::into_std_vec(self.into_entries());
+        entries.par_sort_by(move |a, b| cmp(&a.key, &a.value, &b.key, &b.value));
+        IntoParIter { entries }
+    }
+
+    /// Sort the key-value pairs of the map in parallel and return a by-value parallel
+    /// iterator of the key-value pairs with the result.
+    pub fn par_sorted_unstable_by<F>(self, cmp: F) -> IntoParIter<K, V>
+    where
+        F: Fn(&K, &V, &K, &V) -> Ordering + Sync,
+    {
+        let mut entries = allocator::into_std_vec(self.into_entries());
+        entries.par_sort_unstable_by(move |a, b| cmp(&a.key, &a.value, &b.key, &b.value));
+        IntoParIter { entries }
+    }
+}
+
 /// A parallel mutable iterator over the values of an [`IndexMap`].
 ///
 /// This `struct` is created by the [`IndexMap::par_values_mut`] method.
@@ -519,11 +544,12 @@ impl<K: Send, V: Send> IndexedParallelIterator for ParValuesMut<'_, K, V> {
     indexed_parallel_iterator_methods!(Bucket::value_mut);
 }
 
-impl<K, V, S> FromParallelIterator<(K, V)> for IndexMap<K, V, S>
+impl<K, V, S, Idx> FromParallelIterator<(K, V)> for IndexMap<K, V, S, Idx>
 where
     K: Eq + Hash + Send,
     V: Send,
     S: BuildHasher + Default + Send,
+    Idx: IndexType,
 {
     fn from_par_iter<I>(iter: I) -> Self
     where
@@ -531,7 +557,7 @@ where
     {
         let list = collect(iter);
         let len = list.iter().map(Vec::len).sum();
-        let mut map = Self::with_capacity_and_hasher(len, S::default());
+        let mut map = Self::with_capacity_and_hasher_idx(len, S::default());
         for vec in list {
             map.extend(vec);
         }
@@ -539,11 +565,13 @@ where
     }
 }
 
-impl<K, V, S> ParallelExtend<(K, V)> for IndexMap<K, V, S>
+impl<K, V, S, Idx, A> ParallelExtend<(K, V)> for IndexMap<K, V, S, Idx, A>
 where
     K: Eq + Hash + Send,
     V: Send,
     S: BuildHasher + Send,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn par_extend<I>(&mut self, iter: I)
     where
@@ -555,11 +583,13 @@ where
     }
 }
 
-impl<'a, K: 'a, V: 'a, S> ParallelExtend<(&'a K, &'a V)> for IndexMap<K, V, S>
+impl<'a, K: 'a, V: 'a, S, Idx, A> ParallelExtend<(&'a K, &'a V)> for IndexMap<K, V, S, Idx, A>
 where
     K: Copy + Eq + Hash + Send + Sync,
     V: Copy + Send + Sync,
     S: BuildHasher + Send,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn par_extend<I>(&mut self, iter: I)
     where
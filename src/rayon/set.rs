@@ -7,29 +7,31 @@ use super::collect;
 use rayon::iter::plumbing::{Consumer, ProducerCallback, UnindexedConsumer};
 use rayon::prelude::*;
 
-use crate::vec::Vec;
 use alloc::boxed::Box;
+use alloc::vec::Vec;
 use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{BuildHasher, Hash};
 use core::ops::RangeBounds;
 
+use crate::allocator::{self, Allocator, Global};
 use crate::set::Slice;
 use crate::Entries;
-use crate::IndexSet;
+use crate::{IndexSet, IndexType};
 
 type Bucket<T> = crate::Bucket<T, ()>;
 
-impl<T, S> IntoParallelIterator for IndexSet<T, S>
+impl<T, S, Idx> IntoParallelIterator for IndexSet<T, S, Idx>
 where
     T: Send,
+    Idx: IndexType,
 {
     type Item = T;
     type Iter = IntoParIter<T>;
 
     fn into_par_iter(self) -> Self::Iter {
         IntoParIter {
-            entries: self.into_entries(),
+            entries: allocator::into_std_vec(self.into_entries()),
         }
     }
 }
@@ -73,9 +75,11 @@ impl<T: Send> IndexedParallelIterator for IntoParIter<T> {
     indexed_parallel_iterator_methods!(Bucket::key);
 }
 
-impl<'a, T, S> IntoParallelIterator for &'a IndexSet<T, S>
+impl<'a, T, S, Idx, A> IntoParallelIterator for &'a IndexSet<T, S, Idx, A>
 where
     T: Sync,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Item = &'a T;
     type Iter = ParIter<'a, T>;
@@ -134,9 +138,11 @@ impl<T: Sync> IndexedParallelIterator for ParIter<'_, T> {
     indexed_parallel_iterator_methods!(Bucket::key_ref);
 }
 
-impl<'a, T, S> ParallelDrainRange<usize> for &'a mut IndexSet<T, S>
+impl<'a, T, S, Idx, A> ParallelDrainRange<usize> for &'a mut IndexSet<T, S, Idx, A>
 where
     T: Send,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Item = T;
     type Iter = ParDrain<'a, T>;
@@ -155,7 +161,7 @@ where
 ///
 /// [`IndexSet::par_drain`]: ../struct.IndexSet.html#method.par_drain
 pub struct ParDrain<'a, T: Send> {
-    entries: rayon::vec::Drain<'a, Bucket<T>>,
+    entries: allocator::ParDrain<'a, Bucket<T>>,
 }
 
 impl<T: Send> ParallelIterator for ParDrain<'_, T> {
@@ -173,21 +179,25 @@ impl<T: Send> IndexedParallelIterator for ParDrain<'_, T> {
 /// The following methods **require crate feature `"rayon"`**.
 ///
 /// See also the `IntoParallelIterator` implementations.
-impl<T, S> IndexSet<T, S>
+impl<T, S, Idx, A> IndexSet<T, S, Idx, A>
 where
     T: Hash + Eq + Sync,
     S: BuildHasher + Sync,
+    Idx: IndexType,
+    A: Allocator + Sync,
 {
     /// Return a parallel iterator over the values that are in `self` but not `other`.
     ///
     /// While parallel iterators can process items in any order, their relative order
     /// in the `self` set is still preserved for operations like `reduce` and `collect`.
-    pub fn par_difference<'a, S2>(
+    pub fn par_difference<'a, S2, Idx2, A2>(
         &'a self,
-        other: &'a IndexSet<T, S2>,
-    ) -> ParDifference<'a, T, S, S2>
+        other: &'a IndexSet<T, S2, Idx2, A2>,
+    ) -> ParDifference<'a, T, S, S2, Idx, Idx2, A, A2>
     where
         S2: BuildHasher + Sync,
+        Idx2: IndexType,
+        A2: Allocator + Sync,
     {
         ParDifference {
             set1: self,
@@ -202,12 +212,14 @@ where
     /// in the sets is still preserved for operations like `reduce` and `collect`.
     /// Values from `self` are produced in their original order, followed by
     /// values from `other` in their original order.
-    pub fn par_symmetric_difference<'a, S2>(
+    pub fn par_symmetric_difference<'a, S2, Idx2, A2>(
         &'a self,
-        other: &'a IndexSet<T, S2>,
-    ) -> ParSymmetricDifference<'a, T, S, S2>
+        other: &'a IndexSet<T, S2, Idx2, A2>,
+    ) -> ParSymmetricDifference<'a, T, S, S2, Idx, Idx2, A, A2>
     where
         S2: BuildHasher + Sync,
+        Idx2: IndexType,
+        A2: Allocator + Sync,
     {
         ParSymmetricDifference {
             set1: self,
@@ -219,12 +231,14 @@ where
     ///
     /// While parallel iterators can process items in any order, their relative order
     /// in the `self` set is still preserved for operations like `reduce` and `collect`.
-    pub fn par_intersection<'a, S2>(
+    pub fn par_intersection<'a, S2, Idx2, A2>(
         &'a self,
-        other: &'a IndexSet<T, S2>,
-    ) -> ParIntersection<'a, T, S, S2>
+        other: &'a IndexSet<T, S2, Idx2, A2>,
+    ) -> ParIntersection<'a, T, S, S2, Idx, Idx2, A, A2>
     where
         S2: BuildHasher + Sync,
+        Idx2: IndexType,
+        A2: Allocator + Sync,
     {
         ParIntersection {
             set1: self,
@@ -238,9 +252,14 @@ where
     /// in the sets is still preserved for operations like `reduce` and `collect`.
     /// Values from `self` are produced in their original order, followed by
     /// values that are unique to `other` in their original order.
-    pub fn par_union<'a, S2>(&'a self, other: &'a IndexSet<T, S2>) -> ParUnion<'a, T, S, S2>
+    pub fn par_union<'a, S2, Idx2, A2>(
+        &'a self,
+        other: &'a IndexSet<T, S2, Idx2, A2>,
+    ) -> ParUnion<'a, T, S, S2, Idx, Idx2, A, A2>
     where
         S2: BuildHasher + Sync,
+        Idx2: IndexType,
+        A2: Allocator + Sync,
     {
         ParUnion {
             set1: self,
@@ -250,18 +269,22 @@ where
 
     /// Returns `true` if `self` contains all of the same values as `other`,
     /// regardless of each set's indexed order, determined in parallel.
-    pub fn par_eq<S2>(&self, other: &IndexSet<T, S2>) -> bool
+    pub fn par_eq<S2, Idx2, A2>(&self, other: &IndexSet<T, S2, Idx2, A2>) -> bool
     where
         S2: BuildHasher + Sync,
+        Idx2: IndexType,
+        A2: Allocator + Sync,
     {
         self.len() == other.len() && self.par_is_subset(other)
     }
 
     /// Returns `true` if `self` has no elements in common with `other`,
     /// determined in parallel.
-    pub fn par_is_disjoint<S2>(&self, other: &IndexSet<T, S2>) -> bool
+    pub fn par_is_disjoint<S2, Idx2, A2>(&self, other: &IndexSet<T, S2, Idx2, A2>) -> bool
     where
         S2: BuildHasher + Sync,
+        Idx2: IndexType,
+        A2: Allocator + Sync,
     {
         if self.len() <= other.len() {
             self.par_iter().all(move |value| !other.contains(value))
@@ -272,18 +295,22 @@ where
 
     /// Returns `true` if all elements of `other` are contained in `self`,
     /// determined in parallel.
-    pub fn par_is_superset<S2>(&self, other: &IndexSet<T, S2>) -> bool
+    pub fn par_is_superset<S2, Idx2, A2>(&self, other: &IndexSet<T, S2, Idx2, A2>) -> bool
     where
         S2: BuildHasher + Sync,
+        Idx2: IndexType,
+        A2: Allocator + Sync,
     {
         other.par_is_subset(self)
     }
 
     /// Returns `true` if all elements of `self` are contained in `other`,
     /// determined in parallel.
-    pub fn par_is_subset<S2>(&self, other: &IndexSet<T, S2>) -> bool
+    pub fn par_is_subset<S2, Idx2, A2>(&self, other: &IndexSet<T, S2, Idx2, A2>) -> bool
     where
         S2: BuildHasher + Sync,
+        Idx2: IndexType,
+        A2: Allocator + Sync,
     {
         self.len() <= other.len() && self.par_iter().all(move |value| other.contains(value))
     }
@@ -293,22 +320,37 @@ where
 ///
 /// This `struct` is created by the [`IndexSet::par_difference`] method.
 /// See its documentation for more.
-pub struct ParDifference<'a, T, S1, S2> {
-    set1: &'a IndexSet<T, S1>,
-    set2: &'a IndexSet<T, S2>,
-}
-
-impl<T, S1, S2> Clone for ParDifference<'_, T, S1, S2> {
+pub struct ParDifference<
+    'a,
+    T,
+    S1,
+    S2,
+    Idx1 = usize,
+    Idx2 = usize,
+    A1: Allocator = Global,
+    A2: Allocator = Global,
+> {
+    set1: &'a IndexSet<T, S1, Idx1, A1>,
+    set2: &'a IndexSet<T, S2, Idx2, A2>,
+}
+
+impl<T, S1, S2, Idx1, Idx2, A1: Allocator, A2: Allocator> Clone
+    for ParDifference<'_, T, S1, S2, Idx1, Idx2, A1, A2>
+{
     fn clone(&self) -> Self {
         ParDifference { ..*self }
     }
 }
 
-impl<T, S1, S2> fmt::Debug for ParDifference<'_, T, S1, S2>
+impl<T, S1, S2, Idx1, Idx2, A1, A2> fmt::Debug for ParDifference<'_, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: fmt::Debug + Eq + Hash,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list()
@@ -317,11 +359,16 @@ where
     }
 }
 
-impl<'a, T, S1, S2> ParallelIterator for ParDifference<'a, T, S1, S2>
+impl<'a, T, S1, S2, Idx1, Idx2, A1, A2> ParallelIterator
+    for ParDifference<'a, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: Hash + Eq + Sync,
     S1: BuildHasher + Sync,
     S2: BuildHasher + Sync,
+    Idx1: IndexType,
+    A1: Allocator + Sync,
+    Idx2: IndexType,
+    A2: Allocator + Sync,
 {
     type Item = &'a T;
 
@@ -341,22 +388,38 @@ where
 ///
 /// This `struct` is created by the [`IndexSet::par_intersection`] method.
 /// See its documentation for more.
-pub struct ParIntersection<'a, T, S1, S2> {
-    set1: &'a IndexSet<T, S1>,
-    set2: &'a IndexSet<T, S2>,
-}
-
-impl<T, S1, S2> Clone for ParIntersection<'_, T, S1, S2> {
+pub struct ParIntersection<
+    'a,
+    T,
+    S1,
+    S2,
+    Idx1 = usize,
+    Idx2 = usize,
+    A1: Allocator = Global,
+    A2: Allocator = Global,
+> {
+    set1: &'a IndexSet<T, S1, Idx1, A1>,
+    set2: &'a IndexSet<T, S2, Idx2, A2>,
+}
+
+impl<T, S1, S2, Idx1, Idx2, A1: Allocator, A2: Allocator> Clone
+    for ParIntersection<'_, T, S1, S2, Idx1, Idx2, A1, A2>
+{
     fn clone(&self) -> Self {
         ParIntersection { ..*self }
     }
 }
 
-impl<T, S1, S2> fmt::Debug for ParIntersection<'_, T, S1, S2>
+impl<T, S1, S2, Idx1, Idx2, A1, A2> fmt::Debug
+    for ParIntersection<'_, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: fmt::Debug + Eq + Hash,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list()
@@ -365,11 +428,16 @@ where
     }
 }
 
-impl<'a, T, S1, S2> ParallelIterator for ParIntersection<'a, T, S1, S2>
+impl<'a, T, S1, S2, Idx1, Idx2, A1, A2> ParallelIterator
+    for ParIntersection<'a, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: Hash + Eq + Sync,
     S1: BuildHasher + Sync,
     S2: BuildHasher + Sync,
+    Idx1: IndexType,
+    A1: Allocator + Sync,
+    Idx2: IndexType,
+    A2: Allocator + Sync,
 {
     type Item = &'a T;
 
@@ -389,22 +457,38 @@ where
 ///
 /// This `struct` is created by the [`IndexSet::par_symmetric_difference`] method.
 /// See its documentation for more.
-pub struct ParSymmetricDifference<'a, T, S1, S2> {
-    set1: &'a IndexSet<T, S1>,
-    set2: &'a IndexSet<T, S2>,
-}
-
-impl<T, S1, S2> Clone for ParSymmetricDifference<'_, T, S1, S2> {
+pub struct ParSymmetricDifference<
+    'a,
+    T,
+    S1,
+    S2,
+    Idx1 = usize,
+    Idx2 = usize,
+    A1: Allocator = Global,
+    A2: Allocator = Global,
+> {
+    set1: &'a IndexSet<T, S1, Idx1, A1>,
+    set2: &'a IndexSet<T, S2, Idx2, A2>,
+}
+
+impl<T, S1, S2, Idx1, Idx2, A1: Allocator, A2: Allocator> Clone
+    for ParSymmetricDifference<'_, T, S1, S2, Idx1, Idx2, A1, A2>
+{
     fn clone(&self) -> Self {
         ParSymmetricDifference { ..*self }
     }
 }
 
-impl<T, S1, S2> fmt::Debug for ParSymmetricDifference<'_, T, S1, S2>
+impl<T, S1, S2, Idx1, Idx2, A1, A2> fmt::Debug
+    for ParSymmetricDifference<'_, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: fmt::Debug + Eq + Hash,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list()
@@ -413,11 +497,16 @@ where
     }
 }
 
-impl<'a, T, S1, S2> ParallelIterator for ParSymmetricDifference<'a, T, S1, S2>
+impl<'a, T, S1, S2, Idx1, Idx2, A1, A2> ParallelIterator
+    for ParSymmetricDifference<'a, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: Hash + Eq + Sync,
     S1: BuildHasher + Sync,
     S2: BuildHasher + Sync,
+    Idx1: IndexType,
+    A1: Allocator + Sync,
+    Idx2: IndexType,
+    A2: Allocator + Sync,
 {
     type Item = &'a T;
 
@@ -437,33 +526,53 @@ where
 ///
 /// This `struct` is created by the [`IndexSet::par_union`] method.
 /// See its documentation for more.
-pub struct ParUnion<'a, T, S1, S2> {
-    set1: &'a IndexSet<T, S1>,
-    set2: &'a IndexSet<T, S2>,
-}
-
-impl<T, S1, S2> Clone for ParUnion<'_, T, S1, S2> {
+pub struct ParUnion<
+    'a,
+    T,
+    S1,
+    S2,
+    Idx1 = usize,
+    Idx2 = usize,
+    A1: Allocator = Global,
+    A2: Allocator = Global,
+> {
+    set1: &'a IndexSet<T, S1, Idx1, A1>,
+    set2: &'a IndexSet<T, S2, Idx2, A2>,
+}
+
+impl<T, S1, S2, Idx1, Idx2, A1: Allocator, A2: Allocator> Clone
+    for ParUnion<'_, T, S1, S2, Idx1, Idx2, A1, A2>
+{
     fn clone(&self) -> Self {
         ParUnion { ..*self }
     }
 }
 
-impl<T, S1, S2> fmt::Debug for ParUnion<'_, T, S1, S2>
+impl<T, S1, S2, Idx1, Idx2, A1, A2> fmt::Debug for ParUnion<'_, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: fmt::Debug + Eq + Hash,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.set1.union(self.set2)).finish()
     }
 }
 
-impl<'a, T, S1, S2> ParallelIterator for ParUnion<'a, T, S1, S2>
+impl<'a, T, S1, S2, Idx1, Idx2, A1, A2> ParallelIterator
+    for ParUnion<'a, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: Hash + Eq + Sync,
     S1: BuildHasher + Sync,
     S2: BuildHasher + Sync,
+    Idx1: IndexType,
+    A1: Allocator + Sync,
+    Idx2: IndexType,
+    A2: Allocator + Sync,
 {
     type Item = &'a T;
 
@@ -482,9 +591,11 @@ where
 /// Parallel sorting methods.
 ///
 /// The following methods **require crate feature `"rayon"`**.
-impl<T, S> IndexSet<T, S>
+impl<T, S, Idx, A> IndexSet<T, S, Idx, A>
 where
     T: Send,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Sort the set’s values in parallel by their default ordering.
     pub fn par_sort(&mut self)
@@ -506,17 +617,6 @@ where
         });
     }
 
-    /// Sort the values of the set in parallel and return a by-value parallel iterator of
-    /// the values with the result.
-    pub fn par_sorted_by<F>(self, cmp: F) -> IntoParIter<T>
-    where
-        F: Fn(&T, &T) -> Ordering + Sync,
-    {
-        let mut entries = self.into_entries();
-        entries.par_sort_by(move |a, b| cmp(&a.key, &b.key));
-        IntoParIter { entries }
-    }
-
     /// Sort the set's values in parallel by their default ordering.
     pub fn par_sort_unstable(&mut self)
     where
@@ -537,33 +637,54 @@ where
         });
     }
 
+    /// Sort the set’s values in place and in parallel, using a key extraction function.
+    pub fn par_sort_by_cached_key<K, F>(&mut self, sort_key: F)
+    where
+        K: Ord + Send,
+        F: Fn(&T) -> K + Sync,
+    {
+        self.with_entries(move |entries| {
+            entries.par_sort_by_cached_key(move |a| sort_key(&a.key));
+        });
+    }
+}
+
+/// Parallel sorting methods that return an owning parallel iterator.
+///
+/// The following methods **require crate feature `"rayon"`**.
+impl<T, S, Idx> IndexSet<T, S, Idx>
+where
+    T: Send,
+    Idx: IndexType,
+{
     /// Sort the values of the set in parallel and return a by-value parallel iterator of
     /// the values with the result.
-    pub fn par_sorted_unstable_by<F>(self, cmp: F) -> IntoParIter<T>
+    pub fn par_sorted_by<F>(self, cmp: F) -> IntoParIter<T>
     where
         F: Fn(&T, &T) -> Ordering + Sync,
     {
-        let mut entries = self.into_entries();
-        entries.par_sort_unstable_by(move |a, b| cmp(&a.key, &b.key));
+        let mut entries = allocator::into_std_vec(self.into_entries());
+        entries.par_sort_by(move |a, b| cmp(&a.key, &b.key));
         IntoParIter { entries }
     }
 
-    /// Sort the set’s values in place and in parallel, using a key extraction function.
-    pub fn par_sort_by_cached_key<K, F>(&mut self, sort_key: F)
+    /// Sort the values of the set in parallel and return a by-value parallel iterator of
+    /// the values with the result.
+    pub fn par_sorted_unstable_by<F>(self, cmp: F) -> IntoParIter<T>
     where
-        K: Ord + Send,
-        F: Fn(&T) -> K + Sync,
+        F: Fn(&T, &T) -> Ordering + Sync,
     {
-        self.with_entries(move |entries| {
-            entries.par_sort_by_cached_key(move |a| sort_key(&a.key));
-        });
+        let mut entries = allocator::into_std_vec(self.into_entries());
+        entries.par_sort_unstable_by(move |a, b| cmp(&a.key, &b.key));
+        IntoParIter { entries }
     }
 }
 
-impl<T, S> FromParallelIterator<T> for IndexSet<T, S>
+impl<T, S, Idx> FromParallelIterator<T> for IndexSet<T, S, Idx>
 where
     T: Eq + Hash + Send,
     S: BuildHasher + Default + Send,
+    Idx: IndexType,
 {
     fn from_par_iter<I>(iter: I) -> Self
     where
@@ -571,7 +692,7 @@ where
     {
         let list = collect(iter);
         let len = list.iter().map(Vec::len).sum();
-        let mut set = Self::with_capacity_and_hasher(len, S::default());
+        let mut set = Self::with_capacity_and_hasher_idx(len, S::default());
         for vec in list {
             set.extend(vec);
         }
@@ -579,10 +700,12 @@ where
     }
 }
 
-impl<T, S> ParallelExtend<T> for IndexSet<T, S>
+impl<T, S, Idx, A> ParallelExtend<T> for IndexSet<T, S, Idx, A>
 where
     T: Eq + Hash + Send,
     S: BuildHasher + Send,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn par_extend<I>(&mut self, iter: I)
     where
@@ -594,10 +717,12 @@ where
     }
 }
 
-impl<'a, T: 'a, S> ParallelExtend<&'a T> for IndexSet<T, S>
+impl<'a, T: 'a, S, Idx, A> ParallelExtend<&'a T> for IndexSet<T, S, Idx, A>
 where
     T: Copy + Eq + Hash + Send + Sync,
     S: BuildHasher + Send,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn par_extend<I>(&mut self, iter: I)
     where
@@ -1,7 +1,8 @@
 //! Minimal support for `rustc-rayon`, not intended for general use.
 
-use crate::vec::Vec;
-use crate::{Bucket, Entries, IndexMap, IndexSet};
+use crate::allocator::{self, Allocator};
+use crate::{Bucket, Entries, IndexMap, IndexSet, IndexType};
+use alloc::vec::Vec;
 
 use rustc_rayon::iter::plumbing::{Consumer, ProducerCallback, UnindexedConsumer};
 use rustc_rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
@@ -9,17 +10,18 @@ use rustc_rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelI
 mod map {
     use super::*;
 
-    impl<K, V, S> IntoParallelIterator for IndexMap<K, V, S>
+    impl<K, V, S, Idx> IntoParallelIterator for IndexMap<K, V, S, Idx>
     where
         K: Send,
         V: Send,
+        Idx: IndexType,
     {
         type Item = (K, V);
         type Iter = IntoParIter<K, V>;
 
         fn into_par_iter(self) -> Self::Iter {
             IntoParIter {
-                entries: self.into_entries(),
+                entries: allocator::into_std_vec(self.into_entries()),
             }
         }
     }
@@ -38,10 +40,12 @@ mod map {
         indexed_parallel_iterator_methods!(Bucket::key_value);
     }
 
-    impl<'a, K, V, S> IntoParallelIterator for &'a IndexMap<K, V, S>
+    impl<'a, K, V, S, Idx, A> IntoParallelIterator for &'a IndexMap<K, V, S, Idx, A>
     where
         K: Sync,
         V: Sync,
+        Idx: IndexType,
+        A: Allocator,
     {
         type Item = (&'a K, &'a V);
         type Iter = ParIter<'a, K, V>;
@@ -67,10 +71,12 @@ mod map {
         indexed_parallel_iterator_methods!(Bucket::refs);
     }
 
-    impl<'a, K, V, S> IntoParallelIterator for &'a mut IndexMap<K, V, S>
+    impl<'a, K, V, S, Idx, A> IntoParallelIterator for &'a mut IndexMap<K, V, S, Idx, A>
     where
         K: Sync + Send,
         V: Send,
+        Idx: IndexType,
+        A: Allocator,
     {
         type Item = (&'a K, &'a mut V);
         type Iter = ParIterMut<'a, K, V>;
@@ -100,16 +106,17 @@ mod map {
 mod set {
     use super::*;
 
-    impl<T, S> IntoParallelIterator for IndexSet<T, S>
+    impl<T, S, Idx> IntoParallelIterator for IndexSet<T, S, Idx>
     where
         T: Send,
+        Idx: IndexType,
     {
         type Item = T;
         type Iter = IntoParIter<T>;
 
         fn into_par_iter(self) -> Self::Iter {
             IntoParIter {
-                entries: self.into_entries(),
+                entries: allocator::into_std_vec(self.into_entries()),
             }
         }
     }
@@ -128,9 +135,11 @@ mod set {
         indexed_parallel_iterator_methods!(Bucket::key);
     }
 
-    impl<'a, T, S> IntoParallelIterator for &'a IndexSet<T, S>
+    impl<'a, T, S, Idx, A> IntoParallelIterator for &'a IndexSet<T, S, Idx, A>
     where
         T: Sync,
+        Idx: IndexType,
+        A: Allocator,
     {
         type Item = &'a T;
         type Iter = ParIter<'a, T>;
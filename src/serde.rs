@@ -2,20 +2,25 @@
 
 use serde::de::value::{MapDeserializer, SeqDeserializer};
 use serde::de::{
-    Deserialize, Deserializer, Error, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+    Deserialize, DeserializeSeed, Deserializer, Error, Expected, IntoDeserializer, MapAccess,
+    SeqAccess, Visitor,
 };
 use serde::ser::{Serialize, Serializer};
 
 use core::fmt::{self, Formatter};
 use core::hash::{BuildHasher, Hash};
 use core::marker::PhantomData;
+use core::mem;
 
-use crate::IndexMap;
+use crate::allocator::Allocator;
+use crate::{Bucket, IndexMap, IndexType};
 
-impl<K, V, S> Serialize for IndexMap<K, V, S>
+impl<K, V, S, Idx, A> Serialize for IndexMap<K, V, S, Idx, A>
 where
     K: Serialize,
     V: Serialize,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn serialize<T>(&self, serializer: T) -> Result<T::Ok, T::Error>
     where
@@ -25,15 +30,224 @@ where
     }
 }
 
-struct IndexMapVisitor<K, V, S>(PhantomData<(K, V, S)>);
+/// The most memory that is preallocated from a size hint by default, since the
+/// size hint may come from untrusted input, like serde's own cautious hints.
+const MAX_PREALLOC_BYTES: usize = 1024 * 1024;
 
-impl<'de, K, V, S> Visitor<'de> for IndexMapVisitor<K, V, S>
+/// Return how many elements of `T` to preallocate for an untrusted size hint.
+pub(crate) fn cautious_capacity<T>(size_hint: Option<usize>) -> usize {
+    let max = MAX_PREALLOC_BYTES / Ord::max(mem::size_of::<T>(), 1);
+    Ord::min(size_hint.unwrap_or(0), max)
+}
+
+/// The expected length in the error for deserializing too many entries
+struct AtMost(usize);
+
+impl Expected for AtMost {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "at most {} entries", self.0)
+    }
+}
+
+/// A [`DeserializeSeed`] that creates an [`IndexMap`] with a given hasher.
+///
+/// The [`Deserialize`] implementation of `IndexMap` needs `S: Default` to
+/// create its hasher, which rules out hashers that are created with a key or
+/// seed, so this seed takes the hasher instance instead. It deserializes the
+/// same map format, and [`map::serde_seq::IndexMapSeed`][crate::map::serde_seq::IndexMapSeed]
+/// is the counterpart for the ordered sequence format.
+///
+/// The seed can also bound the memory for untrusted input, with
+/// [`with_max_len`][Self::with_max_len] and
+/// [`with_capacity_limit`][Self::with_capacity_limit]. Either way, the map is
+/// allocated with [`try_reserve`][IndexMap::try_reserve], so a failed
+/// allocation is a deserialization error rather than an abort.
+///
+/// ```
+/// use indexmap::map::IndexMapSeed;
+/// use indexmap::IndexMap;
+/// use serde::de::{value::MapDeserializer, DeserializeSeed};
+/// use std::collections::hash_map::RandomState;
+///
+/// let hasher = RandomState::new();
+/// let de = MapDeserializer::<_, serde::de::value::Error>::new([(1, 'a'), (2, 'b')].into_iter());
+/// let map: IndexMap<_, _, _> = IndexMapSeed::new(hasher).deserialize(de).unwrap();
+/// assert!(map.into_iter().eq([(1, 'a'), (2, 'b')]));
+///
+/// let de = MapDeserializer::<_, serde::de::value::Error>::new([(1, 'a'), (2, 'b')].into_iter());
+/// let result = IndexMapSeed::<i32, char, _>::new(RandomState::new())
+///     .with_max_len(1)
+///     .deserialize(de);
+/// assert!(result.is_err());
+/// ```
+pub struct IndexMapSeed<K, V, S, Idx = usize> {
+    hash_builder: S,
+    capacity_limit: Option<usize>,
+    max_len: usize,
+    marker: PhantomData<(K, V, Idx)>,
+}
+
+impl<K, V, S, Idx> IndexMapSeed<K, V, S, Idx> {
+    /// Create a seed for an `IndexMap` that will use the given hasher.
+    pub fn new(hash_builder: S) -> Self {
+        IndexMapSeed {
+            hash_builder,
+            capacity_limit: None,
+            max_len: usize::MAX,
+            marker: PhantomData,
+        }
+    }
+
+    /// Limit the capacity that is allocated up front to `limit` entries.
+    ///
+    /// By default, the map preallocates as many entries as the size hint of
+    /// the format, up to about 1 MiB. This doesn't limit how many entries can
+    /// be deserialized, since the map still grows as usual past its initial
+    /// capacity.
+    pub fn with_capacity_limit(mut self, limit: usize) -> Self {
+        self.capacity_limit = Some(limit);
+        self
+    }
+
+    /// Fail to deserialize a map with more than `max_len` entries.
+    ///
+    /// A size hint over the limit fails before anything is allocated, and
+    /// otherwise the map fails as soon as it grows past the limit, with an
+    /// [`invalid_length`][Error::invalid_length] error.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Build a map from the size hint and the entries of the input.
+    pub(crate) fn build<E, F>(
+        self,
+        size_hint: Option<usize>,
+        next_entry: F,
+    ) -> Result<IndexMap<K, V, S, Idx>, E>
+    where
+        K: Hash + Eq,
+        S: BuildHasher,
+        Idx: IndexType,
+        E: Error,
+        F: FnMut() -> Result<Option<(K, V)>, E>,
+    {
+        self.build_with(size_hint, next_entry, |map, key, value| {
+            map.insert(key, value);
+            Ok(())
+        })
+    }
+
+    /// Build a map like [`build`][Self::build], but add each entry with `insert`.
+    pub(crate) fn build_with<E, F, I>(
+        self,
+        size_hint: Option<usize>,
+        next_entry: F,
+        insert: I,
+    ) -> Result<IndexMap<K, V, S, Idx>, E>
+    where
+        K: Hash + Eq,
+        S: BuildHasher,
+        Idx: IndexType,
+        E: Error,
+        F: FnMut() -> Result<Option<(K, V)>, E>,
+        I: FnMut(&mut IndexMap<K, V, S, Idx>, K, V) -> Result<(), E>,
+    {
+        if let Some(len) = size_hint.filter(|&len| len > self.max_len) {
+            return Err(E::invalid_length(len, &AtMost(self.max_len)));
+        }
+        let capacity = match self.capacity_limit {
+            Some(limit) => Ord::min(size_hint.unwrap_or(0), limit),
+            None => cautious_capacity::<Bucket<K, V>>(size_hint),
+        };
+        let mut map = IndexMap::with_hasher_idx(self.hash_builder);
+        extend_bounded(&mut map, capacity, self.max_len, next_entry, insert)?;
+        Ok(map)
+    }
+}
+
+/// Add the entries of the input to `map` with `insert`, after reserving
+/// `capacity` entries, and fail once the map holds more than `max_len`.
+///
+/// The map only grows with [`try_reserve`][IndexMap::try_reserve], so running
+/// out of memory or indices is an error rather than a panic or an abort.
+fn extend_bounded<K, V, S, Idx, A, E, F, I>(
+    map: &mut IndexMap<K, V, S, Idx, A>,
+    capacity: usize,
+    max_len: usize,
+    mut next_entry: F,
+    mut insert: I,
+) -> Result<(), E>
+where
+    Idx: IndexType,
+    A: Allocator,
+    E: Error,
+    F: FnMut() -> Result<Option<(K, V)>, E>,
+    I: FnMut(&mut IndexMap<K, V, S, Idx, A>, K, V) -> Result<(), E>,
+{
+    map.try_reserve(capacity).map_err(E::custom)?;
+
+    while let Some((key, value)) = next_entry()? {
+        if map.len() == map.capacity() {
+            map.try_reserve(1).map_err(E::custom)?;
+        }
+        insert(map, key, value)?;
+        if map.len() > max_len {
+            return Err(E::invalid_length(map.len(), &AtMost(max_len)));
+        }
+    }
+
+    Ok(())
+}
+
+impl<K, V, S: Clone, Idx> Clone for IndexMapSeed<K, V, S, Idx> {
+    fn clone(&self) -> Self {
+        IndexMapSeed {
+            hash_builder: self.hash_builder.clone(),
+            capacity_limit: self.capacity_limit,
+            max_len: self.max_len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<K, V, S: fmt::Debug, Idx> fmt::Debug for IndexMapSeed<K, V, S, Idx> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IndexMapSeed")
+            .field("hash_builder", &self.hash_builder)
+            .field("capacity_limit", &self.capacity_limit)
+            .field("max_len", &self.max_len)
+            .finish()
+    }
+}
+
+impl<'de, K, V, S, Idx> DeserializeSeed<'de> for IndexMapSeed<K, V, S, Idx>
 where
     K: Deserialize<'de> + Eq + Hash,
     V: Deserialize<'de>,
-    S: Default + BuildHasher,
+    S: BuildHasher,
+    Idx: IndexType,
+{
+    type Value = IndexMap<K, V, S, Idx>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(IndexMapVisitor(self))
+    }
+}
+
+struct IndexMapVisitor<K, V, S, Idx>(IndexMapSeed<K, V, S, Idx>);
+
+impl<'de, K, V, S, Idx> Visitor<'de> for IndexMapVisitor<K, V, S, Idx>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: BuildHasher,
+    Idx: IndexType,
 {
-    type Value = IndexMap<K, V, S>;
+    type Value = IndexMap<K, V, S, Idx>;
 
     fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         write!(formatter, "a map")
@@ -43,37 +257,102 @@ where
     where
         A: MapAccess<'de>,
     {
-        let mut values =
-            IndexMap::with_capacity_and_hasher(map.size_hint().unwrap_or(0), S::default());
-
-        while let Some((key, value)) = map.next_entry()? {
-            values.insert(key, value);
-        }
-
-        Ok(values)
+        self.0.build(map.size_hint(), || map.next_entry())
     }
 }
 
-impl<'de, K, V, S> Deserialize<'de> for IndexMap<K, V, S>
+impl<'de, K, V, S, Idx> Deserialize<'de> for IndexMap<K, V, S, Idx>
 where
     K: Deserialize<'de> + Eq + Hash,
     V: Deserialize<'de>,
     S: Default + BuildHasher,
+    Idx: IndexType,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_map(IndexMapVisitor(PhantomData))
+        IndexMapSeed::new(S::default()).deserialize(deserializer)
+    }
+}
+
+/// Visitor to deserialize into an existing `IndexMap`
+struct IndexMapInPlaceVisitor<'a, K, V, S, Idx, A: Allocator>(&'a mut IndexMap<K, V, S, Idx, A>);
+
+impl<'de, K, V, S, Idx, A> Visitor<'de> for IndexMapInPlaceVisitor<'_, K, V, S, Idx, A>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a map")
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let capacity = cautious_capacity::<Bucket<K, V>>(map.size_hint());
+        extend_bounded(
+            self.0,
+            capacity,
+            usize::MAX,
+            || map.next_entry(),
+            |map, key, value| {
+                map.insert(key, value);
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Deserializes a map into an existing [`IndexMap`], using its hasher.
+///
+/// The key-value pairs are inserted like [`Extend`], so this doesn't need
+/// `S: Default`, and the map may already have some entries.
+///
+/// ```
+/// # use indexmap::IndexMap;
+/// use serde::de::{value::MapDeserializer, DeserializeSeed};
+/// use std::collections::hash_map::RandomState;
+///
+/// let mut map: IndexMap<_, _, RandomState> = IndexMap::with_hasher(RandomState::new());
+/// map.insert(1, 'a');
+/// let de = MapDeserializer::<_, serde::de::value::Error>::new([(2, 'b'), (1, 'c')].into_iter());
+/// map.deserialize(de).unwrap();
+/// assert!(map.into_iter().eq([(1, 'c'), (2, 'b')]));
+/// ```
+impl<'de, K, V, S, Idx, A> DeserializeSeed<'de> for &mut IndexMap<K, V, S, Idx, A>
+where
+    K: Deserialize<'de> + Eq + Hash,
+    V: Deserialize<'de>,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(IndexMapInPlaceVisitor(self))
     }
 }
 
-impl<'de, K, V, S, E> IntoDeserializer<'de, E> for IndexMap<K, V, S>
+impl<'de, K, V, S, E, Idx, A> IntoDeserializer<'de, E> for IndexMap<K, V, S, Idx, A>
 where
     K: IntoDeserializer<'de, E> + Eq + Hash,
     V: IntoDeserializer<'de, E>,
     S: BuildHasher,
     E: Error,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Deserializer = MapDeserializer<'de, <Self as IntoIterator>::IntoIter, E>;
 
@@ -84,9 +363,11 @@ where
 
 use crate::IndexSet;
 
-impl<T, S> Serialize for IndexSet<T, S>
+impl<T, S, Idx, A> Serialize for IndexSet<T, S, Idx, A>
 where
     T: Serialize,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn serialize<Se>(&self, serializer: Se) -> Result<Se::Ok, Se::Error>
     where
@@ -96,14 +377,84 @@ where
     }
 }
 
-struct IndexSetVisitor<T, S>(PhantomData<(T, S)>);
+/// A [`DeserializeSeed`] that creates an [`IndexSet`] with a given hasher.
+///
+/// The [`Deserialize`] implementation of `IndexSet` needs `S: Default` to
+/// create its hasher, which rules out hashers that are created with a key or
+/// seed, so this seed takes the hasher instance instead. It deserializes the
+/// same sequence format.
+///
+/// Like [`map::IndexMapSeed`][crate::map::IndexMapSeed], it can also bound
+/// the memory for untrusted input.
+pub struct IndexSetSeed<T, S, Idx = usize>(IndexMapSeed<T, (), S, Idx>);
+
+impl<T, S, Idx> IndexSetSeed<T, S, Idx> {
+    /// Create a seed for an `IndexSet` that will use the given hasher.
+    pub fn new(hash_builder: S) -> Self {
+        IndexSetSeed(IndexMapSeed::new(hash_builder))
+    }
+
+    /// Limit the capacity that is allocated up front to `limit` values.
+    ///
+    /// By default, the set preallocates as many values as the size hint of
+    /// the format, up to about 1 MiB. This doesn't limit how many values can
+    /// be deserialized, since the set still grows as usual past its initial
+    /// capacity.
+    pub fn with_capacity_limit(self, limit: usize) -> Self {
+        IndexSetSeed(self.0.with_capacity_limit(limit))
+    }
+
+    /// Fail to deserialize a set with more than `max_len` values.
+    ///
+    /// A size hint over the limit fails before anything is allocated, and
+    /// otherwise the set fails as soon as it grows past the limit, with an
+    /// [`invalid_length`][Error::invalid_length] error.
+    pub fn with_max_len(self, max_len: usize) -> Self {
+        IndexSetSeed(self.0.with_max_len(max_len))
+    }
+}
+
+impl<T, S: Clone, Idx> Clone for IndexSetSeed<T, S, Idx> {
+    fn clone(&self) -> Self {
+        IndexSetSeed(self.0.clone())
+    }
+}
+
+impl<T, S: fmt::Debug, Idx> fmt::Debug for IndexSetSeed<T, S, Idx> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IndexSetSeed")
+            .field("hash_builder", &self.0.hash_builder)
+            .field("capacity_limit", &self.0.capacity_limit)
+            .field("max_len", &self.0.max_len)
+            .finish()
+    }
+}
 
-impl<'de, T, S> Visitor<'de> for IndexSetVisitor<T, S>
+impl<'de, T, S, Idx> DeserializeSeed<'de> for IndexSetSeed<T, S, Idx>
 where
     T: Deserialize<'de> + Eq + Hash,
-    S: Default + BuildHasher,
+    S: BuildHasher,
+    Idx: IndexType,
+{
+    type Value = IndexSet<T, S, Idx>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(IndexSetVisitor(self))
+    }
+}
+
+struct IndexSetVisitor<T, S, Idx>(IndexSetSeed<T, S, Idx>);
+
+impl<'de, T, S, Idx> Visitor<'de> for IndexSetVisitor<T, S, Idx>
+where
+    T: Deserialize<'de> + Eq + Hash,
+    S: BuildHasher,
+    Idx: IndexType,
 {
-    type Value = IndexSet<T, S>;
+    type Value = IndexSet<T, S, Idx>;
 
     fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
         write!(formatter, "a set")
@@ -113,35 +464,90 @@ where
     where
         A: SeqAccess<'de>,
     {
-        let mut values =
-            IndexSet::with_capacity_and_hasher(seq.size_hint().unwrap_or(0), S::default());
-
-        while let Some(value) = seq.next_element()? {
-            values.insert(value);
-        }
-
-        Ok(values)
+        let size_hint = seq.size_hint();
+        let next_entry = || Ok(seq.next_element()?.map(|value| (value, ())));
+        let map = self.0 .0.build(size_hint, next_entry)?;
+        Ok(IndexSet { map })
     }
 }
 
-impl<'de, T, S> Deserialize<'de> for IndexSet<T, S>
+impl<'de, T, S, Idx> Deserialize<'de> for IndexSet<T, S, Idx>
 where
     T: Deserialize<'de> + Eq + Hash,
     S: Default + BuildHasher,
+    Idx: IndexType,
 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        deserializer.deserialize_seq(IndexSetVisitor(PhantomData))
+        IndexSetSeed::new(S::default()).deserialize(deserializer)
+    }
+}
+
+/// Visitor to deserialize into an existing `IndexSet`
+struct IndexSetInPlaceVisitor<'a, T, S, Idx, A: Allocator>(&'a mut IndexSet<T, S, Idx, A>);
+
+impl<'de, T, S, Idx, A> Visitor<'de> for IndexSetInPlaceVisitor<'_, T, S, Idx, A>
+where
+    T: Deserialize<'de> + Eq + Hash,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a set")
+    }
+
+    fn visit_seq<Sq>(self, mut seq: Sq) -> Result<Self::Value, Sq::Error>
+    where
+        Sq: SeqAccess<'de>,
+    {
+        let capacity = cautious_capacity::<Bucket<T, ()>>(seq.size_hint());
+        let next_entry = || Ok(seq.next_element()?.map(|value| (value, ())));
+        extend_bounded(
+            &mut self.0.map,
+            capacity,
+            usize::MAX,
+            next_entry,
+            |map, value, ()| {
+                map.insert(value, ());
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Deserializes a sequence into an existing [`IndexSet`], using its hasher.
+///
+/// The values are inserted like [`Extend`], so this doesn't need
+/// `S: Default`, and the set may already have some values.
+impl<'de, T, S, Idx, A> DeserializeSeed<'de> for &mut IndexSet<T, S, Idx, A>
+where
+    T: Deserialize<'de> + Eq + Hash,
+    S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(IndexSetInPlaceVisitor(self))
     }
 }
 
-impl<'de, T, S, E> IntoDeserializer<'de, E> for IndexSet<T, S>
+impl<'de, T, S, E, Idx, A> IntoDeserializer<'de, E> for IndexSet<T, S, Idx, A>
 where
     T: IntoDeserializer<'de, E> + Eq + Hash,
     S: BuildHasher,
     E: Error,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Deserializer = SeqDeserializer<<Self as IntoIterator>::IntoIter, E>;
 
@@ -1,16 +1,30 @@
 //! A hash set implemented using [`IndexMap`]
 
+mod entry;
 mod iter;
+mod mutable;
+pub mod raw_entry_v1;
 mod slice;
 
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde_strict;
+
 #[cfg(test)]
 mod tests;
 
+pub use self::entry::{Entry, OccupiedEntry, VacantEntry};
 pub use self::iter::{
     Difference, Drain, Intersection, IntoIter, Iter, Splice, SymmetricDifference, Union,
 };
+pub use self::mutable::MutableValues;
+pub use self::raw_entry_v1::RawEntryApiV1;
 pub use self::slice::Slice;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub use crate::serde::IndexSetSeed;
 
+use crate::allocator::{self, Allocator, Global};
 #[cfg(feature = "rayon")]
 pub use crate::rayon::set as rayon;
 use crate::TryReserveError;
@@ -18,15 +32,18 @@ use crate::TryReserveError;
 #[cfg(feature = "std")]
 use std::collections::hash_map::RandomState;
 
-use crate::util::try_simplify_range;
+use crate::util::{hash_unordered, try_simplify_range};
 use alloc::boxed::Box;
-use alloc::vec::Vec;
+use core::borrow::Borrow;
 use core::cmp::Ordering;
 use core::fmt;
-use core::hash::{BuildHasher, Hash};
-use core::ops::{BitAnd, BitOr, BitXor, Index, RangeBounds, Sub};
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Index, RangeBounds, Sub,
+    SubAssign,
+};
 
-use super::{Entries, Equivalent, IndexMap};
+use super::{Entries, Equivalent, IndexMap, IndexType};
 
 type Bucket<T> = super::Bucket<T, ()>;
 
@@ -60,6 +77,10 @@ type Bucket<T> = super::Bucket<T, ()>;
 /// `0..self.len()`. For example, the method `.get_full` looks up the index for
 /// a value, and the method `.get_index` looks up the value by index.
 ///
+/// The hash table stores these indices as the `Idx` type, which is `usize` by default.
+/// A smaller [`IndexType`] like `u32` reduces the memory used by the table,
+/// but also limits how many values the set can hold.
+///
 /// # Complexity
 ///
 /// Internally, `IndexSet<T, S>` just holds an [`IndexMap<T, (), S>`](IndexMap). Thus the complexity
@@ -79,18 +100,20 @@ type Bucket<T> = super::Bucket<T, ()>;
 /// assert!(!letters.contains(&'y'));
 /// ```
 #[cfg(feature = "std")]
-pub struct IndexSet<T, S = RandomState> {
-    pub(crate) map: IndexMap<T, (), S>,
+pub struct IndexSet<T, S = RandomState, Idx = usize, A: Allocator = Global> {
+    pub(crate) map: IndexMap<T, (), S, Idx, A>,
 }
 #[cfg(not(feature = "std"))]
-pub struct IndexSet<T, S> {
-    pub(crate) map: IndexMap<T, (), S>,
+pub struct IndexSet<T, S, Idx = usize, A: Allocator = Global> {
+    pub(crate) map: IndexMap<T, (), S, Idx, A>,
 }
 
-impl<T, S> Clone for IndexSet<T, S>
+impl<T, S, Idx, A> Clone for IndexSet<T, S, Idx, A>
 where
     T: Clone,
     S: Clone,
+    Idx: IndexType,
+    A: Allocator + Clone,
 {
     fn clone(&self) -> Self {
         IndexSet {
@@ -103,11 +126,12 @@ where
     }
 }
 
-impl<T, S> Entries for IndexSet<T, S> {
+impl<T, S, Idx: IndexType, A: Allocator> Entries for IndexSet<T, S, Idx, A> {
     type Entry = Bucket<T>;
+    type Alloc = A;
 
     #[inline]
-    fn into_entries(self) -> Vec<Self::Entry> {
+    fn into_entries(self) -> allocator::Vec<Self::Entry, A> {
         self.map.into_entries()
     }
 
@@ -129,9 +153,11 @@ impl<T, S> Entries for IndexSet<T, S> {
     }
 }
 
-impl<T, S> fmt::Debug for IndexSet<T, S>
+impl<T, S, Idx, A> fmt::Debug for IndexSet<T, S, Idx, A>
 where
     T: fmt::Debug,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if cfg!(not(feature = "test_debug")) {
@@ -170,9 +196,7 @@ impl<T, S> IndexSet<T, S> {
     ///
     /// Computes in **O(n)** time.
     pub fn with_capacity_and_hasher(n: usize, hash_builder: S) -> Self {
-        IndexSet {
-            map: IndexMap::with_capacity_and_hasher(n, hash_builder),
-        }
+        Self::with_capacity_and_hasher_idx(n, hash_builder)
     }
 
     /// Create a new set with `hash_builder`.
@@ -180,11 +204,90 @@ impl<T, S> IndexSet<T, S> {
     /// This function is `const`, so it
     /// can be called in `static` contexts.
     pub const fn with_hasher(hash_builder: S) -> Self {
+        Self::with_hasher_idx(hash_builder)
+    }
+}
+
+impl<T, S, Idx: IndexType> IndexSet<T, S, Idx> {
+    /// Create a new set with capacity for `n` elements, which are indexed by
+    /// `Idx`. (Does not allocate if `n` is zero.)
+    ///
+    /// This is [`with_capacity_and_hasher`][IndexSet::with_capacity_and_hasher]
+    /// for any [`IndexType`], which has to be named or inferred from the context.
+    ///
+    /// Computes in **O(n)** time.
+    pub fn with_capacity_and_hasher_idx(n: usize, hash_builder: S) -> Self {
+        IndexSet {
+            map: IndexMap::with_capacity_and_hasher_idx(n, hash_builder),
+        }
+    }
+
+    /// Create a new set with `hash_builder`, whose elements are indexed by `Idx`.
+    ///
+    /// This is [`with_hasher`][IndexSet::with_hasher] for any [`IndexType`],
+    /// which has to be named or inferred from the context.
+    ///
+    /// This function is `const`, so it
+    /// can be called in `static` contexts.
+    pub const fn with_hasher_idx(hash_builder: S) -> Self {
         IndexSet {
-            map: IndexMap::with_hasher(hash_builder),
+            map: IndexMap::with_hasher_idx(hash_builder),
         }
     }
+}
 
+#[cfg(all(feature = "std", feature = "allocator-api2"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "std", feature = "allocator-api2"))))]
+impl<T, A: Allocator + Clone> IndexSet<T, RandomState, usize, A> {
+    /// Create a new set that allocates its storage in `alloc`. (Does not allocate.)
+    ///
+    /// ```
+    /// use allocator_api2::alloc::Global;
+    /// use indexmap::IndexSet;
+    ///
+    /// let mut set = IndexSet::new_in(Global);
+    /// set.insert("a");
+    /// assert!(set.contains("a"));
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        IndexSet {
+            map: IndexMap::new_in(alloc),
+        }
+    }
+
+    /// Create a new set with capacity for `n` elements, allocated in `alloc`.
+    /// (Does not allocate if `n` is zero.)
+    ///
+    /// Computes in **O(n)** time.
+    pub fn with_capacity_in(n: usize, alloc: A) -> Self {
+        IndexSet {
+            map: IndexMap::with_capacity_in(n, alloc),
+        }
+    }
+}
+
+#[cfg(feature = "allocator-api2")]
+#[cfg_attr(docsrs, doc(cfg(feature = "allocator-api2")))]
+impl<T, S, Idx: IndexType, A: Allocator + Clone> IndexSet<T, S, Idx, A> {
+    /// Create a new set with capacity for `n` elements and `hash_builder`,
+    /// allocated in `alloc`. (Does not allocate if `n` is zero.)
+    ///
+    /// Computes in **O(n)** time.
+    pub fn with_capacity_and_hasher_in(n: usize, hash_builder: S, alloc: A) -> Self {
+        IndexSet {
+            map: IndexMap::with_capacity_and_hasher_in(n, hash_builder, alloc),
+        }
+    }
+
+    /// Create a new set with `hash_builder`, allocated in `alloc`. (Does not allocate.)
+    pub fn with_hasher_in(hash_builder: S, alloc: A) -> Self {
+        IndexSet {
+            map: IndexMap::with_hasher_in(hash_builder, alloc),
+        }
+    }
+}
+
+impl<T, S, Idx: IndexType, A: Allocator> IndexSet<T, S, Idx, A> {
     /// Return the number of elements the set can hold without reallocating.
     ///
     /// This number is a lower bound; the set might be able to hold more,
@@ -200,6 +303,13 @@ impl<T, S> IndexSet<T, S> {
         self.map.hasher()
     }
 
+    /// Return a reference to the allocator of the set's storage.
+    #[cfg(feature = "allocator-api2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "allocator-api2")))]
+    pub fn allocator(&self) -> &A {
+        self.map.allocator()
+    }
+
     /// Return the number of elements in the set.
     ///
     /// Computes in **O(1)** time.
@@ -246,7 +356,7 @@ impl<T, S> IndexSet<T, S> {
     ///
     /// ***Panics*** if the starting point is greater than the end point or if
     /// the end point is greater than the length of the set.
-    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T>
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A>
     where
         R: RangeBounds<usize>,
     {
@@ -263,6 +373,7 @@ impl<T, S> IndexSet<T, S> {
     pub fn split_off(&mut self, at: usize) -> Self
     where
         S: Clone,
+        A: Clone,
     {
         Self {
             map: self.map.split_off(at),
@@ -322,10 +433,12 @@ impl<T, S> IndexSet<T, S> {
     }
 }
 
-impl<T, S> IndexSet<T, S>
+impl<T, S, Idx, A> IndexSet<T, S, Idx, A>
 where
     T: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Insert the value into the set.
     ///
@@ -353,6 +466,105 @@ where
         (index, existing.is_none())
     }
 
+    /// Insert the value into the set at its ordered position among sorted values.
+    ///
+    /// This is equivalent to finding the position with
+    /// [`binary_search`][Self::binary_search], and if needed calling
+    /// [`insert_before`][Self::insert_before] for a new value.
+    ///
+    /// If the sorted item is found in the set, it returns the index of that
+    /// existing item and `false`, without any change. Otherwise, it inserts the
+    /// new item and returns its sorted index and `true`.
+    ///
+    /// If the existing items are **not** already sorted, then the binary search
+    /// may fail to find a matching value, and the value of the returned `index`
+    /// is unspecified. An equivalent item that is already in the set is moved to
+    /// that position rather than duplicated, so the set stays consistent either way.
+    ///
+    /// Computes in **O(n)** time (average). Instead of repeating calls to
+    /// `insert_sorted`, it may be faster to call batched [`insert`][Self::insert]
+    /// or [`extend`][Self::extend] and only call [`sort`][Self::sort] or
+    /// [`sort_unstable`][Self::sort_unstable] once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexSet;
+    /// let mut set = IndexSet::new();
+    ///
+    /// assert_eq!(set.insert_sorted(30), (0, true));
+    /// assert_eq!(set.insert_sorted(10), (0, true));
+    /// assert_eq!(set.insert_sorted(20), (1, true));
+    /// assert_eq!(set.insert_sorted(30), (2, false));
+    ///
+    /// assert!(set.iter().eq(&[10, 20, 30]));
+    /// ```
+    pub fn insert_sorted(&mut self, value: T) -> (usize, bool)
+    where
+        T: Ord,
+    {
+        let (index, existing) = self.map.insert_sorted(value, ());
+        (index, existing.is_none())
+    }
+
+    /// Insert the value into the set at the given index.
+    ///
+    /// If an equivalent item already exists in the set, it returns
+    /// `false` leaving the original value in the set, but moved to the given
+    /// index. Note that existing values **cannot** be moved to `index == set.len()`!
+    /// (See [`insert_before`](Self::insert_before) for different behavior here.)
+    ///
+    /// Otherwise, it inserts the new value at the given index and returns `true`.
+    ///
+    /// ***Panics*** if `index` is out of bounds.
+    /// Valid indices are `0..set.len()` (exclusive) when moving an existing value, or
+    /// `0..=set.len()` (inclusive) when inserting a new value.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn shift_insert(&mut self, index: usize, value: T) -> bool {
+        self.map.shift_insert(index, value, ()).is_none()
+    }
+
+    /// Insert the value into the set before the value at the given index, or at the end.
+    ///
+    /// If an equivalent item already exists in the set, it returns `false` leaving the
+    /// original value in the set, but moved to the new position. The returned index
+    /// will either be the given index or one less, depending on how the value moved.
+    /// (See [`shift_insert`](Self::shift_insert) for different behavior here.)
+    ///
+    /// Otherwise, it inserts the new value exactly at the given index and returns `true`.
+    ///
+    /// ***Panics*** if `index` is out of bounds.
+    /// Valid indices are `0..=set.len()` (inclusive).
+    ///
+    /// Computes in **O(n)** time (average).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexSet;
+    /// let mut set: IndexSet<char> = ('a'..='z').collect();
+    ///
+    /// // The new value '*' goes exactly at the given index.
+    /// assert_eq!(set.get_index_of(&'*'), None);
+    /// assert_eq!(set.insert_before(10, '*'), (10, true));
+    /// assert_eq!(set.get_index_of(&'*'), Some(10));
+    ///
+    /// // Moving the value 'a' up will shift others down, so this moves *before* 10 to index 9.
+    /// assert_eq!(set.insert_before(10, 'a'), (9, false));
+    /// assert_eq!(set.get_index_of(&'a'), Some(9));
+    /// assert_eq!(set.get_index_of(&'*'), Some(10));
+    ///
+    /// // Moving the value 'z' down will shift others up, so this moves to exactly 10.
+    /// assert_eq!(set.insert_before(10, 'z'), (10, false));
+    /// assert_eq!(set.get_index_of(&'z'), Some(10));
+    /// assert_eq!(set.get_index_of(&'*'), Some(11));
+    /// ```
+    pub fn insert_before(&mut self, index: usize, value: T) -> (usize, bool) {
+        let (index, existing) = self.map.insert_before(index, value, ());
+        (index, existing.is_none())
+    }
+
     /// Adds a value to the set, replacing the existing value, if any, that is
     /// equal to the given one, without altering its insertion order. Returns
     /// the replaced value.
@@ -375,12 +587,25 @@ where
         }
     }
 
+    /// Get the given value's corresponding entry in the set for insertion and/or
+    /// in-place manipulation.
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn entry(&mut self, value: T) -> Entry<'_, T, Idx, A> {
+        Entry::new(self.map.entry(value))
+    }
+
     /// Return an iterator over the values that are in `self` but not `other`.
     ///
     /// Values are produced in the same order that they appear in `self`.
-    pub fn difference<'a, S2>(&'a self, other: &'a IndexSet<T, S2>) -> Difference<'a, T, S2>
+    pub fn difference<'a, S2, Idx2, A2>(
+        &'a self,
+        other: &'a IndexSet<T, S2, Idx2, A2>,
+    ) -> Difference<'a, T, S2, Idx2, A2>
     where
         S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
     {
         Difference::new(self, other)
     }
@@ -390,12 +615,14 @@ where
     ///
     /// Values from `self` are produced in their original order, followed by
     /// values from `other` in their original order.
-    pub fn symmetric_difference<'a, S2>(
+    pub fn symmetric_difference<'a, S2, Idx2, A2>(
         &'a self,
-        other: &'a IndexSet<T, S2>,
-    ) -> SymmetricDifference<'a, T, S, S2>
+        other: &'a IndexSet<T, S2, Idx2, A2>,
+    ) -> SymmetricDifference<'a, T, S, S2, Idx, Idx2, A, A2>
     where
         S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
     {
         SymmetricDifference::new(self, other)
     }
@@ -403,9 +630,14 @@ where
     /// Return an iterator over the values that are in both `self` and `other`.
     ///
     /// Values are produced in the same order that they appear in `self`.
-    pub fn intersection<'a, S2>(&'a self, other: &'a IndexSet<T, S2>) -> Intersection<'a, T, S2>
+    pub fn intersection<'a, S2, Idx2, A2>(
+        &'a self,
+        other: &'a IndexSet<T, S2, Idx2, A2>,
+    ) -> Intersection<'a, T, S2, Idx2, A2>
     where
         S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
     {
         Intersection::new(self, other)
     }
@@ -414,13 +646,143 @@ where
     ///
     /// Values from `self` are produced in their original order, followed by
     /// values that are unique to `other` in their original order.
-    pub fn union<'a, S2>(&'a self, other: &'a IndexSet<T, S2>) -> Union<'a, T, S>
+    pub fn union<'a, S2, Idx2, A2>(
+        &'a self,
+        other: &'a IndexSet<T, S2, Idx2, A2>,
+    ) -> Union<'a, T, S, Idx, A>
     where
         S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
     {
         Union::new(self, other)
     }
 
+    /// Add all of the values of `other` to `self`, in place.
+    ///
+    /// Values of `self` keep their order, followed by the values that are
+    /// unique to `other` in their original order, just like [`union`][Self::union].
+    ///
+    /// Computes in **O(m)** time (amortized average), where *m* is the length of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexSet;
+    ///
+    /// let mut a = IndexSet::from([3, 1, 4]);
+    /// a.union_with(&IndexSet::from([5, 4, 2, 1]));
+    /// assert!(a.iter().eq(&[3, 1, 4, 5, 2]));
+    /// ```
+    pub fn union_with<S2, Idx2, A2>(&mut self, other: &IndexSet<T, S2, Idx2, A2>)
+    where
+        T: Clone,
+        Idx2: IndexType,
+        A2: Allocator,
+    {
+        for value in other {
+            let hash = self.map.hash(value);
+            if self.map.core.get_index_of(hash, value).is_none() {
+                self.map.core.insert_unique(hash, value.clone(), ());
+            }
+        }
+    }
+
+    /// Remove the values of `self` that are not in `other`, in place.
+    ///
+    /// The remaining values keep their order, just like [`intersection`][Self::intersection].
+    ///
+    /// Computes in **O(n)** time (average).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexSet;
+    ///
+    /// let mut a = IndexSet::from([3, 1, 4, 5]);
+    /// a.intersect_with(&IndexSet::from([5, 4, 2, 1]));
+    /// assert!(a.iter().eq(&[1, 4, 5]));
+    /// ```
+    pub fn intersect_with<S2, Idx2, A2>(&mut self, other: &IndexSet<T, S2, Idx2, A2>)
+    where
+        S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
+    {
+        (self.map.core).retain_in_order_incremental(|value, ()| other.contains(value));
+    }
+
+    /// Remove the values of `self` that are also in `other`, in place.
+    ///
+    /// The remaining values keep their order, just like [`difference`][Self::difference].
+    ///
+    /// Computes in **O(n)** time (average).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexSet;
+    ///
+    /// let mut a = IndexSet::from([3, 1, 4, 5]);
+    /// a.difference_with(&IndexSet::from([5, 4, 2]));
+    /// assert!(a.iter().eq(&[3, 1]));
+    /// ```
+    pub fn difference_with<S2, Idx2, A2>(&mut self, other: &IndexSet<T, S2, Idx2, A2>)
+    where
+        S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
+    {
+        if !other.is_empty() {
+            (self.map.core).retain_in_order_incremental(|value, ()| !other.contains(value));
+        }
+    }
+
+    /// Keep the values that are in `self` or `other` but not in both, in place.
+    ///
+    /// Values of `self` keep their order, followed by the values that are
+    /// unique to `other` in their original order, just like
+    /// [`symmetric_difference`][Self::symmetric_difference].
+    ///
+    /// Computes in **O(n + m)** time (average).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexSet;
+    ///
+    /// let mut a = IndexSet::from([3, 1, 4, 5]);
+    /// a.symmetric_difference_with(&IndexSet::from([5, 4, 2, 6]));
+    /// assert!(a.iter().eq(&[3, 1, 2, 6]));
+    /// ```
+    pub fn symmetric_difference_with<S2, Idx2, A2>(&mut self, other: &IndexSet<T, S2, Idx2, A2>)
+    where
+        T: Clone,
+        S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
+    {
+        let len = self.len();
+        let mut common = false;
+        for value in other {
+            let hash = self.map.hash(value);
+            if self.map.core.get_index_of(hash, value).is_some() {
+                common = true;
+            } else {
+                self.map.core.insert_unique(hash, value.clone(), ());
+            }
+        }
+        if common {
+            // Only the original values can be in both sets.
+            let mut index = 0;
+            self.map.core.retain_in_order_incremental(|value, ()| {
+                let keep = index >= len || !other.contains(value);
+                index += 1;
+                keep
+            });
+        }
+    }
+
     /// Creates a splicing iterator that replaces the specified range in the set
     /// with the given `replace_with` iterator and yields the removed items.
     /// `replace_with` does not need to be the same length as `range`.
@@ -450,18 +812,25 @@ where
     /// assert!(set.into_iter().eq([0, 1, 5, 3, 2, 4]));
     /// assert_eq!(removed, &[2, 3]);
     /// ```
-    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, T, S>
+    pub fn splice<R, I>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> Splice<'_, I::IntoIter, T, S, Idx, A>
     where
         R: RangeBounds<usize>,
         I: IntoIterator<Item = T>,
+        A: Clone,
     {
         Splice::new(self, range, replace_with.into_iter())
     }
 }
 
-impl<T, S> IndexSet<T, S>
+impl<T, S, Idx, A> IndexSet<T, S, Idx, A>
 where
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Return `true` if an equivalent to `value` exists in the set.
     ///
@@ -519,7 +888,7 @@ where
 
     /// Remove the value from the set, and return `true` if it was present.
     ///
-    /// Like [`Vec::swap_remove`], the value is removed by swapping it with the
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the value is removed by swapping it with the
     /// last element of the set and popping it off. **This perturbs
     /// the position of what used to be the last element!**
     ///
@@ -535,7 +904,7 @@ where
 
     /// Remove the value from the set, and return `true` if it was present.
     ///
-    /// Like [`Vec::remove`], the value is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the value is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -568,7 +937,7 @@ where
     /// Removes and returns the value in the set, if any, that is equal to the
     /// given one.
     ///
-    /// Like [`Vec::swap_remove`], the value is removed by swapping it with the
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the value is removed by swapping it with the
     /// last element of the set and popping it off. **This perturbs
     /// the position of what used to be the last element!**
     ///
@@ -585,7 +954,7 @@ where
     /// Removes and returns the value in the set, if any, that is equal to the
     /// given one.
     ///
-    /// Like [`Vec::remove`], the value is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the value is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -601,7 +970,7 @@ where
 
     /// Remove the value from the set return it and the index it had.
     ///
-    /// Like [`Vec::swap_remove`], the value is removed by swapping it with the
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the value is removed by swapping it with the
     /// last element of the set and popping it off. **This perturbs
     /// the position of what used to be the last element!**
     ///
@@ -615,7 +984,7 @@ where
 
     /// Remove the value from the set return it and the index it had.
     ///
-    /// Like [`Vec::remove`], the value is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the value is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -628,7 +997,7 @@ where
     }
 }
 
-impl<T, S> IndexSet<T, S> {
+impl<T, S, Idx: IndexType, A: Allocator> IndexSet<T, S, Idx, A> {
     /// Remove the last value
     ///
     /// This preserves the order of the remaining elements.
@@ -672,11 +1041,29 @@ impl<T, S> IndexSet<T, S> {
         self.map.sort_by(move |a, _, b, _| cmp(a, b));
     }
 
+    /// Sort the set’s values in the given index range in place using the
+    /// comparison function `cmp`, and leave the rest of the set as it is.
+    ///
+    /// See [`IndexMap::sort_range_by`] for more details.
+    ///
+    /// ***Panics*** if the starting point is greater than the end point or if
+    /// the end point is greater than the length of the set.
+    ///
+    /// Computes in **O(m log m)** time (average) and **O(m)** space, where *m*
+    /// is the length of the range. The sort is stable.
+    pub fn sort_range_by<R, F>(&mut self, range: R, mut cmp: F)
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&T, &T) -> Ordering,
+    {
+        self.map.sort_range_by(range, move |a, _, b, _| cmp(a, b));
+    }
+
     /// Sort the values of the set and return a by-value iterator of
     /// the values with the result.
     ///
     /// The sort is stable.
-    pub fn sorted_by<F>(self, mut cmp: F) -> IntoIter<T>
+    pub fn sorted_by<F>(self, mut cmp: F) -> IntoIter<T, A>
     where
         F: FnMut(&T, &T) -> Ordering,
     {
@@ -707,7 +1094,7 @@ impl<T, S> IndexSet<T, S> {
 
     /// Sort the values of the set and return a by-value iterator of
     /// the values with the result.
-    pub fn sorted_unstable_by<F>(self, mut cmp: F) -> IntoIter<T>
+    pub fn sorted_unstable_by<F>(self, mut cmp: F) -> IntoIter<T, A>
     where
         F: FnMut(&T, &T) -> Ordering,
     {
@@ -791,6 +1178,35 @@ impl<T, S> IndexSet<T, S> {
         self.as_slice().partition_point(pred)
     }
 
+    /// Returns a slice of the values of a sorted set that are within `range`.
+    ///
+    /// The bounds are found with [`partition_point`][Self::partition_point], so this is
+    /// like [`BTreeSet::range`][alloc::collections::BTreeSet::range] for a set that is
+    /// sorted, e.g. after [`sort`][Self::sort]. If the values are **not** sorted, the
+    /// returned slice is unspecified. A range whose start is after its end returns an
+    /// empty slice.
+    ///
+    /// Computes in **O(log(n))** time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::IndexSet;
+    /// let mut set: IndexSet<u32> = [8, 3, 5, 1, 13, 2].into();
+    /// set.sort();
+    ///
+    /// assert!(set.range(2..8).iter().eq(&[2, 3, 5]));
+    /// assert!(set.range(5..).iter().eq(&[5, 8, 13]));
+    /// ```
+    pub fn range<Q, R>(&self, range: R) -> &Slice<T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        self.as_slice().range(range)
+    }
+
     /// Reverses the order of the set’s values in place.
     ///
     /// Computes in **O(n)** time and **O(1)** space.
@@ -804,12 +1220,27 @@ impl<T, S> IndexSet<T, S> {
     pub fn as_slice(&self) -> &Slice<T> {
         Slice::from_slice(self.as_entries())
     }
+}
 
+impl<T, S, Idx: IndexType> IndexSet<T, S, Idx> {
     /// Converts into a boxed slice of all the values in the set.
     ///
     /// Note that this will drop the inner hash table and any excess capacity.
     pub fn into_boxed_slice(self) -> Box<Slice<T>> {
-        Slice::from_boxed(self.into_entries().into_boxed_slice())
+        let entries = allocator::into_std_vec(self.into_entries());
+        Slice::from_boxed(entries.into_boxed_slice())
+    }
+}
+
+impl<T, S, Idx: IndexType, A: Allocator> IndexSet<T, S, Idx, A> {
+    /// Converts into a boxed slice of all the values in the set,
+    /// keeping them in the set's allocator.
+    ///
+    /// Note that this will drop the inner hash table and any excess capacity.
+    #[cfg(feature = "allocator-api2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "allocator-api2")))]
+    pub fn into_boxed_slice_in(self) -> allocator_api2::boxed::Box<Slice<T>, A> {
+        Slice::from_boxed_in(self.into_entries().into_boxed_slice())
     }
 
     /// Get a value by index
@@ -850,7 +1281,7 @@ impl<T, S> IndexSet<T, S> {
     ///
     /// Valid indices are *0 <= index < self.len()*
     ///
-    /// Like [`Vec::swap_remove`], the value is removed by swapping it with the
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the value is removed by swapping it with the
     /// last element of the set and popping it off. **This perturbs
     /// the position of what used to be the last element!**
     ///
@@ -863,7 +1294,7 @@ impl<T, S> IndexSet<T, S> {
     ///
     /// Valid indices are *0 <= index < self.len()*
     ///
-    /// Like [`Vec::remove`], the value is removed by shifting all of the
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the value is removed by shifting all of the
     /// elements that follow it, preserving their relative order.
     /// **This perturbs the index of all of those elements!**
     ///
@@ -891,6 +1322,69 @@ impl<T, S> IndexSet<T, S> {
     pub fn swap_indices(&mut self, a: usize, b: usize) {
         self.map.swap_indices(a, b)
     }
+
+    /// Rotates the values in the given index range, so that the value at
+    /// `range.start + k` becomes the first of the range, and the first `k`
+    /// values move to the end of the range.
+    ///
+    /// See [`IndexMap::rotate_left`] for more details.
+    ///
+    /// ***Panics*** if the range is out of bounds, or if `k` is greater than
+    /// the length of the range.
+    ///
+    /// Computes in **O(m)** time (average), where *m* is the length of the range.
+    pub fn rotate_left<R>(&mut self, range: R, k: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        self.map.rotate_left(range, k)
+    }
+
+    /// Rotates the values in the given index range, so that the last `k`
+    /// values of the range move to its start.
+    ///
+    /// See [`IndexMap::rotate_right`] for more details.
+    ///
+    /// ***Panics*** if the range is out of bounds, or if `k` is greater than
+    /// the length of the range.
+    ///
+    /// Computes in **O(m)** time (average), where *m* is the length of the range.
+    pub fn rotate_right<R>(&mut self, range: R, k: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        self.map.rotate_right(range, k)
+    }
+
+    /// Reverses the order of the values in the given index range.
+    ///
+    /// ***Panics*** if the range is out of bounds.
+    ///
+    /// Computes in **O(m)** time (average), where *m* is the length of the range.
+    pub fn reverse_range<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        self.map.reverse_range(range)
+    }
+
+    /// Moves the values in the given index range as a block, so that the
+    /// first of them ends up at index `to`, by shifting the other values
+    /// in-between.
+    ///
+    /// See [`IndexMap::move_range`] for more details.
+    ///
+    /// ***Panics*** if the range is out of bounds, or if `to` is greater than
+    /// the length of the set minus the length of the range.
+    ///
+    /// Computes in **O(m)** time (average), where *m* is the distance between
+    /// `to` and the start of the range plus the length of the range.
+    pub fn move_range<R>(&mut self, range: R, to: usize)
+    where
+        R: RangeBounds<usize>,
+    {
+        self.map.move_range(range, to)
+    }
 }
 
 /// Access [`IndexSet`] values at indexed positions.
@@ -921,7 +1415,7 @@ impl<T, S> IndexSet<T, S> {
 /// set.insert("foo");
 /// println!("{:?}", set[10]); // panics!
 /// ```
-impl<T, S> Index<usize> for IndexSet<T, S> {
+impl<T, S, Idx: IndexType, A: Allocator> Index<usize> for IndexSet<T, S, Idx, A> {
     type Output = T;
 
     /// Returns a reference to the value at the supplied `index`.
@@ -933,10 +1427,11 @@ impl<T, S> Index<usize> for IndexSet<T, S> {
     }
 }
 
-impl<T, S> FromIterator<T> for IndexSet<T, S>
+impl<T, S, Idx> FromIterator<T> for IndexSet<T, S, Idx>
 where
     T: Hash + Eq,
     S: BuildHasher + Default,
+    Idx: IndexType,
 {
     fn from_iter<I: IntoIterator<Item = T>>(iterable: I) -> Self {
         let iter = iterable.into_iter().map(|x| (x, ()));
@@ -966,10 +1461,12 @@ where
     }
 }
 
-impl<T, S> Extend<T> for IndexSet<T, S>
+impl<T, S, Idx, A> Extend<T> for IndexSet<T, S, Idx, A>
 where
     T: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
         let iter = iterable.into_iter().map(|x| (x, ()));
@@ -977,10 +1474,12 @@ where
     }
 }
 
-impl<'a, T, S> Extend<&'a T> for IndexSet<T, S>
+impl<'a, T, S, Idx, A> Extend<&'a T> for IndexSet<T, S, Idx, A>
 where
     T: Hash + Eq + Copy + 'a,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iterable: I) {
         let iter = iterable.into_iter().copied();
@@ -988,9 +1487,10 @@ where
     }
 }
 
-impl<T, S> Default for IndexSet<T, S>
+impl<T, S, Idx> Default for IndexSet<T, S, Idx>
 where
     S: Default,
+    Idx: IndexType,
 {
     /// Return an empty [`IndexSet`]
     fn default() -> Self {
@@ -1000,33 +1500,60 @@ where
     }
 }
 
-impl<T, S1, S2> PartialEq<IndexSet<T, S2>> for IndexSet<T, S1>
+impl<T, S1, S2, Idx1, Idx2, A1, A2> PartialEq<IndexSet<T, S2, Idx2, A2>>
+    for IndexSet<T, S1, Idx1, A1>
 where
     T: Hash + Eq,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
-    fn eq(&self, other: &IndexSet<T, S2>) -> bool {
+    fn eq(&self, other: &IndexSet<T, S2, Idx2, A2>) -> bool {
         self.len() == other.len() && self.is_subset(other)
     }
 }
 
-impl<T, S> Eq for IndexSet<T, S>
+impl<T, S, Idx, A> Eq for IndexSet<T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
+{
+}
+
+impl<T, S, Idx, A> Hash for IndexSet<T, S, Idx, A>
+where
+    T: Hash,
+    Idx: IndexType,
+    A: Allocator,
 {
+    /// Hashes the values without regard for their order, consistent with the
+    /// set's [`PartialEq`].
+    ///
+    /// Use [`Ordered`][crate::Ordered] to hash the set as a sequence instead.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        hash_unordered(self.iter(), state);
+    }
 }
 
-impl<T, S> IndexSet<T, S>
+impl<T, S, Idx, A> IndexSet<T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     /// Returns `true` if `self` has no elements in common with `other`.
-    pub fn is_disjoint<S2>(&self, other: &IndexSet<T, S2>) -> bool
+    pub fn is_disjoint<S2, Idx2, A2>(&self, other: &IndexSet<T, S2, Idx2, A2>) -> bool
     where
         S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
     {
         if self.len() <= other.len() {
             self.iter().all(move |value| !other.contains(value))
@@ -1036,84 +1563,177 @@ where
     }
 
     /// Returns `true` if all elements of `self` are contained in `other`.
-    pub fn is_subset<S2>(&self, other: &IndexSet<T, S2>) -> bool
+    pub fn is_subset<S2, Idx2, A2>(&self, other: &IndexSet<T, S2, Idx2, A2>) -> bool
     where
         S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
     {
         self.len() <= other.len() && self.iter().all(move |value| other.contains(value))
     }
 
     /// Returns `true` if all elements of `other` are contained in `self`.
-    pub fn is_superset<S2>(&self, other: &IndexSet<T, S2>) -> bool
+    pub fn is_superset<S2, Idx2, A2>(&self, other: &IndexSet<T, S2, Idx2, A2>) -> bool
     where
         S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
     {
         other.is_subset(self)
     }
 }
 
-impl<T, S1, S2> BitAnd<&IndexSet<T, S2>> for &IndexSet<T, S1>
+impl<T, S1, S2, Idx1, Idx2, A2> BitAnd<&IndexSet<T, S2, Idx2, A2>> for &IndexSet<T, S1, Idx1>
 where
     T: Eq + Hash + Clone,
     S1: BuildHasher + Default,
     S2: BuildHasher,
+    Idx1: IndexType,
+    Idx2: IndexType,
+    A2: Allocator,
 {
-    type Output = IndexSet<T, S1>;
+    type Output = IndexSet<T, S1, Idx1>;
 
     /// Returns the set intersection, cloned into a new set.
     ///
     /// Values are collected in the same order that they appear in `self`.
-    fn bitand(self, other: &IndexSet<T, S2>) -> Self::Output {
+    fn bitand(self, other: &IndexSet<T, S2, Idx2, A2>) -> Self::Output {
         self.intersection(other).cloned().collect()
     }
 }
 
-impl<T, S1, S2> BitOr<&IndexSet<T, S2>> for &IndexSet<T, S1>
+impl<T, S1, S2, Idx1, Idx2, A2> BitOr<&IndexSet<T, S2, Idx2, A2>> for &IndexSet<T, S1, Idx1>
 where
     T: Eq + Hash + Clone,
     S1: BuildHasher + Default,
     S2: BuildHasher,
+    Idx1: IndexType,
+    Idx2: IndexType,
+    A2: Allocator,
 {
-    type Output = IndexSet<T, S1>;
+    type Output = IndexSet<T, S1, Idx1>;
 
     /// Returns the set union, cloned into a new set.
     ///
     /// Values from `self` are collected in their original order, followed by
     /// values that are unique to `other` in their original order.
-    fn bitor(self, other: &IndexSet<T, S2>) -> Self::Output {
+    fn bitor(self, other: &IndexSet<T, S2, Idx2, A2>) -> Self::Output {
         self.union(other).cloned().collect()
     }
 }
 
-impl<T, S1, S2> BitXor<&IndexSet<T, S2>> for &IndexSet<T, S1>
+impl<T, S1, S2, Idx1, Idx2, A2> BitXor<&IndexSet<T, S2, Idx2, A2>> for &IndexSet<T, S1, Idx1>
 where
     T: Eq + Hash + Clone,
     S1: BuildHasher + Default,
     S2: BuildHasher,
+    Idx1: IndexType,
+    Idx2: IndexType,
+    A2: Allocator,
 {
-    type Output = IndexSet<T, S1>;
+    type Output = IndexSet<T, S1, Idx1>;
 
     /// Returns the set symmetric-difference, cloned into a new set.
     ///
     /// Values from `self` are collected in their original order, followed by
     /// values from `other` in their original order.
-    fn bitxor(self, other: &IndexSet<T, S2>) -> Self::Output {
+    fn bitxor(self, other: &IndexSet<T, S2, Idx2, A2>) -> Self::Output {
         self.symmetric_difference(other).cloned().collect()
     }
 }
 
-impl<T, S1, S2> Sub<&IndexSet<T, S2>> for &IndexSet<T, S1>
+impl<T, S1, S2, Idx1, Idx2, A2> Sub<&IndexSet<T, S2, Idx2, A2>> for &IndexSet<T, S1, Idx1>
 where
     T: Eq + Hash + Clone,
     S1: BuildHasher + Default,
     S2: BuildHasher,
+    Idx1: IndexType,
+    Idx2: IndexType,
+    A2: Allocator,
 {
-    type Output = IndexSet<T, S1>;
+    type Output = IndexSet<T, S1, Idx1>;
 
     /// Returns the set difference, cloned into a new set.
     ///
     /// Values are collected in the same order that they appear in `self`.
-    fn sub(self, other: &IndexSet<T, S2>) -> Self::Output {
+    fn sub(self, other: &IndexSet<T, S2, Idx2, A2>) -> Self::Output {
         self.difference(other).cloned().collect()
     }
 }
+
+impl<T, S1, S2, Idx1, Idx2, A1, A2> BitAndAssign<&IndexSet<T, S2, Idx2, A2>>
+    for IndexSet<T, S1, Idx1, A1>
+where
+    T: Eq + Hash,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    Idx1: IndexType,
+    Idx2: IndexType,
+    A1: Allocator,
+    A2: Allocator,
+{
+    /// Removes the values that are not in `other`, keeping the order of the rest.
+    ///
+    /// See [`intersect_with`][IndexSet::intersect_with].
+    fn bitand_assign(&mut self, other: &IndexSet<T, S2, Idx2, A2>) {
+        self.intersect_with(other);
+    }
+}
+
+impl<T, S1, S2, Idx1, Idx2, A1, A2> BitOrAssign<&IndexSet<T, S2, Idx2, A2>>
+    for IndexSet<T, S1, Idx1, A1>
+where
+    T: Eq + Hash + Clone,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    Idx1: IndexType,
+    Idx2: IndexType,
+    A1: Allocator,
+    A2: Allocator,
+{
+    /// Adds the values that are unique to `other`, in their original order.
+    ///
+    /// See [`union_with`][IndexSet::union_with].
+    fn bitor_assign(&mut self, other: &IndexSet<T, S2, Idx2, A2>) {
+        self.union_with(other);
+    }
+}
+
+impl<T, S1, S2, Idx1, Idx2, A1, A2> BitXorAssign<&IndexSet<T, S2, Idx2, A2>>
+    for IndexSet<T, S1, Idx1, A1>
+where
+    T: Eq + Hash + Clone,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    Idx1: IndexType,
+    Idx2: IndexType,
+    A1: Allocator,
+    A2: Allocator,
+{
+    /// Removes the values that are also in `other`, and adds the values that
+    /// are unique to `other` in their original order.
+    ///
+    /// See [`symmetric_difference_with`][IndexSet::symmetric_difference_with].
+    fn bitxor_assign(&mut self, other: &IndexSet<T, S2, Idx2, A2>) {
+        self.symmetric_difference_with(other);
+    }
+}
+
+impl<T, S1, S2, Idx1, Idx2, A1, A2> SubAssign<&IndexSet<T, S2, Idx2, A2>>
+    for IndexSet<T, S1, Idx1, A1>
+where
+    T: Eq + Hash,
+    S1: BuildHasher,
+    S2: BuildHasher,
+    Idx1: IndexType,
+    Idx2: IndexType,
+    A1: Allocator,
+    A2: Allocator,
+{
+    /// Removes the values that are also in `other`, keeping the order of the rest.
+    ///
+    /// See [`difference_with`][IndexSet::difference_with].
+    fn sub_assign(&mut self, other: &IndexSet<T, S2, Idx2, A2>) {
+        self.difference_with(other);
+    }
+}
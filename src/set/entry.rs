@@ -0,0 +1,179 @@
+use crate::allocator::{Allocator, Global};
+use crate::{map, IndexType};
+use core::fmt;
+
+/// Entry for an existing value in an [`IndexSet`][crate::IndexSet]
+/// or a vacant location to insert one.
+///
+/// This `enum` is created by the [`IndexSet::entry`][crate::IndexSet::entry] method.
+pub enum Entry<'a, T, Idx = usize, A: Allocator = Global> {
+    /// Existing slot with equivalent value.
+    Occupied(OccupiedEntry<'a, T, Idx, A>),
+    /// Vacant slot (no equivalent value in the set).
+    Vacant(VacantEntry<'a, T, Idx, A>),
+}
+
+impl<'a, T, Idx: IndexType, A: Allocator> Entry<'a, T, Idx, A> {
+    pub(super) fn new(entry: map::Entry<'a, T, (), Idx, A>) -> Self {
+        match entry {
+            map::Entry::Occupied(inner) => Entry::Occupied(OccupiedEntry { inner }),
+            map::Entry::Vacant(inner) => Entry::Vacant(VacantEntry { inner }),
+        }
+    }
+
+    /// Return the index where the value exists or will be inserted.
+    pub fn index(&self) -> usize {
+        match *self {
+            Entry::Occupied(ref entry) => entry.index(),
+            Entry::Vacant(ref entry) => entry.index(),
+        }
+    }
+
+    /// Gets a reference to the entry's value, either within the set if occupied,
+    /// or else the new value that was used to find the entry.
+    pub fn get(&self) -> &T {
+        match *self {
+            Entry::Occupied(ref entry) => entry.get(),
+            Entry::Vacant(ref entry) => entry.get(),
+        }
+    }
+
+    /// Ensures the value is in the set by inserting it if it was vacant,
+    /// and returns an [`OccupiedEntry`] for it either way.
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn insert(self) -> OccupiedEntry<'a, T, Idx, A> {
+        match self {
+            Entry::Occupied(entry) => entry,
+            Entry::Vacant(entry) => entry.insert(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, Idx: IndexType, A: Allocator> fmt::Debug for Entry<'_, T, Idx, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tuple = f.debug_tuple("Entry");
+        match self {
+            Entry::Vacant(v) => tuple.field(v),
+            Entry::Occupied(o) => tuple.field(o),
+        };
+        tuple.finish()
+    }
+}
+
+/// A view into an occupied entry in an [`IndexSet`][crate::IndexSet].
+/// It is part of the [`Entry`] enum.
+pub struct OccupiedEntry<'a, T, Idx = usize, A: Allocator = Global> {
+    inner: map::OccupiedEntry<'a, T, (), Idx, A>,
+}
+
+impl<'a, T, Idx: IndexType, A: Allocator> OccupiedEntry<'a, T, Idx, A> {
+    /// Return the index of the value.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.inner.index()
+    }
+
+    /// Gets a reference to the value in the set.
+    ///
+    /// Note that this is not the value that was used to find the entry. There may be an
+    /// observable difference if the value type has any distinguishing features outside of `Hash`
+    /// and `Eq`, like extra fields or the memory address of an allocation.
+    pub fn get(&self) -> &T {
+        self.inner.key()
+    }
+
+    /// Remove and return the value stored in the set for this entry.
+    ///
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the value is removed by swapping it
+    /// with the last element of the set and popping it off.
+    /// **This perturbs the position of what used to be the last element!**
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn swap_remove(self) -> T {
+        self.inner.swap_remove_entry().0
+    }
+
+    /// Remove and return the value stored in the set for this entry.
+    ///
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the value is removed by shifting all of the
+    /// elements that follow it, preserving their relative order.
+    /// **This perturbs the index of all of those elements!**
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn shift_remove(self) -> T {
+        self.inner.shift_remove_entry().0
+    }
+
+    /// Moves the position of the entry to a new index
+    /// by shifting all other entries in-between.
+    ///
+    /// This is equivalent to [`IndexSet::move_index`][`crate::IndexSet::move_index`]
+    /// coming `from` the current [`.index()`][Self::index].
+    ///
+    /// * If `self.index() < to`, the other values will shift down while the targeted value moves up.
+    /// * If `self.index() > to`, the other values will shift up while the targeted value moves down.
+    ///
+    /// ***Panics*** if `to` is out of bounds.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn move_index(self, to: usize) {
+        self.inner.move_index(to);
+    }
+}
+
+impl<T: fmt::Debug, Idx: IndexType, A: Allocator> fmt::Debug for OccupiedEntry<'_, T, Idx, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("value", self.get())
+            .finish()
+    }
+}
+
+/// A view into a vacant entry in an [`IndexSet`][crate::IndexSet].
+/// It is part of the [`Entry`] enum.
+pub struct VacantEntry<'a, T, Idx = usize, A: Allocator = Global> {
+    inner: map::VacantEntry<'a, T, (), Idx, A>,
+}
+
+impl<'a, T, Idx: IndexType, A: Allocator> VacantEntry<'a, T, Idx, A> {
+    /// Return the index where the value may be inserted.
+    pub fn index(&self) -> usize {
+        self.inner.index()
+    }
+
+    /// Gets a reference to the value that was used to find the entry.
+    pub fn get(&self) -> &T {
+        self.inner.key()
+    }
+
+    /// Takes ownership of the value, leaving the entry vacant.
+    pub fn into_value(self) -> T {
+        self.inner.into_key()
+    }
+
+    /// Inserts the entry's value into the set, and returns an [`OccupiedEntry`] for it.
+    ///
+    /// Computes in **O(1)** time (amortized average).
+    pub fn insert(self) -> OccupiedEntry<'a, T, Idx, A> {
+        OccupiedEntry {
+            inner: self.inner.insert_entry(()),
+        }
+    }
+
+    /// Inserts the entry's value into the set at the given index,
+    /// shifting others to the right.
+    ///
+    /// ***Panics*** if `index` is out of bounds.
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn shift_insert(self, index: usize) {
+        self.inner.shift_insert(index, ());
+    }
+}
+
+impl<T: fmt::Debug, Idx: IndexType, A: Allocator> fmt::Debug for VacantEntry<'_, T, Idx, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("VacantEntry").field(self.get()).finish()
+    }
+}
@@ -1,13 +1,13 @@
-use super::{Bucket, Entries, IndexSet, Slice};
+use super::{Bucket, Entries, IndexSet, IndexType, Slice};
 
-use alloc::vec::{self, Vec};
+use crate::allocator::{self, Allocator, Global, Vec};
 use core::fmt;
 use core::hash::{BuildHasher, Hash};
 use core::iter::{Chain, FusedIterator};
 use core::ops::RangeBounds;
 use core::slice::Iter as SliceIter;
 
-impl<'a, T, S> IntoIterator for &'a IndexSet<T, S> {
+impl<'a, T, S, Idx: IndexType, A: Allocator> IntoIterator for &'a IndexSet<T, S, Idx, A> {
     type Item = &'a T;
     type IntoIter = Iter<'a, T>;
 
@@ -16,9 +16,9 @@ impl<'a, T, S> IntoIterator for &'a IndexSet<T, S> {
     }
 }
 
-impl<T, S> IntoIterator for IndexSet<T, S> {
+impl<T, S, Idx: IndexType, A: Allocator> IntoIterator for IndexSet<T, S, Idx, A> {
     type Item = T;
-    type IntoIter = IntoIter<T>;
+    type IntoIter = IntoIter<T, A>;
 
     fn into_iter(self) -> Self::IntoIter {
         IntoIter::new(self.into_entries())
@@ -88,12 +88,12 @@ impl<T> Default for Iter<'_, T> {
 ///
 /// This `struct` is created by the [`IndexSet::into_iter`] method
 /// (provided by the [`IntoIterator`] trait). See its documentation for more.
-pub struct IntoIter<T> {
-    iter: vec::IntoIter<Bucket<T>>,
+pub struct IntoIter<T, A: Allocator = Global> {
+    iter: allocator::IntoIter<Bucket<T>, A>,
 }
 
-impl<T> IntoIter<T> {
-    pub(super) fn new(entries: Vec<Bucket<T>>) -> Self {
+impl<T, A: Allocator> IntoIter<T, A> {
+    pub(super) fn new(entries: Vec<Bucket<T>, A>) -> Self {
         Self {
             iter: entries.into_iter(),
         }
@@ -105,25 +105,25 @@ impl<T> IntoIter<T> {
     }
 }
 
-impl<T> Iterator for IntoIter<T> {
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
     type Item = T;
 
     iterator_methods!(Bucket::key);
 }
 
-impl<T> DoubleEndedIterator for IntoIter<T> {
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
     double_ended_iterator_methods!(Bucket::key);
 }
 
-impl<T> ExactSizeIterator for IntoIter<T> {
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<T> FusedIterator for IntoIter<T> {}
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
 
-impl<T: fmt::Debug> fmt::Debug for IntoIter<T> {
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let iter = self.iter.as_slice().iter().map(Bucket::key_ref);
         f.debug_list().entries(iter).finish()
@@ -142,12 +142,12 @@ impl<T> Default for IntoIter<T> {
 ///
 /// This `struct` is created by the [`IndexSet::drain`] method.
 /// See its documentation for more.
-pub struct Drain<'a, T> {
-    iter: vec::Drain<'a, Bucket<T>>,
+pub struct Drain<'a, T, A: Allocator = Global> {
+    iter: allocator::Drain<'a, Bucket<T>, A>,
 }
 
-impl<'a, T> Drain<'a, T> {
-    pub(super) fn new(iter: vec::Drain<'a, Bucket<T>>) -> Self {
+impl<'a, T, A: Allocator> Drain<'a, T, A> {
+    pub(super) fn new(iter: allocator::Drain<'a, Bucket<T>, A>) -> Self {
         Self { iter }
     }
 
@@ -157,25 +157,25 @@ impl<'a, T> Drain<'a, T> {
     }
 }
 
-impl<T> Iterator for Drain<'_, T> {
+impl<T, A: Allocator> Iterator for Drain<'_, T, A> {
     type Item = T;
 
     iterator_methods!(Bucket::key);
 }
 
-impl<T> DoubleEndedIterator for Drain<'_, T> {
+impl<T, A: Allocator> DoubleEndedIterator for Drain<'_, T, A> {
     double_ended_iterator_methods!(Bucket::key);
 }
 
-impl<T> ExactSizeIterator for Drain<'_, T> {
+impl<T, A: Allocator> ExactSizeIterator for Drain<'_, T, A> {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<T> FusedIterator for Drain<'_, T> {}
+impl<T, A: Allocator> FusedIterator for Drain<'_, T, A> {}
 
-impl<T: fmt::Debug> fmt::Debug for Drain<'_, T> {
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for Drain<'_, T, A> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let iter = self.iter.as_slice().iter().map(Bucket::key_ref);
         f.debug_list().entries(iter).finish()
@@ -186,13 +186,20 @@ impl<T: fmt::Debug> fmt::Debug for Drain<'_, T> {
 ///
 /// This `struct` is created by the [`IndexSet::difference`] method.
 /// See its documentation for more.
-pub struct Difference<'a, T, S> {
+pub struct Difference<'a, T, S, Idx = usize, A: Allocator = Global> {
     iter: Iter<'a, T>,
-    other: &'a IndexSet<T, S>,
+    other: &'a IndexSet<T, S, Idx, A>,
 }
 
-impl<'a, T, S> Difference<'a, T, S> {
-    pub(super) fn new<S1>(set: &'a IndexSet<T, S1>, other: &'a IndexSet<T, S>) -> Self {
+impl<'a, T, S, Idx, A: Allocator> Difference<'a, T, S, Idx, A> {
+    pub(super) fn new<S1, Idx1, A1>(
+        set: &'a IndexSet<T, S1, Idx1, A1>,
+        other: &'a IndexSet<T, S, Idx, A>,
+    ) -> Self
+    where
+        Idx1: IndexType,
+        A1: Allocator,
+    {
         Self {
             iter: set.iter(),
             other,
@@ -200,10 +207,12 @@ impl<'a, T, S> Difference<'a, T, S> {
     }
 }
 
-impl<'a, T, S> Iterator for Difference<'a, T, S>
+impl<'a, T, S, Idx, A> Iterator for Difference<'a, T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Item = &'a T;
 
@@ -221,10 +230,12 @@ where
     }
 }
 
-impl<T, S> DoubleEndedIterator for Difference<'_, T, S>
+impl<T, S, Idx, A> DoubleEndedIterator for Difference<'_, T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         while let Some(item) = self.iter.next_back() {
@@ -236,14 +247,16 @@ where
     }
 }
 
-impl<T, S> FusedIterator for Difference<'_, T, S>
+impl<T, S, Idx, A> FusedIterator for Difference<'_, T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
 }
 
-impl<T, S> Clone for Difference<'_, T, S> {
+impl<T, S, Idx, A: Allocator> Clone for Difference<'_, T, S, Idx, A> {
     fn clone(&self) -> Self {
         Difference {
             iter: self.iter.clone(),
@@ -252,10 +265,12 @@ impl<T, S> Clone for Difference<'_, T, S> {
     }
 }
 
-impl<T, S> fmt::Debug for Difference<'_, T, S>
+impl<T, S, Idx, A> fmt::Debug for Difference<'_, T, S, Idx, A>
 where
     T: fmt::Debug + Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
@@ -266,13 +281,20 @@ where
 ///
 /// This `struct` is created by the [`IndexSet::intersection`] method.
 /// See its documentation for more.
-pub struct Intersection<'a, T, S> {
+pub struct Intersection<'a, T, S, Idx = usize, A: Allocator = Global> {
     iter: Iter<'a, T>,
-    other: &'a IndexSet<T, S>,
+    other: &'a IndexSet<T, S, Idx, A>,
 }
 
-impl<'a, T, S> Intersection<'a, T, S> {
-    pub(super) fn new<S1>(set: &'a IndexSet<T, S1>, other: &'a IndexSet<T, S>) -> Self {
+impl<'a, T, S, Idx, A: Allocator> Intersection<'a, T, S, Idx, A> {
+    pub(super) fn new<S1, Idx1, A1>(
+        set: &'a IndexSet<T, S1, Idx1, A1>,
+        other: &'a IndexSet<T, S, Idx, A>,
+    ) -> Self
+    where
+        Idx1: IndexType,
+        A1: Allocator,
+    {
         Self {
             iter: set.iter(),
             other,
@@ -280,10 +302,12 @@ impl<'a, T, S> Intersection<'a, T, S> {
     }
 }
 
-impl<'a, T, S> Iterator for Intersection<'a, T, S>
+impl<'a, T, S, Idx, A> Iterator for Intersection<'a, T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Item = &'a T;
 
@@ -301,10 +325,12 @@ where
     }
 }
 
-impl<T, S> DoubleEndedIterator for Intersection<'_, T, S>
+impl<T, S, Idx, A> DoubleEndedIterator for Intersection<'_, T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         while let Some(item) = self.iter.next_back() {
@@ -316,14 +342,16 @@ where
     }
 }
 
-impl<T, S> FusedIterator for Intersection<'_, T, S>
+impl<T, S, Idx, A> FusedIterator for Intersection<'_, T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
 }
 
-impl<T, S> Clone for Intersection<'_, T, S> {
+impl<T, S, Idx, A: Allocator> Clone for Intersection<'_, T, S, Idx, A> {
     fn clone(&self) -> Self {
         Intersection {
             iter: self.iter.clone(),
@@ -332,10 +360,12 @@ impl<T, S> Clone for Intersection<'_, T, S> {
     }
 }
 
-impl<T, S> fmt::Debug for Intersection<'_, T, S>
+impl<T, S, Idx, A> fmt::Debug for Intersection<'_, T, S, Idx, A>
 where
     T: fmt::Debug + Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
@@ -346,17 +376,34 @@ where
 ///
 /// This `struct` is created by the [`IndexSet::symmetric_difference`] method.
 /// See its documentation for more.
-pub struct SymmetricDifference<'a, T, S1, S2> {
-    iter: Chain<Difference<'a, T, S2>, Difference<'a, T, S1>>,
-}
-
-impl<'a, T, S1, S2> SymmetricDifference<'a, T, S1, S2>
+pub struct SymmetricDifference<
+    'a,
+    T,
+    S1,
+    S2,
+    Idx1 = usize,
+    Idx2 = usize,
+    A1: Allocator = Global,
+    A2: Allocator = Global,
+> {
+    #[allow(clippy::type_complexity)]
+    iter: Chain<Difference<'a, T, S2, Idx2, A2>, Difference<'a, T, S1, Idx1, A1>>,
+}
+
+impl<'a, T, S1, S2, Idx1, Idx2, A1, A2> SymmetricDifference<'a, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: Eq + Hash,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
-    pub(super) fn new(set1: &'a IndexSet<T, S1>, set2: &'a IndexSet<T, S2>) -> Self {
+    pub(super) fn new(
+        set1: &'a IndexSet<T, S1, Idx1, A1>,
+        set2: &'a IndexSet<T, S2, Idx2, A2>,
+    ) -> Self {
         let diff1 = set1.difference(set2);
         let diff2 = set2.difference(set1);
         Self {
@@ -365,11 +412,16 @@ where
     }
 }
 
-impl<'a, T, S1, S2> Iterator for SymmetricDifference<'a, T, S1, S2>
+impl<'a, T, S1, S2, Idx1, Idx2, A1, A2> Iterator
+    for SymmetricDifference<'a, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: Eq + Hash,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
     type Item = &'a T;
 
@@ -389,11 +441,16 @@ where
     }
 }
 
-impl<T, S1, S2> DoubleEndedIterator for SymmetricDifference<'_, T, S1, S2>
+impl<T, S1, S2, Idx1, Idx2, A1, A2> DoubleEndedIterator
+    for SymmetricDifference<'_, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: Eq + Hash,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.iter.next_back()
@@ -407,15 +464,22 @@ where
     }
 }
 
-impl<T, S1, S2> FusedIterator for SymmetricDifference<'_, T, S1, S2>
+impl<T, S1, S2, Idx1, Idx2, A1, A2> FusedIterator
+    for SymmetricDifference<'_, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: Eq + Hash,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
 }
 
-impl<T, S1, S2> Clone for SymmetricDifference<'_, T, S1, S2> {
+impl<T, S1, S2, Idx1, Idx2, A1: Allocator, A2: Allocator> Clone
+    for SymmetricDifference<'_, T, S1, S2, Idx1, Idx2, A1, A2>
+{
     fn clone(&self) -> Self {
         SymmetricDifference {
             iter: self.iter.clone(),
@@ -423,11 +487,16 @@ impl<T, S1, S2> Clone for SymmetricDifference<'_, T, S1, S2> {
     }
 }
 
-impl<T, S1, S2> fmt::Debug for SymmetricDifference<'_, T, S1, S2>
+impl<T, S1, S2, Idx1, Idx2, A1, A2> fmt::Debug
+    for SymmetricDifference<'_, T, S1, S2, Idx1, Idx2, A1, A2>
 where
     T: fmt::Debug + Eq + Hash,
     S1: BuildHasher,
     S2: BuildHasher,
+    Idx1: IndexType,
+    A1: Allocator,
+    Idx2: IndexType,
+    A2: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
@@ -438,18 +507,25 @@ where
 ///
 /// This `struct` is created by the [`IndexSet::union`] method.
 /// See its documentation for more.
-pub struct Union<'a, T, S> {
-    iter: Chain<Iter<'a, T>, Difference<'a, T, S>>,
+pub struct Union<'a, T, S, Idx = usize, A: Allocator = Global> {
+    iter: Chain<Iter<'a, T>, Difference<'a, T, S, Idx, A>>,
 }
 
-impl<'a, T, S> Union<'a, T, S>
+impl<'a, T, S, Idx, A> Union<'a, T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
-    pub(super) fn new<S2>(set1: &'a IndexSet<T, S>, set2: &'a IndexSet<T, S2>) -> Self
+    pub(super) fn new<S2, Idx2, A2>(
+        set1: &'a IndexSet<T, S, Idx, A>,
+        set2: &'a IndexSet<T, S2, Idx2, A2>,
+    ) -> Self
     where
         S2: BuildHasher,
+        Idx2: IndexType,
+        A2: Allocator,
     {
         Self {
             iter: set1.iter().chain(set2.difference(set1)),
@@ -457,10 +533,12 @@ where
     }
 }
 
-impl<'a, T, S> Iterator for Union<'a, T, S>
+impl<'a, T, S, Idx, A> Iterator for Union<'a, T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Item = &'a T;
 
@@ -480,10 +558,12 @@ where
     }
 }
 
-impl<T, S> DoubleEndedIterator for Union<'_, T, S>
+impl<T, S, Idx, A> DoubleEndedIterator for Union<'_, T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.iter.next_back()
@@ -497,14 +577,16 @@ where
     }
 }
 
-impl<T, S> FusedIterator for Union<'_, T, S>
+impl<T, S, Idx, A> FusedIterator for Union<'_, T, S, Idx, A>
 where
     T: Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
 }
 
-impl<T, S> Clone for Union<'_, T, S> {
+impl<T, S, Idx, A: Allocator> Clone for Union<'_, T, S, Idx, A> {
     fn clone(&self) -> Self {
         Union {
             iter: self.iter.clone(),
@@ -512,10 +594,12 @@ impl<T, S> Clone for Union<'_, T, S> {
     }
 }
 
-impl<T, S> fmt::Debug for Union<'_, T, S>
+impl<T, S, Idx, A> fmt::Debug for Union<'_, T, S, Idx, A>
 where
     T: fmt::Debug + Eq + Hash,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_list().entries(self.clone()).finish()
@@ -526,22 +610,26 @@ where
 ///
 /// This `struct` is created by [`IndexSet::splice()`].
 /// See its documentation for more.
-pub struct Splice<'a, I, T, S>
+pub struct Splice<'a, I, T, S, Idx = usize, A: Allocator = Global>
 where
     I: Iterator<Item = T>,
     T: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
-    iter: crate::map::Splice<'a, UnitValue<I>, T, (), S>,
+    iter: crate::map::Splice<'a, UnitValue<I>, T, (), S, Idx, A>,
 }
 
-impl<'a, I, T, S> Splice<'a, I, T, S>
+impl<'a, I, T, S, Idx, A> Splice<'a, I, T, S, Idx, A>
 where
     I: Iterator<Item = T>,
     T: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator + Clone,
 {
-    pub(super) fn new<R>(set: &'a mut IndexSet<T, S>, range: R, replace_with: I) -> Self
+    pub(super) fn new<R>(set: &'a mut IndexSet<T, S, Idx, A>, range: R, replace_with: I) -> Self
     where
         R: RangeBounds<usize>,
     {
@@ -551,11 +639,13 @@ where
     }
 }
 
-impl<I, T, S> Iterator for Splice<'_, I, T, S>
+impl<I, T, S, Idx, A> Iterator for Splice<'_, I, T, S, Idx, A>
 where
     I: Iterator<Item = T>,
     T: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     type Item = T;
 
@@ -568,33 +658,39 @@ where
     }
 }
 
-impl<I, T, S> DoubleEndedIterator for Splice<'_, I, T, S>
+impl<I, T, S, Idx, A> DoubleEndedIterator for Splice<'_, I, T, S, Idx, A>
 where
     I: Iterator<Item = T>,
     T: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
         Some(self.iter.next_back()?.0)
     }
 }
 
-impl<I, T, S> ExactSizeIterator for Splice<'_, I, T, S>
+impl<I, T, S, Idx, A> ExactSizeIterator for Splice<'_, I, T, S, Idx, A>
 where
     I: Iterator<Item = T>,
     T: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn len(&self) -> usize {
         self.iter.len()
     }
 }
 
-impl<I, T, S> FusedIterator for Splice<'_, I, T, S>
+impl<I, T, S, Idx, A> FusedIterator for Splice<'_, I, T, S, Idx, A>
 where
     I: Iterator<Item = T>,
     T: Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
 }
 
@@ -608,11 +704,13 @@ impl<I: Iterator> Iterator for UnitValue<I> {
     }
 }
 
-impl<'a, I, T, S> fmt::Debug for Splice<'a, I, T, S>
+impl<'a, I, T, S, Idx, A> fmt::Debug for Splice<'a, I, T, S, Idx, A>
 where
     I: fmt::Debug + Iterator<Item = T>,
     T: fmt::Debug + Hash + Eq,
     S: BuildHasher,
+    Idx: IndexType,
+    A: Allocator,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(&self.iter, f)
@@ -0,0 +1,407 @@
+//! Opt-in access to the experimental raw entry API.
+//!
+//! This module is the [`IndexSet`] counterpart to [`map::raw_entry_v1`][crate::map::raw_entry_v1],
+//! which is designed to mimic the raw entry API of [`HashMap`][std::collections::hash_map] as of
+//! Rust 1.75. See that module for more details.
+//!
+//! The main difference for sets is that a vacant entry remembers the hash that was used to find
+//! it, so a value can be computed from a borrowed lookup key and inserted without hashing again.
+
+use crate::allocator::{Allocator, Global};
+use crate::map::raw_entry_v1 as map_raw;
+use crate::map::RawEntryApiV1 as _;
+use crate::{Equivalent, IndexSet, IndexType};
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+
+/// Opt-in access to the experimental raw entry API.
+///
+/// See the [`raw_entry_v1`][self] module documentation for more information.
+pub trait RawEntryApiV1<T, S, Idx = usize, A: Allocator = Global>: private::Sealed {
+    /// Creates a raw immutable entry builder for the [`IndexSet`].
+    ///
+    /// Raw entries provide the lowest level of control for searching and
+    /// manipulating a set. They must be manually initialized with a hash and
+    /// then manually searched.
+    ///
+    /// This is useful for
+    /// * Hash memoization
+    /// * Using a search key that doesn't work with the [`Equivalent`] trait
+    /// * Using custom comparison logic without newtype wrappers
+    ///
+    /// Unless you are in such a situation, higher-level and more foolproof APIs like
+    /// [`get`][IndexSet::get] should be preferred.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use core::hash::{BuildHasher, Hash, Hasher};
+    /// use indexmap::set::{IndexSet, RawEntryApiV1};
+    ///
+    /// let mut set = IndexSet::new();
+    /// set.extend(["a", "b", "c"]);
+    ///
+    /// let mut state = set.hasher().build_hasher();
+    /// "b".hash(&mut state);
+    /// let hash = state.finish();
+    ///
+    /// assert_eq!(set.raw_entry_v1().from_key("b"), Some(&"b"));
+    /// assert_eq!(set.raw_entry_v1().from_hash(hash, |q| *q == "b"), Some(&"b"));
+    /// assert_eq!(set.raw_entry_v1().from_key_hashed_nocheck(hash, "b"), Some(&"b"));
+    /// assert_eq!(set.raw_entry_v1().from_key("d"), None);
+    /// ```
+    fn raw_entry_v1(&self) -> RawEntryBuilder<'_, T, S, Idx, A>;
+
+    /// Creates a raw entry builder for the [`IndexSet`].
+    ///
+    /// Raw entries provide the lowest level of control for searching and
+    /// manipulating a set. They must be manually initialized with a hash and
+    /// then manually searched. After this, insertions into a vacant entry
+    /// still require an owned value to be provided.
+    ///
+    /// Raw entries give mutable access to the values. This must not be used
+    /// to modify how the value would compare or hash, as the set will not re-evaluate
+    /// where the value should go, meaning the values may become "lost" if their
+    /// location does not reflect their state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::set::{IndexSet, RawEntryApiV1};
+    /// use indexmap::set::raw_entry_v1::RawEntryMut;
+    ///
+    /// let mut set: IndexSet<String> = IndexSet::new();
+    /// set.insert("a".to_string());
+    ///
+    /// // Only allocate an owned `String` when the borrowed key is missing,
+    /// // and only hash it once.
+    /// for key in ["a", "b", "a", "c"] {
+    ///     set.raw_entry_mut_v1()
+    ///         .from_key(key)
+    ///         .or_insert_with(|| key.to_string());
+    /// }
+    /// assert!(set.iter().eq(["a", "b", "c"]));
+    ///
+    /// match set.raw_entry_mut_v1().from_key("b") {
+    ///     RawEntryMut::Vacant(_) => unreachable!(),
+    ///     RawEntryMut::Occupied(entry) => {
+    ///         assert_eq!(entry.index(), 1);
+    ///         assert_eq!(entry.shift_remove(), "b");
+    ///     }
+    /// }
+    /// assert!(set.iter().eq(["a", "c"]));
+    /// ```
+    fn raw_entry_mut_v1(&mut self) -> RawEntryBuilderMut<'_, T, S, Idx, A>;
+}
+
+impl<T, S, Idx: IndexType, A: Allocator> RawEntryApiV1<T, S, Idx, A> for IndexSet<T, S, Idx, A> {
+    fn raw_entry_v1(&self) -> RawEntryBuilder<'_, T, S, Idx, A> {
+        RawEntryBuilder {
+            inner: self.map.raw_entry_v1(),
+        }
+    }
+
+    fn raw_entry_mut_v1(&mut self) -> RawEntryBuilderMut<'_, T, S, Idx, A> {
+        RawEntryBuilderMut { set: self }
+    }
+}
+
+/// A builder for computing where in an [`IndexSet`] a value would be stored.
+///
+/// This `struct` is created by the [`IndexSet::raw_entry_v1`] method, provided by the
+/// [`RawEntryApiV1`] trait. See its documentation for more.
+pub struct RawEntryBuilder<'a, T, S, Idx = usize, A: Allocator = Global> {
+    inner: map_raw::RawEntryBuilder<'a, T, (), S, Idx, A>,
+}
+
+impl<T, S, Idx: IndexType, A: Allocator> fmt::Debug for RawEntryBuilder<'_, T, S, Idx, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawEntryBuilder").finish_non_exhaustive()
+    }
+}
+
+impl<'a, T, S, Idx: IndexType, A: Allocator> RawEntryBuilder<'a, T, S, Idx, A> {
+    /// Access an entry by key.
+    pub fn from_key<Q: ?Sized>(self, key: &Q) -> Option<&'a T>
+    where
+        S: BuildHasher,
+        Q: Hash + Equivalent<T>,
+    {
+        Some(self.inner.from_key(key)?.0)
+    }
+
+    /// Access an entry by a key and its hash.
+    pub fn from_key_hashed_nocheck<Q: ?Sized>(self, hash: u64, key: &Q) -> Option<&'a T>
+    where
+        Q: Equivalent<T>,
+    {
+        Some(self.inner.from_key_hashed_nocheck(hash, key)?.0)
+    }
+
+    /// Access an entry by hash.
+    pub fn from_hash<F>(self, hash: u64, is_match: F) -> Option<&'a T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        Some(self.inner.from_hash(hash, is_match)?.0)
+    }
+}
+
+/// A builder for computing where in an [`IndexSet`] a value would be stored.
+///
+/// This `struct` is created by the [`IndexSet::raw_entry_mut_v1`] method, provided by the
+/// [`RawEntryApiV1`] trait. See its documentation for more.
+pub struct RawEntryBuilderMut<'a, T, S, Idx = usize, A: Allocator = Global> {
+    set: &'a mut IndexSet<T, S, Idx, A>,
+}
+
+impl<T, S, Idx: IndexType, A: Allocator> fmt::Debug for RawEntryBuilderMut<'_, T, S, Idx, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawEntryBuilderMut").finish_non_exhaustive()
+    }
+}
+
+impl<'a, T, S, Idx: IndexType, A: Allocator> RawEntryBuilderMut<'a, T, S, Idx, A> {
+    /// Access an entry by key.
+    pub fn from_key<Q: ?Sized>(self, key: &Q) -> RawEntryMut<'a, T, S, Idx, A>
+    where
+        S: BuildHasher,
+        Q: Hash + Equivalent<T>,
+    {
+        let hash = self.set.map.hash(key);
+        self.from_key_hashed_nocheck(hash.get(), key)
+    }
+
+    /// Access an entry by a key and its hash.
+    pub fn from_key_hashed_nocheck<Q: ?Sized>(
+        self,
+        hash: u64,
+        key: &Q,
+    ) -> RawEntryMut<'a, T, S, Idx, A>
+    where
+        Q: Equivalent<T>,
+    {
+        self.from_hash(hash, |k| Q::equivalent(key, k))
+    }
+
+    /// Access an entry by hash.
+    pub fn from_hash<F>(self, hash: u64, is_match: F) -> RawEntryMut<'a, T, S, Idx, A>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        match self.set.map.raw_entry_mut_v1().from_hash(hash, is_match) {
+            map_raw::RawEntryMut::Occupied(inner) => {
+                RawEntryMut::Occupied(RawOccupiedEntryMut { inner })
+            }
+            map_raw::RawEntryMut::Vacant(inner) => {
+                RawEntryMut::Vacant(RawVacantEntryMut { inner, hash })
+            }
+        }
+    }
+}
+
+/// Raw entry for an existing value or a vacant location to insert one.
+pub enum RawEntryMut<'a, T, S, Idx = usize, A: Allocator = Global> {
+    /// Existing slot with equivalent value.
+    Occupied(RawOccupiedEntryMut<'a, T, S, Idx, A>),
+    /// Vacant slot (no equivalent value in the set).
+    Vacant(RawVacantEntryMut<'a, T, S, Idx, A>),
+}
+
+impl<T: fmt::Debug, S, Idx: IndexType, A: Allocator> fmt::Debug for RawEntryMut<'_, T, S, Idx, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tuple = f.debug_tuple("RawEntryMut");
+        match self {
+            Self::Vacant(v) => tuple.field(v),
+            Self::Occupied(o) => tuple.field(o),
+        };
+        tuple.finish()
+    }
+}
+
+impl<'a, T, S, Idx: IndexType, A: Allocator> RawEntryMut<'a, T, S, Idx, A> {
+    /// Return the index where the value exists or will be inserted.
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Occupied(entry) => entry.index(),
+            Self::Vacant(entry) => entry.index(),
+        }
+    }
+
+    /// Inserts the given default value in the entry if it is vacant and returns a mutable
+    /// reference to it. Otherwise a mutable reference to an already existent value is returned.
+    pub fn or_insert(self, default: T) -> &'a mut T
+    where
+        T: Hash,
+        S: BuildHasher,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Inserts the result of the `call` function in the entry if it is vacant and returns a
+    /// mutable reference to it. Otherwise a mutable reference to an already existent value is
+    /// returned.
+    ///
+    /// The new value is inserted with the hash that was used to find the entry, without hashing
+    /// it again, so it must hash the same as the lookup key. See
+    /// [`RawVacantEntryMut::insert_with`].
+    pub fn or_insert_with<F>(self, call: F) -> &'a mut T
+    where
+        F: FnOnce() -> T,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert_with(call),
+        }
+    }
+
+    /// Modifies the entry if it is occupied.
+    pub fn and_modify<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut T),
+    {
+        if let Self::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// A raw view into an occupied entry in an [`IndexSet`].
+/// It is part of the [`RawEntryMut`] enum.
+pub struct RawOccupiedEntryMut<'a, T, S, Idx = usize, A: Allocator = Global> {
+    inner: map_raw::RawOccupiedEntryMut<'a, T, (), S, Idx, A>,
+}
+
+impl<T: fmt::Debug, S, Idx: IndexType, A: Allocator> fmt::Debug
+    for RawOccupiedEntryMut<'_, T, S, Idx, A>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawOccupiedEntryMut")
+            .field("value", self.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T, S, Idx: IndexType, A: Allocator> RawOccupiedEntryMut<'a, T, S, Idx, A> {
+    /// Return the index of the value.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.inner.index()
+    }
+
+    /// Gets a reference to the entry's value in the set.
+    ///
+    /// Note that this is not the value that was used to find the entry. There may be an
+    /// observable difference if the value type has any distinguishing features outside of `Hash`
+    /// and `Eq`, like extra fields or the memory address of an allocation.
+    pub fn get(&self) -> &T {
+        self.inner.key()
+    }
+
+    /// Gets a mutable reference to the entry's value in the set.
+    ///
+    /// This must not be used to modify how the value would compare or hash.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.key_mut()
+    }
+
+    /// Converts into a mutable reference to the entry's value in the set,
+    /// with a lifetime bound to the set itself.
+    ///
+    /// This must not be used to modify how the value would compare or hash.
+    pub fn into_mut(self) -> &'a mut T {
+        self.inner.into_key()
+    }
+
+    /// Sets the value of the entry, and returns the entry's old value.
+    pub fn insert(&mut self, value: T) -> T {
+        self.inner.insert_key(value)
+    }
+
+    /// Remove and return the value stored in the set for this entry.
+    ///
+    /// Like [`Vec::swap_remove`][alloc::vec::Vec::swap_remove], the value is removed by swapping it
+    /// with the last element of the set and popping it off.
+    /// **This perturbs the position of what used to be the last element!**
+    ///
+    /// Computes in **O(1)** time (average).
+    pub fn swap_remove(self) -> T {
+        self.inner.swap_remove_entry().0
+    }
+
+    /// Remove and return the value stored in the set for this entry.
+    ///
+    /// Like [`Vec::remove`][alloc::vec::Vec::remove], the value is removed by shifting all of the
+    /// elements that follow it, preserving their relative order.
+    /// **This perturbs the index of all of those elements!**
+    ///
+    /// Computes in **O(n)** time (average).
+    pub fn shift_remove(self) -> T {
+        self.inner.shift_remove_entry().0
+    }
+}
+
+/// A view into a vacant raw entry in an [`IndexSet`].
+/// It is part of the [`RawEntryMut`] enum.
+pub struct RawVacantEntryMut<'a, T, S, Idx = usize, A: Allocator = Global> {
+    inner: map_raw::RawVacantEntryMut<'a, T, (), S, Idx, A>,
+    hash: u64,
+}
+
+impl<T, S, Idx: IndexType, A: Allocator> fmt::Debug for RawVacantEntryMut<'_, T, S, Idx, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawVacantEntryMut").finish_non_exhaustive()
+    }
+}
+
+impl<'a, T, S, Idx: IndexType, A: Allocator> RawVacantEntryMut<'a, T, S, Idx, A> {
+    /// Return the index where a value may be inserted.
+    pub fn index(&self) -> usize {
+        self.inner.index()
+    }
+
+    /// Return the hash that was used to find this entry.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Inserts the given value into the set,
+    /// and returns a mutable reference to it.
+    pub fn insert(self, value: T) -> &'a mut T
+    where
+        T: Hash,
+        S: BuildHasher,
+    {
+        self.inner.insert(value, ()).0
+    }
+
+    /// Inserts the given value into the set with the provided hash,
+    /// and returns a mutable reference to it.
+    pub fn insert_hashed_nocheck(self, hash: u64, value: T) -> &'a mut T {
+        self.inner.insert_hashed_nocheck(hash, value, ()).0
+    }
+
+    /// Inserts the result of the `call` function into the set, using the hash that was used to
+    /// find this entry, and returns a mutable reference to it.
+    ///
+    /// This is useful to create an owned value from a borrowed lookup key only when it is
+    /// missing, without hashing it again. The new value must hash the same as that key, or else
+    /// it can not be found in the set afterward.
+    pub fn insert_with<F>(self, call: F) -> &'a mut T
+    where
+        F: FnOnce() -> T,
+    {
+        let hash = self.hash;
+        self.insert_hashed_nocheck(hash, call())
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl<T, S, Idx, A: super::Allocator> Sealed for super::IndexSet<T, S, Idx, A> {}
+}
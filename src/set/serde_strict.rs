@@ -0,0 +1,176 @@
+//! Functions to serialize and deserialize an [`IndexSet`] as a sequence that
+//! must not contain duplicate values.
+//!
+//! The default `serde` implementation deserializes a set by inserting each
+//! value in turn, so a value that occurs more than once is silently ignored
+//! after its first occurrence. This module fails to deserialize such a
+//! sequence instead, with an error that names the duplicate value.
+//! Serialization is the same as the default implementation.
+//!
+//! This module may be used in a field attribute for derived implementations:
+//!
+//! ```
+//! # use indexmap::IndexSet;
+//! # use serde_derive::{Deserialize, Serialize};
+//! #[derive(Deserialize, Serialize)]
+//! struct Data {
+//!     #[serde(with = "indexmap::set::serde_strict")]
+//!     set: IndexSet<i32>,
+//!     // ...
+//! }
+//! ```
+
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{BuildHasher, Hash};
+
+use crate::allocator::Allocator;
+use crate::map::{self, IndexMapSeed};
+use crate::{IndexSet, IndexType};
+
+/// Serializes an [`IndexSet`] as a sequence, like its [`Serialize`] implementation.
+///
+/// This function may be used in a field attribute for deriving [`Serialize`]:
+///
+/// ```
+/// # use indexmap::IndexSet;
+/// # use serde_derive::Serialize;
+/// #[derive(Serialize)]
+/// struct Data {
+///     #[serde(serialize_with = "indexmap::set::serde_strict::serialize")]
+///     set: IndexSet<i32>,
+///     // ...
+/// }
+/// ```
+pub fn serialize<T, S, Se, Idx, A>(
+    set: &IndexSet<T, S, Idx, A>,
+    serializer: Se,
+) -> Result<Se::Ok, Se::Error>
+where
+    T: Serialize,
+    Se: Serializer,
+    Idx: IndexType,
+    A: Allocator,
+{
+    serializer.collect_seq(set)
+}
+
+/// A [`DeserializeSeed`] that creates an [`IndexSet`] with a given hasher,
+/// failing on any duplicate value.
+///
+/// This is like [`deserialize`], but it takes the hasher instance instead of
+/// needing `S: Default`. See [`set::IndexSetSeed`][crate::set::IndexSetSeed]
+/// for the bounds it can also put on untrusted input.
+pub struct IndexSetSeed<T, S, Idx = usize>(IndexMapSeed<T, (), S, Idx>);
+
+impl<T, S, Idx> IndexSetSeed<T, S, Idx> {
+    /// Create a seed for an `IndexSet` that will use the given hasher.
+    pub fn new(hash_builder: S) -> Self {
+        IndexSetSeed(IndexMapSeed::new(hash_builder))
+    }
+
+    /// Limit the capacity that is allocated up front to `limit` values.
+    ///
+    /// See [`set::IndexSetSeed::with_capacity_limit`][crate::set::IndexSetSeed::with_capacity_limit].
+    pub fn with_capacity_limit(self, limit: usize) -> Self {
+        IndexSetSeed(self.0.with_capacity_limit(limit))
+    }
+
+    /// Fail to deserialize a set with more than `max_len` values.
+    ///
+    /// See [`set::IndexSetSeed::with_max_len`][crate::set::IndexSetSeed::with_max_len].
+    pub fn with_max_len(self, max_len: usize) -> Self {
+        IndexSetSeed(self.0.with_max_len(max_len))
+    }
+}
+
+impl<T, S: Clone, Idx> Clone for IndexSetSeed<T, S, Idx> {
+    fn clone(&self) -> Self {
+        IndexSetSeed(self.0.clone())
+    }
+}
+
+impl<T, S: fmt::Debug, Idx> fmt::Debug for IndexSetSeed<T, S, Idx> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IndexSetSeed").field(&self.0).finish()
+    }
+}
+
+impl<'de, T, S, Idx> DeserializeSeed<'de> for IndexSetSeed<T, S, Idx>
+where
+    T: Deserialize<'de> + Eq + Hash + Debug,
+    S: BuildHasher,
+    Idx: IndexType,
+{
+    type Value = IndexSet<T, S, Idx>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(StrictVisitor(self.0))
+    }
+}
+
+/// Visitor to deserialize an `IndexSet` without duplicate values
+struct StrictVisitor<T, S, Idx>(IndexMapSeed<T, (), S, Idx>);
+
+impl<'de, T, S, Idx> Visitor<'de> for StrictVisitor<T, S, Idx>
+where
+    T: Deserialize<'de> + Eq + Hash + Debug,
+    S: BuildHasher,
+    Idx: IndexType,
+{
+    type Value = IndexSet<T, S, Idx>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "a set with unique values")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let size_hint = seq.size_hint();
+        let next_entry = || Ok(seq.next_element()?.map(|value| (value, ())));
+        let map = self.0.build_with(size_hint, next_entry, |map, value, ()| {
+            match map.entry(value) {
+                map::Entry::Occupied(entry) => Err(Error::custom(format_args!(
+                    "duplicate set value {:?}",
+                    entry.key()
+                ))),
+                map::Entry::Vacant(entry) => {
+                    entry.insert(());
+                    Ok(())
+                }
+            }
+        })?;
+        Ok(IndexSet { map })
+    }
+}
+
+/// Deserializes an [`IndexSet`] from a sequence, failing on any duplicate value.
+///
+/// This function may be used in a field attribute for deriving [`Deserialize`]:
+///
+/// ```
+/// # use indexmap::IndexSet;
+/// # use serde_derive::Deserialize;
+/// #[derive(Deserialize)]
+/// struct Data {
+///     #[serde(deserialize_with = "indexmap::set::serde_strict::deserialize")]
+///     set: IndexSet<i32>,
+///     // ...
+/// }
+/// ```
+pub fn deserialize<'de, D, T, S, Idx>(deserializer: D) -> Result<IndexSet<T, S, Idx>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Eq + Hash + Debug,
+    S: Default + BuildHasher,
+    Idx: IndexType,
+{
+    IndexSetSeed::new(S::default()).deserialize(deserializer)
+}
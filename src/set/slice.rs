@@ -1,8 +1,10 @@
-use super::{Bucket, Entries, IndexSet, IntoIter, Iter};
-use crate::util::try_simplify_range;
+use super::{Bucket, Entries, IndexSet, IndexType, IntoIter, Iter};
+use crate::allocator::{self, Allocator};
+use crate::util::{sorted_key_range, try_simplify_range};
 
 use alloc::boxed::Box;
 use alloc::vec::Vec;
+use core::borrow::Borrow;
 use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{Hash, Hasher};
@@ -35,6 +37,14 @@ impl<T> Slice<T> {
     fn into_boxed(self: Box<Self>) -> Box<[Bucket<T>]> {
         unsafe { Box::from_raw(Box::into_raw(self) as *mut [Bucket<T>]) }
     }
+
+    #[cfg(feature = "allocator-api2")]
+    pub(super) fn from_boxed_in<A: Allocator>(
+        entries: allocator::Box<[Bucket<T>], A>,
+    ) -> allocator::Box<Self, A> {
+        let (entries, alloc) = allocator::Box::into_raw_with_allocator(entries);
+        unsafe { allocator::Box::from_raw_in(entries as *mut Self, alloc) }
+    }
 }
 
 impl<T> Slice<T> {
@@ -42,6 +52,13 @@ impl<T> Slice<T> {
         self.into_boxed().into_vec()
     }
 
+    /// Create a boxed slice from entries that may have duplicate values, or
+    /// arbitrary hash values, since neither is used by the slice.
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_entries(entries: Vec<Bucket<T>>) -> Box<Self> {
+        Self::from_boxed(entries.into_boxed_slice())
+    }
+
     /// Returns an empty slice.
     pub const fn new<'a>() -> &'a Self {
         Self::from_slice(&[])
@@ -172,6 +189,24 @@ impl<T> Slice<T> {
     {
         self.entries.partition_point(move |a| pred(&a.key))
     }
+
+    /// Returns a subslice of the values of a sorted slice that are within `range`.
+    ///
+    /// The bounds are found with [`partition_point`][Self::partition_point], so this is
+    /// like [`BTreeSet::range`][alloc::collections::BTreeSet::range] for a slice that is
+    /// sorted. If the values are **not** sorted, the returned slice is unspecified.
+    /// A range whose start is after its end returns an empty slice.
+    ///
+    /// Computes in **O(log(n))** time.
+    pub fn range<Q, R>(&self, range: R) -> &Self
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Ord,
+        R: RangeBounds<Q>,
+    {
+        let range = sorted_key_range(&self.entries, range);
+        Self::from_slice(&self.entries[range])
+    }
 }
 
 impl<'a, T> IntoIterator for &'a Slice<T> {
@@ -188,7 +223,7 @@ impl<T> IntoIterator for Box<Slice<T>> {
     type Item = T;
 
     fn into_iter(self) -> Self::IntoIter {
-        IntoIter::new(self.into_entries())
+        IntoIter::new(allocator::from_std_vec(self.into_entries()))
     }
 }
 
@@ -263,7 +298,7 @@ impl<T> Index<usize> for Slice<T> {
 // Instead, we repeat the implementations for all the core range types.
 macro_rules! impl_index {
     ($($range:ty),*) => {$(
-        impl<T, S> Index<$range> for IndexSet<T, S> {
+        impl<T, S, Idx: IndexType, A: Allocator> Index<$range> for IndexSet<T, S, Idx, A> {
             type Output = Slice<T>;
 
             fn index(&self, range: $range) -> &Self::Output {
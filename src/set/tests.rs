@@ -1,5 +1,6 @@
 use super::*;
 use std::string::String;
+use std::vec::Vec;
 
 #[test]
 fn it_works() {
@@ -381,6 +382,42 @@ fn swap_remove_index() {
     }
 }
 
+#[test]
+fn shift_insert() {
+    let mut set: IndexSet<i32> = (0..5).collect();
+
+    assert!(set.shift_insert(1, 10));
+    assert!(set.iter().eq(&[0, 10, 1, 2, 3, 4]));
+
+    assert!(!set.shift_insert(5, 0));
+    assert!(set.iter().eq(&[10, 1, 2, 3, 4, 0]));
+
+    assert!(set.shift_insert(set.len(), 20));
+    assert!(set.iter().eq(&[10, 1, 2, 3, 4, 0, 20]));
+
+    for (i, x) in set.iter().enumerate() {
+        assert_eq!(set.get_index_of(x), Some(i));
+    }
+}
+
+#[test]
+fn insert_before() {
+    let mut set: IndexSet<i32> = (0..5).collect();
+
+    assert_eq!(set.insert_before(2, 10), (2, true));
+    assert!(set.iter().eq(&[0, 1, 10, 2, 3, 4]));
+
+    assert_eq!(set.insert_before(set.len(), 0), (5, false));
+    assert!(set.iter().eq(&[1, 10, 2, 3, 4, 0]));
+
+    assert_eq!(set.insert_before(0, 4), (0, false));
+    assert!(set.iter().eq(&[4, 1, 10, 2, 3, 0]));
+
+    for (i, x) in set.iter().enumerate() {
+        assert_eq!(set.get_index_of(x), Some(i));
+    }
+}
+
 #[test]
 fn partial_eq_and_eq() {
     let mut set_a = IndexSet::new();
@@ -534,6 +571,42 @@ fn ops() {
     assert_eq!(&set_d - &set_c, &set_d - &set_b);
 }
 
+#[test]
+fn assign_ops() {
+    let sets: [IndexSet<i32>; 5] = [
+        IndexSet::new(),
+        (0..3).collect(),
+        (3..6).collect(),
+        (0..6).collect(),
+        (3..9).rev().collect(),
+    ];
+
+    for a in &sets {
+        for b in &sets {
+            // The in-place results must match the lazy iterators' order too.
+            let check = |set: IndexSet<i32>, iter: &mut dyn Iterator<Item = &i32>| {
+                assert!(set.iter().eq(iter), "{a:?} with {b:?} gave {set:?}");
+                for (i, value) in set.iter().enumerate() {
+                    assert_eq!(set.get_index_of(value), Some(i));
+                }
+            };
+
+            let mut set = a.clone();
+            set &= b;
+            check(set, &mut a.intersection(b));
+            let mut set = a.clone();
+            set |= b;
+            check(set, &mut a.union(b));
+            let mut set = a.clone();
+            set ^= b;
+            check(set, &mut a.symmetric_difference(b));
+            let mut set = a.clone();
+            set -= b;
+            check(set, &mut a.difference(b));
+        }
+    }
+}
+
 #[test]
 #[cfg(feature = "std")]
 fn from_array() {
@@ -694,3 +767,224 @@ fn test_partition_point() {
     assert_eq!(b.partition_point(|&x| x < 7), 2);
     assert_eq!(b.partition_point(|&x| x < 8), 3);
 }
+
+#[test]
+fn entry() {
+    let mut set: IndexSet<i32> = (0..4).collect();
+
+    match set.entry(2) {
+        Entry::Occupied(e) => {
+            assert_eq!(e.index(), 2);
+            assert_eq!(*e.get(), 2);
+        }
+        Entry::Vacant(_) => panic!(),
+    }
+
+    let e = set.entry(10);
+    assert_eq!(e.index(), 4);
+    assert_eq!(*e.get(), 10);
+    let e = e.insert();
+    assert_eq!(e.index(), 4);
+    assert_eq!(*e.get(), 10);
+    assert_eq!(set.entry(10).insert().index(), 4);
+    assert_eq!(set.len(), 5);
+
+    match set.entry(1) {
+        Entry::Occupied(e) => assert_eq!(e.shift_remove(), 1),
+        Entry::Vacant(_) => panic!(),
+    }
+    assert!(set.iter().eq(&[0, 2, 3, 10]));
+
+    match set.entry(0) {
+        Entry::Occupied(e) => assert_eq!(e.swap_remove(), 0),
+        Entry::Vacant(_) => panic!(),
+    }
+    assert!(set.iter().eq(&[10, 2, 3]));
+
+    match set.entry(10) {
+        Entry::Occupied(e) => e.move_index(2),
+        Entry::Vacant(_) => panic!(),
+    }
+    assert!(set.iter().eq(&[2, 3, 10]));
+
+    match set.entry(5) {
+        Entry::Vacant(e) => e.shift_insert(1),
+        Entry::Occupied(_) => panic!(),
+    }
+    assert!(set.iter().eq(&[2, 5, 3, 10]));
+
+    match set.entry(7) {
+        Entry::Vacant(e) => assert_eq!(e.into_value(), 7),
+        Entry::Occupied(_) => panic!(),
+    }
+    assert_eq!(set.len(), 4);
+}
+
+#[test]
+fn raw_entry() {
+    use super::raw_entry_v1::RawEntryMut;
+
+    let mut set: IndexSet<String> = IndexSet::new();
+    set.insert("a".into());
+
+    assert_eq!(
+        set.raw_entry_v1().from_key("a").map(String::as_str),
+        Some("a")
+    );
+    assert_eq!(set.raw_entry_v1().from_key("b"), None);
+
+    let mut calls = 0;
+    for key in ["a", "b", "a", "c", "b"] {
+        set.raw_entry_mut_v1().from_key(key).or_insert_with(|| {
+            calls += 1;
+            key.into()
+        });
+    }
+    assert_eq!(calls, 2);
+    assert!(set.iter().eq(["a", "b", "c"]));
+    // values inserted with the lookup hash must be found again by a normal lookup
+    assert_eq!(set.get_index_of("b"), Some(1));
+    assert_eq!(set.get_index_of("c"), Some(2));
+
+    match set.raw_entry_mut_v1().from_key("d") {
+        RawEntryMut::Vacant(e) => {
+            assert_eq!(e.index(), 3);
+            e.insert("d".into());
+        }
+        RawEntryMut::Occupied(_) => panic!(),
+    }
+    match set.raw_entry_mut_v1().from_key("a") {
+        RawEntryMut::Occupied(e) => {
+            assert_eq!(e.index(), 0);
+            assert_eq!(e.swap_remove(), "a");
+        }
+        RawEntryMut::Vacant(_) => panic!(),
+    }
+    assert!(set.iter().eq(["d", "b", "c"]));
+}
+
+#[test]
+fn hasher_constructors_infer_index_type() {
+    use std::collections::hash_map::RandomState;
+
+    // These must keep inferring the default `usize` index type.
+    let mut set = IndexSet::with_hasher(RandomState::new());
+    set.insert(1);
+    let mut set2 = IndexSet::with_capacity_and_hasher(4, RandomState::new());
+    set2.insert(1);
+    assert_eq!(set, set2);
+
+    let set: IndexSet<u16, _, u8> = IndexSet::with_capacity_and_hasher_idx(4, RandomState::new());
+    assert!(set.capacity() >= 4);
+}
+
+#[test]
+fn compact_index_type() {
+    use std::collections::hash_map::RandomState;
+
+    let mut small: IndexSet<i32, RandomState, u8> = IndexSet::default();
+    small.extend(0..10);
+    let large: IndexSet<i32> = (5..15).collect();
+
+    assert!(small.difference(&large).eq(&[0, 1, 2, 3, 4]));
+    assert!(large.intersection(&small).eq(&[5, 6, 7, 8, 9]));
+    assert_eq!(small.union(&large).count(), 15);
+    assert_eq!(small.symmetric_difference(&large).count(), 10);
+    assert!(!small.is_disjoint(&large));
+
+    let first_half: IndexSet<i32, RandomState, u16> = (0..5).collect();
+    assert!(first_half.is_subset(&small));
+    assert_eq!(&small - &large, first_half);
+
+    small.extend(10..255);
+    assert!(small.try_reserve(1).is_err());
+    assert_eq!(small.insert_full(7), (7, false));
+}
+
+#[test]
+#[cfg(feature = "allocator-api2")]
+fn global_allocator_in() {
+    use allocator_api2::alloc::Global;
+
+    let mut set = IndexSet::with_capacity_in(10, Global);
+    set.extend(0..10);
+    let tail = set.split_off(5);
+    assert!(set.iter().copied().eq(0..5));
+
+    let slice = tail.into_boxed_slice_in();
+    assert_eq!(slice.len(), 5);
+    assert!(slice.into_iter().copied().eq(5..10));
+}
+
+#[test]
+fn range_on_slices() {
+    let mut set: IndexSet<i32> = [9, -3, 4, 0, 12, 7].into_iter().collect();
+    set.sort();
+    let slice = set.get_range(1..).unwrap();
+    assert!(slice.range(0..9).iter().eq(&[0, 4, 7]));
+    assert!(slice.range(..0).is_empty());
+    assert!(slice.range(..).iter().eq(set.range(-2..)));
+}
+
+#[test]
+fn hash_ignores_order() {
+    use crate::Ordered;
+    use std::collections::HashSet;
+
+    let sets: [IndexSet<u32>; 3] = [
+        (0..10).collect(),
+        (0..10).rev().collect(),
+        (0..10).map(|i| (i * 7) % 10).collect(),
+    ];
+    let unordered: HashSet<&IndexSet<u32>> = sets.iter().collect();
+    assert_eq!(unordered.len(), 1);
+    let ordered: HashSet<Ordered<&IndexSet<u32>>> = sets.iter().map(Ordered).collect();
+    assert_eq!(ordered.len(), 3);
+}
+
+#[test]
+fn mutable_values() {
+    use crate::set::MutableValues;
+
+    #[derive(Debug)]
+    struct Tagged(u32, u32);
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+    impl Eq for Tagged {}
+    impl core::hash::Hash for Tagged {
+        fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+            self.0.hash(state);
+        }
+    }
+
+    let mut set: IndexSet<Tagged> = (0..6).map(|i| Tagged(i, 0)).collect();
+
+    let (index, value) = set.get_full_mut2(&Tagged(3, 0)).unwrap();
+    assert_eq!(index, 3);
+    value.1 = 30;
+    set.get_index_mut2(1).unwrap().1 = 10;
+    assert!(set.get_index_mut2(6).is_none());
+    assert!(set.get_full_mut2(&Tagged(6, 0)).is_none());
+
+    set.retain2(|value| {
+        value.1 += 1;
+        value.0 % 2 == 1
+    });
+    let tags: Vec<(u32, u32)> = set.iter().map(|t| (t.0, t.1)).collect();
+    assert_eq!(tags, [(1, 11), (3, 31), (5, 1)]);
+    assert_eq!(set.get(&Tagged(3, 0)).unwrap().1, 31);
+}
+
+#[test]
+fn sort_range_by() {
+    let mut set: IndexSet<u32> = (0..10).rev().collect();
+    set.sort_range_by(..3, |a, b| a.cmp(b));
+    assert!(set.iter().eq(&[7, 8, 9, 6, 5, 4, 3, 2, 1, 0]));
+    assert_eq!(set.get_index_of(&9), Some(2));
+    set.sort_range_by(3.., |a, b| a.cmp(b));
+    assert!(set.iter().eq(&[7, 8, 9, 0, 1, 2, 3, 4, 5, 6]));
+    assert_eq!(set.get_index_of(&0), Some(3));
+}
@@ -1,5 +1,9 @@
+use core::borrow::Borrow;
+use core::hash::{Hash, Hasher};
 use core::ops::{Bound, Range, RangeBounds};
 
+use crate::Bucket;
+
 pub(crate) fn third<A, B, C>(t: (A, B, C)) -> C {
     t.2
 }
@@ -51,3 +55,55 @@ where
     }
     Some(start..end)
 }
+
+/// Find the index range of the `entries`, sorted by key, whose keys fall within `range`.
+///
+/// An empty or inverted key range yields an empty index range.
+pub(crate) fn sorted_key_range<K, V, Q, R>(entries: &[Bucket<K, V>], range: R) -> Range<usize>
+where
+    K: Borrow<Q>,
+    Q: ?Sized + Ord,
+    R: RangeBounds<Q>,
+{
+    let start = match range.start_bound() {
+        Bound::Unbounded => 0,
+        Bound::Included(q) => entries.partition_point(|b| b.key.borrow() < q),
+        Bound::Excluded(q) => entries.partition_point(|b| b.key.borrow() <= q),
+    };
+    let end = match range.end_bound() {
+        Bound::Unbounded => entries.len(),
+        Bound::Included(q) => entries.partition_point(|b| b.key.borrow() <= q),
+        Bound::Excluded(q) => entries.partition_point(|b| b.key.borrow() < q),
+    };
+    start..Ord::max(start, end)
+}
+
+/// Hash all of the items into `state`, in a way that doesn't depend on their order.
+///
+/// Equal collections may use different hashers, or even different hasher
+/// types, so the items can't be hashed with their own `BuildHasher`. Instead,
+/// each item is hashed separately with SipHash, keyed from the current
+/// `state`, and the item hashes are combined with a wrapping sum. Since the
+/// keys depend on the caller's hasher, the item hashes are as unpredictable as
+/// that hasher, so collisions can't be found without knowing its keys either.
+pub(crate) fn hash_unordered<I, H>(items: I, state: &mut H)
+where
+    I: IntoIterator,
+    I::Item: Hash,
+    H: Hasher,
+{
+    let k0 = state.finish();
+    state.write_u8(0xff);
+    let k1 = state.finish();
+
+    let sum = items
+        .into_iter()
+        .map(|item| {
+            #[allow(deprecated)]
+            let mut hasher = core::hash::SipHasher::new_with_keys(k0, k1);
+            item.hash(&mut hasher);
+            hasher.finish()
+        })
+        .fold(0, u64::wrapping_add);
+    state.write_u64(sum);
+}
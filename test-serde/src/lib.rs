@@ -2,8 +2,14 @@
 
 use fnv::FnvBuildHasher;
 use indexmap::{indexmap, indexset, IndexMap, IndexSet};
+use serde::de::value::{Error, MapDeserializer, SeqDeserializer};
+use serde::de::DeserializeSeed;
 use serde::{Deserialize, Serialize};
-use serde_test::{assert_tokens, Token};
+use serde_test::{
+    assert_de_tokens, assert_de_tokens_error, assert_ser_tokens, assert_tokens, Token,
+};
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
 
 #[test]
 fn test_serde_map() {
@@ -113,3 +119,490 @@ fn test_serde_seq_map() {
         ],
     );
 }
+
+#[test]
+fn test_serde_strict_map() {
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(transparent)]
+    struct StrictIndexMap {
+        #[serde(with = "indexmap::map::serde_strict")]
+        map: IndexMap<i32, i32>,
+    }
+
+    impl PartialEq for StrictIndexMap {
+        fn eq(&self, other: &Self) -> bool {
+            // explicitly compare items in order
+            self.map.iter().eq(&other.map)
+        }
+    }
+
+    let map = indexmap! { 3 => 4, 1 => 2 };
+    assert_tokens(
+        &StrictIndexMap { map },
+        &[
+            Token::Map { len: Some(2) },
+            Token::I32(3),
+            Token::I32(4),
+            Token::I32(1),
+            Token::I32(2),
+            Token::MapEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<StrictIndexMap>(
+        &[
+            Token::Map { len: Some(3) },
+            Token::I32(3),
+            Token::I32(4),
+            Token::I32(1),
+            Token::I32(2),
+            Token::I32(3),
+            Token::I32(5),
+            Token::MapEnd,
+        ],
+        "duplicate map key 3",
+    );
+}
+
+#[test]
+fn test_serde_strict_set() {
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(transparent)]
+    struct StrictIndexSet {
+        #[serde(with = "indexmap::set::serde_strict")]
+        set: IndexSet<String>,
+    }
+
+    impl PartialEq for StrictIndexSet {
+        fn eq(&self, other: &Self) -> bool {
+            // explicitly compare items in order
+            self.set.iter().eq(&other.set)
+        }
+    }
+
+    let set = indexset! { String::from("b"), String::from("a") };
+    assert_tokens(
+        &StrictIndexSet { set },
+        &[
+            Token::Seq { len: Some(2) },
+            Token::Str("b"),
+            Token::Str("a"),
+            Token::SeqEnd,
+        ],
+    );
+
+    assert_de_tokens_error::<StrictIndexSet>(
+        &[
+            Token::Seq { len: Some(3) },
+            Token::Str("b"),
+            Token::Str("a"),
+            Token::Str("b"),
+            Token::SeqEnd,
+        ],
+        r#"duplicate set value "b""#,
+    );
+}
+
+#[test]
+fn test_serde_borrowed_keys() {
+    // `&str` keys borrow through the default implementation.
+    let map: IndexMap<&str, i32> = indexmap! { "a" => 1, "b" => 2 };
+    assert_de_tokens(
+        &map,
+        &[
+            Token::Map { len: Some(2) },
+            Token::BorrowedStr("a"),
+            Token::I32(1),
+            Token::BorrowedStr("b"),
+            Token::I32(2),
+            Token::MapEnd,
+        ],
+    );
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(transparent)]
+    struct BorrowIndexMap<'a> {
+        #[serde(borrow, with = "indexmap::map::serde_borrow")]
+        map: IndexMap<Cow<'a, str>, i32>,
+    }
+
+    impl PartialEq for BorrowIndexMap<'_> {
+        fn eq(&self, other: &Self) -> bool {
+            // explicitly compare items in order, and whether keys are borrowed
+            let is_borrowed = |key: &Cow<'_, str>| matches!(key, Cow::Borrowed(_));
+            self.map.iter().eq(&other.map)
+                && self
+                    .map
+                    .keys()
+                    .map(is_borrowed)
+                    .eq(other.map.keys().map(is_borrowed))
+        }
+    }
+
+    let map = indexmap! { Cow::Borrowed("a") => 1, Cow::Owned(String::from("b")) => 2 };
+    assert_de_tokens(
+        &BorrowIndexMap { map: map.clone() },
+        &[
+            Token::Map { len: Some(2) },
+            Token::BorrowedStr("a"),
+            Token::I32(1),
+            Token::Str("b"),
+            Token::I32(2),
+            Token::MapEnd,
+        ],
+    );
+    assert_tokens(
+        &BorrowIndexMap { map },
+        &[
+            Token::Map { len: Some(2) },
+            Token::BorrowedStr("a"),
+            Token::I32(1),
+            Token::String("b"),
+            Token::I32(2),
+            Token::MapEnd,
+        ],
+    );
+
+    // An owned key from the format is moved into the map, not copied.
+    let key = String::from("c");
+    let ptr = key.as_ptr();
+    let de = MapDeserializer::<_, Error>::new([(key, 3)].into_iter());
+    let map: IndexMap<Cow<'_, str>, i32> = indexmap::map::serde_borrow::deserialize(de).unwrap();
+    assert!(matches!(map.get_index(0), Some((Cow::Owned(key), 3)) if key.as_ptr() == ptr));
+}
+
+#[test]
+fn test_serde_seed_existing() {
+    let mut map: IndexMap<i32, i32, RandomState> = IndexMap::with_hasher(RandomState::new());
+    map.insert(3, 0);
+    let de = MapDeserializer::<_, Error>::new([(1, 2), (3, 4)].into_iter());
+    map.deserialize(de).unwrap();
+    assert!(map.into_iter().eq([(3, 4), (1, 2)]));
+
+    let mut set: IndexSet<i32, RandomState> = IndexSet::with_hasher(RandomState::new());
+    set.insert(2);
+    let de = SeqDeserializer::<_, Error>::new([1, 2].into_iter());
+    set.deserialize(de).unwrap();
+    assert!(set.into_iter().eq([2, 1]));
+}
+
+/// A hasher that needs a key, so it can't implement `Default`.
+#[derive(Clone)]
+struct KeyedState(u64);
+
+impl std::hash::BuildHasher for KeyedState {
+    type Hasher = std::collections::hash_map::DefaultHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        let mut hasher = Self::Hasher::new();
+        std::hash::Hasher::write_u64(&mut hasher, self.0);
+        hasher
+    }
+}
+
+/// An iterator that claims to be much longer than it is.
+struct Lying<I>(I);
+
+impl<I: Iterator> Iterator for Lying<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (1 << 40, Some(1 << 40))
+    }
+}
+
+#[test]
+fn test_serde_seeds() {
+    use indexmap::map::{serde_seq, IndexMapSeed};
+    use indexmap::set::IndexSetSeed;
+
+    let de = MapDeserializer::<_, Error>::new(Lying([(1, 2), (3, 4)].into_iter()));
+    let map: IndexMap<i32, i32, KeyedState> = IndexMapSeed::new(KeyedState(7))
+        .with_capacity_limit(16)
+        .deserialize(de)
+        .unwrap();
+    assert!(map.iter().eq([(&1, &2), (&3, &4)]));
+    assert_eq!(map.hasher().0, 7);
+
+    let de = SeqDeserializer::<_, Error>::new(Lying([vec![3, 4], vec![1, 2]].into_iter()));
+    let map: IndexMap<i32, i32, KeyedState> = serde_seq::IndexMapSeed::new(KeyedState(8))
+        .with_capacity_limit(16)
+        .deserialize(de)
+        .unwrap();
+    assert!(map.iter().eq([(&3, &4), (&1, &2)]));
+    assert_eq!(map.hasher().0, 8);
+
+    let de = SeqDeserializer::<_, Error>::new(Lying([2, 1, 2].into_iter()));
+    let set: IndexSet<i32, KeyedState> = IndexSetSeed::new(KeyedState(9))
+        .with_capacity_limit(16)
+        .deserialize(de)
+        .unwrap();
+    assert!(set.iter().eq([&2, &1]));
+    assert_eq!(set.hasher().0, 9);
+}
+
+#[test]
+fn test_serde_bounded() {
+    use indexmap::map::{serde_seq, IndexMapSeed};
+    use indexmap::set::IndexSetSeed;
+
+    // A huge size hint only preallocates a bounded capacity by default.
+    let de = MapDeserializer::<_, Error>::new(Lying([(1, 2), (3, 4)].into_iter()));
+    let map = IndexMap::<i32, i32>::deserialize(de).unwrap();
+    assert!(map.iter().eq([(&1, &2), (&3, &4)]));
+    let de = SeqDeserializer::<_, Error>::new(Lying([2, 1].into_iter()));
+    let set = IndexSet::<i32>::deserialize(de).unwrap();
+    assert!(set.iter().eq([&2, &1]));
+
+    // A size hint over the limit fails up front.
+    let de = MapDeserializer::<_, Error>::new(Lying([(1, 2)].into_iter()));
+    let error = IndexMapSeed::<i32, i32, _>::new(KeyedState(0))
+        .with_max_len(100)
+        .deserialize(de)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid length 1099511627776, expected at most 100 entries"
+    );
+
+    // Otherwise the map fails once it grows past the limit, where duplicate
+    // keys don't count. (Filtering hides the size hint.)
+    let entries = [(1, 2), (3, 4), (1, 5), (6, 7)];
+    let de = MapDeserializer::<_, Error>::new(entries.into_iter().filter(|_| true));
+    let error = IndexMapSeed::<i32, i32, _>::new(KeyedState(0))
+        .with_max_len(2)
+        .deserialize(de)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid length 3, expected at most 2 entries"
+    );
+    let de = MapDeserializer::<_, Error>::new(entries[..3].iter().copied().filter(|_| true));
+    let map = IndexMapSeed::<i32, i32, _>::new(KeyedState(0))
+        .with_max_len(2)
+        .deserialize(de)
+        .unwrap();
+    assert!(map.iter().eq([(&1, &5), (&3, &4)]));
+
+    let de =
+        SeqDeserializer::<_, Error>::new([vec![1, 2], vec![3, 4]].into_iter().filter(|_| true));
+    let error = serde_seq::IndexMapSeed::<i32, i32, _>::new(KeyedState(0))
+        .with_max_len(1)
+        .deserialize(de)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid length 2, expected at most 1 entries"
+    );
+
+    let de = SeqDeserializer::<_, Error>::new([1, 2, 1, 3].into_iter().filter(|_| true));
+    let error = IndexSetSeed::<i32, _>::new(KeyedState(0))
+        .with_max_len(2)
+        .deserialize(de)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid length 3, expected at most 2 entries"
+    );
+}
+
+#[test]
+fn test_serde_strict_seeds() {
+    use indexmap::{map, set};
+
+    let de = MapDeserializer::<_, Error>::new(Lying([(1, 2), (3, 4)].into_iter()));
+    let map: IndexMap<i32, i32, KeyedState> = map::serde_strict::IndexMapSeed::new(KeyedState(7))
+        .with_capacity_limit(16)
+        .deserialize(de)
+        .unwrap();
+    assert!(map.iter().eq([(&1, &2), (&3, &4)]));
+    assert_eq!(map.hasher().0, 7);
+
+    let de = MapDeserializer::<_, Error>::new([(1, 2), (3, 4), (1, 5)].into_iter());
+    let error = map::serde_strict::IndexMapSeed::<i32, i32, _>::new(KeyedState(0))
+        .deserialize(de)
+        .unwrap_err();
+    assert_eq!(error.to_string(), "duplicate map key 1");
+
+    let de = MapDeserializer::<_, Error>::new([(1, 2), (3, 4), (5, 6)].into_iter());
+    let error = map::serde_strict::IndexMapSeed::<i32, i32, _>::new(KeyedState(0))
+        .with_max_len(2)
+        .deserialize(de)
+        .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid length 3, expected at most 2 entries"
+    );
+
+    let de = SeqDeserializer::<_, Error>::new([2, 1, 2].into_iter());
+    let error = set::serde_strict::IndexSetSeed::<i32, _>::new(KeyedState(0))
+        .deserialize(de)
+        .unwrap_err();
+    assert_eq!(error.to_string(), "duplicate set value 2");
+
+    let de = SeqDeserializer::<_, Error>::new(Lying([2, 1].into_iter()));
+    let set: IndexSet<i32, KeyedState> = set::serde_strict::IndexSetSeed::new(KeyedState(9))
+        .with_capacity_limit(16)
+        .deserialize(de)
+        .unwrap();
+    assert!(set.iter().eq([&2, &1]));
+    assert_eq!(set.hasher().0, 9);
+}
+
+#[test]
+fn test_serde_index_overflow() {
+    use indexmap::map::serde_borrow;
+
+    // Running out of `u8` indices is an error on every path, not a panic.
+    let mut map: IndexMap<i32, i32, RandomState, u8> = IndexMap::default();
+    let de = MapDeserializer::<_, Error>::new((0..300).map(|i| (i, i)));
+    assert!(map.deserialize(de).is_err());
+
+    let mut set: IndexSet<i32, RandomState, u8> = IndexSet::default();
+    let de = SeqDeserializer::<_, Error>::new(0..300);
+    assert!(set.deserialize(de).is_err());
+
+    let de = MapDeserializer::<_, Error>::new((0..300).map(|i| (i.to_string(), i)));
+    let result: Result<IndexMap<Cow<'_, str>, i32, RandomState, u8>, _> =
+        serde_borrow::deserialize(de);
+    assert!(result.is_err());
+
+    let de = MapDeserializer::<_, Error>::new((0..300).map(|i| (i, i)));
+    let result: Result<IndexMap<i32, i32, RandomState, u8>, _> =
+        indexmap::map::serde_strict::deserialize(de);
+    assert!(result.is_err());
+
+    let de = SeqDeserializer::<_, Error>::new(0..300);
+    let result: Result<IndexSet<i32, RandomState, u8>, _> =
+        indexmap::set::serde_strict::deserialize(de);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_serde_hashed_map() {
+    use std::hash::{BuildHasher, Hash};
+
+    #[derive(Debug, Deserialize, Serialize)]
+    #[serde(transparent)]
+    struct HashedIndexMap {
+        #[serde(with = "indexmap::map::serde_hashed")]
+        map: IndexMap<i32, i32, FnvBuildHasher>,
+    }
+
+    impl PartialEq for HashedIndexMap {
+        fn eq(&self, other: &Self) -> bool {
+            // explicitly compare items in order, and look them up by key
+            let found = |map: &IndexMap<i32, i32, FnvBuildHasher>| {
+                (map.keys().enumerate()).all(|(i, key)| map.get_index_of(key) == Some(i))
+            };
+            self.map.iter().eq(&other.map) && found(&self.map) && found(&other.map)
+        }
+    }
+
+    fn fnv_hash<T: Hash + ?Sized>(value: &T) -> u64 {
+        FnvBuildHasher::default().hash_one(value) as usize as u64
+    }
+
+    fn tokens(fingerprint: u64, entries: &[(u64, i32, i32)]) -> Vec<Token> {
+        let mut tokens = vec![
+            Token::Tuple { len: 2 },
+            Token::U64(fingerprint),
+            Token::Seq {
+                len: Some(entries.len()),
+            },
+        ];
+        for &(hash, key, value) in entries {
+            tokens.extend([
+                Token::Tuple { len: 3 },
+                Token::U64(hash),
+                Token::I32(key),
+                Token::I32(value),
+                Token::TupleEnd,
+            ]);
+        }
+        tokens.extend([Token::SeqEnd, Token::TupleEnd]);
+        tokens
+    }
+
+    let mut map = IndexMap::default();
+    map.insert(3, 4);
+    map.insert(1, 2);
+    let map = HashedIndexMap { map };
+    let fingerprint = fnv_hash("indexmap::map::serde_hashed");
+
+    // With a matching fingerprint, the table is built from the stored hashes.
+    assert_tokens(
+        &map,
+        &tokens(fingerprint, &[(fnv_hash(&3), 3, 4), (fnv_hash(&1), 1, 2)]),
+    );
+
+    // Otherwise, the stored hashes are ignored and the keys are hashed again.
+    assert_de_tokens(&map, &tokens(!fingerprint, &[(0, 3, 4), (0, 1, 2)]));
+}
+
+#[test]
+fn test_serde_slices() {
+    use indexmap::{map, set};
+
+    let map = indexmap! { 3 => 4, 1 => 2 };
+    let set = indexset! { 3, 1 };
+
+    // The slices themselves use the ordered sequence format.
+    let map_tokens = [
+        Token::Seq { len: Some(2) },
+        Token::Tuple { len: 2 },
+        Token::I32(3),
+        Token::I32(4),
+        Token::TupleEnd,
+        Token::Tuple { len: 2 },
+        Token::I32(1),
+        Token::I32(2),
+        Token::TupleEnd,
+        Token::SeqEnd,
+    ];
+    assert_tokens(&map.clone().into_boxed_slice(), &map_tokens);
+    assert_ser_tokens(&map.as_slice(), &map_tokens);
+
+    let set_tokens = [
+        Token::Seq { len: Some(2) },
+        Token::I32(3),
+        Token::I32(1),
+        Token::SeqEnd,
+    ];
+    assert_tokens(&set.clone().into_boxed_slice(), &set_tokens);
+    assert_ser_tokens(&set.as_slice(), &set_tokens);
+
+    // Duplicates are kept, since they are not hashed.
+    let de = SeqDeserializer::<_, Error>::new([1, 2, 1].into_iter());
+    let slice = Box::<set::Slice<i32>>::deserialize(de).unwrap();
+    assert!(slice.iter().eq(&[1, 2, 1]));
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize)]
+    #[serde(transparent)]
+    struct MapSlice {
+        #[serde(with = "indexmap::map::serde_slice")]
+        slice: Box<map::Slice<i32, i32>>,
+    }
+
+    assert_tokens(
+        &MapSlice {
+            slice: map.into_boxed_slice(),
+        },
+        &[
+            Token::Map { len: Some(2) },
+            Token::I32(3),
+            Token::I32(4),
+            Token::I32(1),
+            Token::I32(2),
+            Token::MapEnd,
+        ],
+    );
+
+    let de = MapDeserializer::<_, Error>::new([(1, 2), (3, 4), (1, 5)].into_iter());
+    let slice = indexmap::map::serde_slice::deserialize::<_, i32, i32>(de).unwrap();
+    assert!(slice.iter().eq([(&1, &2), (&3, &4), (&1, &5)]));
+}
@@ -12,14 +12,17 @@ type FnvBuilder = BuildHasherDefault<FnvHasher>;
 type IndexMapFnv<K, V> = IndexMap<K, V, FnvBuilder>;
 
 use std::cmp::min;
-use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::ops::Bound;
 use std::ops::Deref;
 
 use indexmap::map::Entry as OEntry;
+use indexmap::map::IndexMultiMap;
+use indexmap::map::TombstoneMap;
+use indexmap::persistent::IndexMap as PersistentMap;
 use std::collections::hash_map::Entry as HEntry;
 
 fn set<'a, T: 'a, I>(iter: I) -> HashSet<T>
@@ -196,6 +199,119 @@ quickcheck_limit! {
             elements.iter().all(|k| map.get(k).is_some())
     }
 
+    fn tombstone_remove(ops: Vec<(bool, u8)>, ratio: u8) -> bool {
+        let mut map = IndexMap::new();
+        let mut tomb = TombstoneMap::new();
+        tomb.set_max_tombstone_ratio(f64::from(ratio) / 255.0);
+        for &(insert, key) in &ops {
+            if insert {
+                map.shift_remove(&key);
+                map.insert(key, ());
+                tomb.remove(&key);
+                tomb.insert(key, ());
+            } else {
+                assert_eq!(map.shift_remove(&key), tomb.remove(&key));
+            }
+        }
+        tomb.len() == map.len()
+            && tomb.iter().eq(map.iter())
+            && tomb.iter().rev().eq(map.iter().rev())
+            && tomb.first() == map.first()
+            && map.keys().all(|k| tomb.contains_key(k))
+    }
+
+    fn multimap_ops(ops: Vec<(u8, u8)>) -> bool {
+        let mut map = IndexMultiMap::new();
+        let mut pairs: Vec<(u8, usize)> = Vec::new();
+        for (i, &(op, key)) in ops.iter().enumerate() {
+            let key = key % 8;
+            match op % 8 {
+                0 => assert_eq!(map.remove_all(&key), {
+                    let (removed, kept): (Vec<_>, _) = pairs.drain(..).partition(|p| p.0 == key);
+                    pairs = kept;
+                    Vec::from_iter(removed.into_iter().map(|p| p.1))
+                }),
+                1 => assert_eq!(map.pop(), pairs.pop()),
+                2 if !pairs.is_empty() => {
+                    let index = usize::from(key) % pairs.len();
+                    assert_eq!(map.shift_remove_index(index), Some(pairs.remove(index)));
+                }
+                3 => {
+                    map.retain(|_, v| *v % 3 != 0);
+                    pairs.retain(|p| p.1 % 3 != 0);
+                }
+                _ => {
+                    map.insert_append(key, i);
+                    pairs.push((key, i));
+                }
+            }
+        }
+        map.iter().map(|(&k, &v)| (k, v)).eq(pairs.iter().copied())
+            && (0..8).all(|key| {
+                let values = pairs.iter().filter(|p| p.0 == key).map(|p| p.1);
+                map.get_all(&key).copied().eq(values.clone())
+                    && map.get(&key).copied() == values.clone().next()
+                    && map.count(&key) == values.count()
+            })
+    }
+
+    fn insert_sorted(insert: Vec<(u8, u16)>) -> bool {
+        let mut map = IndexMap::new();
+        let mut btree = BTreeMap::new();
+        for &(key, value) in &insert {
+            let (i, old) = map.insert_sorted(key, value);
+            assert_eq!(old, btree.insert(key, value));
+            assert_eq!(map.get_index_of(&key), Some(i));
+        }
+        map.iter().eq(btree.iter())
+    }
+
+    fn range_keys(insert: Vec<u8>, start: Bound<u8>, end: Bound<u8>) -> TestResult {
+        if let (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e)) =
+            (start, end)
+        {
+            // `BTreeSet::range` panics on these, where `range` is just empty.
+            if s > e || (s == e && (start, end) != (Bound::Included(s), Bound::Included(e))) {
+                return TestResult::discard();
+            }
+        }
+        let mut set: IndexSet<u8> = insert.iter().copied().collect();
+        set.sort();
+        let btree: BTreeSet<u8> = insert.into_iter().collect();
+        TestResult::from_bool(set.range((start, end)).iter().eq(btree.range((start, end))))
+    }
+
+    fn persistent_snapshots(ops: Vec<(u8, u8)>) -> bool {
+        let mut map = IndexMap::new();
+        let mut pmap = PersistentMap::new();
+        let mut snapshots = Vec::new();
+        for &(op, key) in &ops {
+            match op % 4 {
+                0 | 1 => assert_eq!(map.insert(key, op), pmap.insert(key, op)),
+                2 if key % 2 == 0 => assert_eq!(map.swap_remove(&key), pmap.swap_remove(&key)),
+                2 => assert_eq!(map.shift_remove(&key), pmap.shift_remove(&key)),
+                _ => snapshots.push((map.clone(), pmap.clone())),
+            }
+        }
+        snapshots.push((map, pmap));
+        snapshots.iter().all(|(map, pmap)| {
+            pmap.iter().eq(map.iter())
+                && map.keys().enumerate().all(|(i, k)| pmap.get_index_of(k) == Some(i))
+        })
+    }
+
+    fn diff_replays(from: Vec<(u8, bool)>, to: Vec<(u8, bool)>) -> bool {
+        let from: IndexMap<_, _> = from.into_iter().collect();
+        let to: IndexMap<_, _> = to.into_iter().collect();
+        let diff: Vec<_> = from.diff(&to).collect();
+
+        let mut replica = from.clone();
+        replica.apply_diff(diff.iter().copied());
+        let mut reversed = from.clone();
+        reversed.apply_diff(diff.iter().rev().copied());
+        replica.iter().eq(&to) && reversed.iter().eq(&to) && (diff.is_empty() == to.iter().eq(&from))
+    }
+
     fn indexing(insert: Vec<u8>) -> bool {
         let mut map: IndexMap<_, _> = insert.into_iter().map(|x| (x, x)).collect();
         let set: IndexSet<_> = map.keys().copied().collect();
@@ -458,6 +574,98 @@ quickcheck_limit! {
         assert_sorted_by_key(map, |t| std::cmp::Reverse(t.0));
     }
 
+    fn sort_range(keyvals: Large<Vec<(i8, i8)>>, a: usize, b: usize) -> () {
+        let mut map: IndexMap<_, _> = IndexMap::from_iter(keyvals.to_vec());
+        let (a, b) = (a % (map.len() + 1), b % (map.len() + 1));
+        let range = a.min(b)..a.max(b);
+        let mut answer = Vec::from_iter(map.clone());
+        answer[range.clone()].sort_by_key(|t| t.1);
+        map.sort_range_by(range, |_, v1, _, v2| Ord::cmp(v1, v2));
+        for (i, &(key, _)) in answer.iter().enumerate() {
+            assert_eq!(map.get_index_of(&key), Some(i));
+        }
+        assert_eq!(answer, Vec::from_iter(map));
+    }
+
+    fn range_reorder(keys: Large<Vec<i8>>, ops: Vec<(u8, usize, usize, usize)>) -> () {
+        let mut map: IndexMap<_, _> = keys.iter().map(|&k| (k, ())).collect();
+        let mut answer = Vec::from_iter(map.keys().copied());
+        let len = answer.len();
+        for (op, a, b, c) in ops {
+            let (a, b) = (a % (len + 1), b % (len + 1));
+            let range = a.min(b)..a.max(b);
+            let n = c % (range.len() + 1);
+            match op % 4 {
+                0 => {
+                    map.rotate_left(range.clone(), n);
+                    answer[range].rotate_left(n);
+                }
+                1 => {
+                    map.rotate_right(range.clone(), n);
+                    answer[range].rotate_right(n);
+                }
+                2 => {
+                    map.reverse_range(range.clone());
+                    answer[range].reverse();
+                }
+                _ => {
+                    let to = c % (len - range.len() + 1);
+                    let block: Vec<_> = answer.drain(range.clone()).collect();
+                    answer.splice(to..to, block);
+                    map.move_range(range, to);
+                }
+            }
+        }
+        itertools::assert_equal(map.keys(), &answer);
+        for (i, key) in answer.iter().enumerate() {
+            assert_eq!(map.get_index_of(key), Some(i));
+        }
+    }
+
+    fn try_extend(base: Vec<(i8, i8)>, keyvals: Vec<(i8, i8)>) -> () {
+        let mut map: IndexMap<_, _> = IndexMap::from_iter(base);
+        let mut answer = Vec::from_iter(map.clone());
+        let expected = keyvals.iter().find_map(|&(k, v)| {
+            match answer.iter().position(|&(k2, _)| k == k2) {
+                Some(i) => Some((i, k, v)),
+                None => {
+                    answer.push((k, v));
+                    None
+                }
+            }
+        });
+        let result = map.try_extend(keyvals);
+        let error = result.err().map(|e| (e.index(), *e.key(), *e.value()));
+        assert_eq!(error, expected);
+        assert_eq!(answer, Vec::from_iter(map));
+    }
+
+    fn extend_with(keyvals: Vec<(i8, i8)>) -> () {
+        let mut map: IndexMap<_, i16> = IndexMap::new();
+        map.extend_with(keyvals.iter().map(|&(k, v)| (k, v.into())), |old, new| *old += new);
+        let mut answer: Vec<(i8, i16)> = Vec::new();
+        for (k, v) in keyvals {
+            match answer.iter_mut().find(|(k2, _)| *k2 == k) {
+                Some((_, total)) => *total += i16::from(v),
+                None => answer.push((k, v.into())),
+            }
+        }
+        assert_eq!(answer, Vec::from_iter(map));
+    }
+
+    fn slice_sort_values(keyvals: Large<Vec<(i8, i8)>>) -> () {
+        let mut map: IndexMap<_, _> = IndexMap::from_iter(keyvals.to_vec());
+        let keys = Vec::from_iter(map.keys().copied());
+        let mut values = Vec::from_iter(map.values().copied());
+        values.sort_by_key(|&v| std::cmp::Reverse(v));
+        map.as_mut_slice().sort_values_by(|v1, v2| v2.cmp(v1));
+        itertools::assert_equal(map.keys(), &keys);
+        itertools::assert_equal(map.values(), &values);
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(map.get_index_of(key), Some(i));
+        }
+    }
+
     fn reverse(keyvals: Large<Vec<(i8, i8)>>) -> () {
         let mut map: IndexMap<_, _> = IndexMap::from_iter(keyvals.to_vec());
 